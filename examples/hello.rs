@@ -1,7 +1,7 @@
 use lokinit::prelude::*;
 use lokinit::window::ScreenMode;
 
-fn main() {
+fn main() -> Result<(), LokinitError> {
     // hehe
     lok::init();
 
@@ -13,8 +13,7 @@ fn main() {
             .size(600, 400)
             .position(200, 400)
             .resizable(true),
-    )
-    .unwrap();
+    )?;
 
     lok::create_window(
         WindowBuilder::new()
@@ -23,19 +22,18 @@ fn main() {
             .size(400, 600)
             .position(400, 200)
             .resizable(true),
-    )
-    .unwrap();
+    )?;
 
-    while let Some(event) = lok::poll_event() {
+    lok::run(|event, _control| {
         let win = event.window;
 
         match event.kind {
             EventKind::Mouse(event) => match event {
-                MouseEvent::ButtonPress(btn, x, y) => {
-                    println!("[{win:?}] Mouse button {btn:?} pressed at ({x}, {y})")
+                MouseEvent::ButtonPress(btn, x, y, mods) => {
+                    println!("[{win:?}] Mouse button {btn:?} pressed at ({x}, {y}) with {mods:?}")
                 }
-                MouseEvent::ButtonRelease(btn, x, y) => {
-                    println!("[{win:?}] Mouse button {btn:?} released at ({x}, {y})")
+                MouseEvent::ButtonRelease(btn, x, y, mods) => {
+                    println!("[{win:?}] Mouse button {btn:?} released at ({x}, {y}) with {mods:?}")
                 }
                 MouseEvent::CursorIn(x, y) => {
                     println!("[{win:?}] Cursor entered window at ({x}, {y})")
@@ -48,27 +46,44 @@ fn main() {
                 _ => {}
             },
             EventKind::Keyboard(event) => match event {
-                KeyboardEvent::KeyPress(keycode) => {
-                    println!("[{win:?}] Key {keycode:?} pressed");
+                KeyboardEvent::KeyPress(keycode, scancode, mods) => {
+                    println!(
+                        "[{win:?}] Key {keycode:?} ({scancode:?}, {:?} on QWERTY) pressed with {mods:?}",
+                        scancode.qwerty_key()
+                    );
 
                     match keycode {
                         KeyCode::F => {
                             println!("[{win:?}] FULLSCREEN");
-                            lok::set_screen_mode(win, ScreenMode::BorderlessFullscreen);
+                            if let Err(why) =
+                                lok::set_screen_mode(win, ScreenMode::BorderlessFullscreen)
+                            {
+                                println!("[{win:?}] Couldn't go fullscreen: {why}");
+                            }
                         }
                         KeyCode::W => {
                             println!("[{win:?}] WINDOWED");
-                            lok::set_screen_mode(win, ScreenMode::Windowed);
+                            if let Err(why) = lok::set_screen_mode(win, ScreenMode::Windowed) {
+                                println!("[{win:?}] Couldn't go windowed: {why}");
+                            }
                         }
                         _ => (),
                     }
                 }
-                KeyboardEvent::KeyRelease(keycode) => {
+                KeyboardEvent::KeyRelease(keycode, _, _) => {
                     println!("[{win:?}] Key {keycode:?} released")
                 }
-                KeyboardEvent::KeyRepeat(keycode) => println!("[{win:?}] Key {keycode:?} repeated"),
+                KeyboardEvent::KeyRepeat(keycode, _, _) => {
+                    println!("[{win:?}] Key {keycode:?} repeated")
+                }
                 KeyboardEvent::ImeCommit(commit) => println!("[{win:?}] IME commit -> {commit:?}"),
+                KeyboardEvent::ImePreedit { text, cursor_range } => {
+                    println!("[{win:?}] IME preedit -> {text:?} (cursor: {cursor_range:?})")
+                }
             },
+            EventKind::ModifiersChanged(mods) => {
+                println!("[{win:?}] Modifiers changed to {mods:?}")
+            }
             EventKind::Resized(width, height) => {
                 println!("[{win:?}] Window resized to ({width}, {height})")
             }
@@ -76,8 +91,9 @@ fn main() {
                 println!("[{win:?}] Window moved to ({x}, {y})")
             }
             EventKind::CloseRequested => {
-                lok::close_window(win);
-                println!("[{win:?}] Closed upon request");
+                if lok::close_window(win).is_ok() {
+                    println!("[{win:?}] Closed upon request");
+                }
             }
             EventKind::Destroyed => {
                 println!("[{win:?}] Destroyed")
@@ -86,6 +102,8 @@ fn main() {
             EventKind::FocusOut => println!("[{win:?}] Window lost focus"),
             _ => {}
         }
-    }
+    })?;
     println!("Event loop ended, quitting!");
+
+    Ok(())
 }
@@ -24,14 +24,14 @@ fn main() {
     )
     .unwrap();
     println!("Creating surface");
-    let surface = window.create_surface(OpenGlConfig::default());
+    let surface = window.create_surface(OpenGlConfig::default()).unwrap();
     println!("Making surface active");
-    window.make_surface_active(surface);
+    window.make_surface_active(surface).unwrap();
 
     let mut drawer = OpenglDrawer::new(600, 400, 1.0);
     draw(&mut drawer, window, surface);
 
-    while let Some(event) = lok::poll_event() {
+    while let Some(event) = lok::poll_event().unwrap() {
         match event.kind {
             EventKind::Resized(x, y) => {
                 drawer.resize(
@@ -66,5 +66,5 @@ fn draw(drawer: &mut OpenglDrawer, window: WindowHandle, surface: WindowSurface)
         alpha: 1.,
     });
     drawer.end_frame();
-    window.flush_surface(surface);
+    window.flush_surface(surface).unwrap();
 }
@@ -0,0 +1,429 @@
+//! A loader for Xcursor themes, the cursor themes of X11, which Wayland compositors expect clients
+//! to draw their cursor from when they can't do it themselves.
+//!
+//! Docs: https://www.x.org/releases/current/doc/man/man3/Xcursor.3.xhtml
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+const MAGIC: &[u8; 4] = b"Xcur";
+/// The type of the table of contents entries and chunks that hold an image.
+const IMAGE_TYPE: u32 = 0xfffd_0002;
+/// The largest width or height an image can have.
+const MAX_IMAGE_SIZE: u32 = 0x7fff;
+
+/// The size of cursors when `XCURSOR_SIZE` isn't set, in pixels.
+pub const DEFAULT_SIZE: u32 = 24;
+
+/// An image of a cursor from a cursor theme.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CursorImage {
+    pub width: u32,
+    pub height: u32,
+    pub hotspot: (u32, u32),
+    /// How long the image is shown for if the cursor is animated.
+    pub delay: Duration,
+    /// `width * height` premultiplied ARGB pixels, in rows from top to bottom.
+    pub pixels: Vec<u32>,
+}
+
+/// A cursor theme, which is looked up in the directories of the Xcursor search path.
+#[derive(Clone, Debug)]
+pub struct CursorTheme {
+    name: String,
+    size: u32,
+    search_path: Vec<PathBuf>,
+}
+
+impl CursorTheme {
+    /// The theme called `name`, with cursors of `size` pixels.
+    pub fn new(name: &str, size: u32) -> Self {
+        Self {
+            name: name.to_owned(),
+            size,
+            search_path: search_path(),
+        }
+    }
+
+    /// The user's theme, as set by `XCURSOR_THEME` and `XCURSOR_SIZE`.
+    pub fn from_env() -> Self {
+        let name = env::var("XCURSOR_THEME").unwrap_or_else(|_| "default".to_owned());
+        let size = env::var("XCURSOR_SIZE")
+            .ok()
+            .and_then(|size| size.parse().ok())
+            .filter(|&size| size > 0)
+            .unwrap_or(DEFAULT_SIZE);
+        Self::new(&name, size)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// Loads the frames of the cursor called `name`, from this theme, the themes it inherits from,
+    /// or the `default` theme. The images are the closest to the theme's size multiplied by
+    /// `scale`.
+    ///
+    /// Fails with [`NotFound`](io::ErrorKind::NotFound) if no theme has the cursor, and like
+    /// [`parse_cursor_file`] if its file isn't valid.
+    pub fn load_cursor(&self, name: &str, scale: u32) -> io::Result<Vec<CursorImage>> {
+        let mut visited = Vec::new();
+        let path = self
+            .find_cursor(&self.name, name, &mut visited)
+            .or_else(|| self.find_cursor("default", name, &mut visited))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no theme has the cursor"))?;
+
+        let data = fs::read(path)?;
+        parse_cursor_file(&data, self.size.saturating_mul(scale))
+    }
+
+    /// Finds the file of a cursor in a theme or in the themes it inherits from, skipping the
+    /// themes that were already visited.
+    fn find_cursor(&self, theme: &str, name: &str, visited: &mut Vec<String>) -> Option<PathBuf> {
+        if visited.iter().any(|visited| visited == theme) {
+            return None;
+        }
+        visited.push(theme.to_owned());
+
+        let file = self
+            .search_path
+            .iter()
+            .map(|dir| dir.join(theme).join("cursors").join(name))
+            .find(|file| file.is_file());
+        if file.is_some() {
+            return file;
+        }
+
+        self.inherits(theme)
+            .iter()
+            .find_map(|parent| self.find_cursor(parent, name, visited))
+    }
+
+    /// The themes that a theme inherits from, listed in the `Inherits` key of its `index.theme`.
+    fn inherits(&self, theme: &str) -> Vec<String> {
+        // Only the first index.theme in the search path counts
+        let Some(index) = self
+            .search_path
+            .iter()
+            .find_map(|dir| fs::read_to_string(dir.join(theme).join("index.theme")).ok())
+        else {
+            return Vec::new();
+        };
+
+        index
+            .lines()
+            .find_map(|line| {
+                line.strip_prefix("Inherits")?
+                    .trim_start()
+                    .strip_prefix('=')
+            })
+            .map(|themes| {
+                themes
+                    .split([',', ';', ' ', '\t'])
+                    .filter(|theme| !theme.is_empty())
+                    .map(str::to_owned)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// The directories that contain cursor themes, from `XCURSOR_PATH` or Xcursor's default path.
+pub fn search_path() -> Vec<PathBuf> {
+    let home = env::var_os("HOME").map(PathBuf::from);
+
+    if let Some(path) = env::var_os("XCURSOR_PATH") {
+        return env::split_paths(&path)
+            .filter_map(|dir| match dir.strip_prefix("~") {
+                Ok(dir) => Some(home.as_ref()?.join(dir)),
+                Err(_) => Some(dir),
+            })
+            .collect();
+    }
+
+    let data_home = env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| Some(home.as_ref()?.join(".local/share")));
+    let mut path: Vec<PathBuf> = data_home.into_iter().map(|dir| dir.join("icons")).collect();
+    path.extend(home.map(|home| home.join(".icons")));
+    path.extend(["/usr/share/icons", "/usr/share/pixmaps"].map(PathBuf::from));
+    path
+}
+
+/// Parses an Xcursor file, and returns the frames of the cursor whose nominal size is the closest
+/// to `size`.
+///
+/// Fails with [`InvalidData`](io::ErrorKind::InvalidData) if the file isn't a valid Xcursor file
+/// or doesn't have any image, and with [`UnexpectedEof`](io::ErrorKind::UnexpectedEof) if it's
+/// truncated.
+pub fn parse_cursor_file(data: &[u8], size: u32) -> io::Result<Vec<CursorImage>> {
+    if data.get(..4).ok_or_else(truncated)? != MAGIC {
+        return Err(invalid("not an Xcursor file"));
+    }
+    let header_len = read_u32(data, 4)? as usize;
+    let toc_len = read_u32(data, 12)? as usize;
+    // Each entry takes 12 bytes, so a longer table can't fit in the file
+    if toc_len > data.len() / 12 {
+        return Err(truncated());
+    }
+
+    // Entries are (type, nominal size, position)
+    let toc = (0..toc_len)
+        .map(|i| {
+            let entry = header_len.checked_add(i * 12).ok_or_else(truncated)?;
+            Ok((
+                read_u32(data, entry)?,
+                read_u32(data, entry.checked_add(4).ok_or_else(truncated)?)?,
+                read_u32(data, entry.checked_add(8).ok_or_else(truncated)?)?,
+            ))
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+
+    let nominal_size = toc
+        .iter()
+        .filter(|(kind, ..)| *kind == IMAGE_TYPE)
+        .map(|(_, nominal_size, _)| *nominal_size)
+        .min_by_key(|nominal_size| nominal_size.abs_diff(size))
+        .ok_or_else(|| invalid("the cursor has no image"))?;
+
+    toc.iter()
+        .filter(|&&(kind, entry_size, _)| kind == IMAGE_TYPE && entry_size == nominal_size)
+        .map(|&(_, _, position)| parse_image(data, position as usize))
+        .collect()
+}
+
+/// Parses an image chunk, which starts at `position`.
+fn parse_image(data: &[u8], position: usize) -> io::Result<CursorImage> {
+    let read_u32 =
+        |offset: usize| read_u32(data, position.checked_add(offset).ok_or_else(truncated)?);
+
+    let header_len = read_u32(0)? as usize;
+    if read_u32(4)? != IMAGE_TYPE {
+        return Err(invalid(
+            "a table of contents entry doesn't point to an image",
+        ));
+    }
+    let (width, height) = (read_u32(16)?, read_u32(20)?);
+    let (xhot, yhot) = (read_u32(24)?, read_u32(28)?);
+    let delay = Duration::from_millis(read_u32(32)?.into());
+
+    if width == 0 || height == 0 || width > MAX_IMAGE_SIZE || height > MAX_IMAGE_SIZE {
+        return Err(invalid("an image has an invalid size"));
+    }
+
+    let start = position.checked_add(header_len).ok_or_else(truncated)?;
+    let len = (width as usize)
+        .checked_mul(height as usize)
+        .and_then(|len| len.checked_mul(4))
+        .ok_or_else(|| invalid("an image has an invalid size"))?;
+    let end = start.checked_add(len).ok_or_else(truncated)?;
+    let pixels = data.get(start..end).ok_or_else(truncated)?;
+
+    Ok(CursorImage {
+        width,
+        height,
+        // Some themes put the hotspot right outside of the image
+        hotspot: (xhot.min(width - 1), yhot.min(height - 1)),
+        delay,
+        pixels: pixels
+            .as_chunks::<4>()
+            .0
+            .iter()
+            .map(|&pixel| u32::from_le_bytes(pixel))
+            .collect(),
+    })
+}
+
+/// Reads the little-endian `u32` at `offset`.
+fn read_u32(data: &[u8], offset: usize) -> io::Result<u32> {
+    let end = offset.checked_add(4).ok_or_else(truncated)?;
+    let bytes = data.get(offset..end).ok_or_else(truncated)?;
+    Ok(u32::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn truncated() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::UnexpectedEof,
+        "the Xcursor file is truncated",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER_LEN: u32 = 16;
+    const CHUNK_HEADER_LEN: u32 = 36;
+    const COMMENT_TYPE: u32 = 0xfffe_0001;
+
+    /// An image chunk to put in a fixture: nominal size, width, height, hotspot, delay.
+    struct Chunk(u32, u32, u32, (u32, u32), u32);
+
+    /// Builds an Xcursor file with one table of contents entry per chunk. Pixel `i` of each image
+    /// is `i`.
+    fn fixture(chunks: &[Chunk]) -> Vec<u8> {
+        let mut file = Vec::new();
+        let push = |file: &mut Vec<u8>, value: u32| file.extend(value.to_le_bytes());
+
+        file.extend(MAGIC);
+        push(&mut file, HEADER_LEN);
+        push(&mut file, 0x1_0000);
+        push(&mut file, chunks.len() as u32);
+
+        let mut position = HEADER_LEN + chunks.len() as u32 * 12;
+        for &Chunk(nominal_size, width, height, ..) in chunks {
+            push(&mut file, IMAGE_TYPE);
+            push(&mut file, nominal_size);
+            push(&mut file, position);
+            position += CHUNK_HEADER_LEN + width * height * 4;
+        }
+
+        for &Chunk(nominal_size, width, height, (xhot, yhot), delay) in chunks {
+            for value in [
+                CHUNK_HEADER_LEN,
+                IMAGE_TYPE,
+                nominal_size,
+                1,
+                width,
+                height,
+                xhot,
+                yhot,
+                delay,
+            ] {
+                push(&mut file, value);
+            }
+            for pixel in 0..width * height {
+                push(&mut file, pixel);
+            }
+        }
+        file
+    }
+
+    fn error_kind(data: &[u8]) -> io::ErrorKind {
+        parse_cursor_file(data, 24).unwrap_err().kind()
+    }
+
+    #[test]
+    fn parses_images_of_the_closest_size() {
+        let file = fixture(&[
+            Chunk(24, 2, 2, (1, 0), 0),
+            Chunk(48, 3, 1, (2, 0), 50),
+            Chunk(48, 3, 1, (0, 0), 70),
+        ]);
+
+        let images = parse_cursor_file(&file, 30).unwrap();
+        assert_eq!(
+            images,
+            [CursorImage {
+                width: 2,
+                height: 2,
+                hotspot: (1, 0),
+                delay: Duration::ZERO,
+                pixels: vec![0, 1, 2, 3],
+            }]
+        );
+
+        let images = parse_cursor_file(&file, 40).unwrap();
+        assert_eq!(images.len(), 2);
+        assert_eq!((images[0].width, images[0].height), (3, 1));
+        assert_eq!(images[0].delay, Duration::from_millis(50));
+        assert_eq!(images[1].delay, Duration::from_millis(70));
+        assert_eq!(images[1].pixels, [0, 1, 2]);
+    }
+
+    #[test]
+    fn clamps_hotspot_to_the_image() {
+        let file = fixture(&[Chunk(24, 4, 3, (4, 9), 0)]);
+        assert_eq!(parse_cursor_file(&file, 24).unwrap()[0].hotspot, (3, 2));
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        assert_eq!(
+            error_kind(b"GIF89a, not a cursor"),
+            io::ErrorKind::InvalidData
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_header() {
+        let file = fixture(&[Chunk(24, 1, 1, (0, 0), 0)]);
+        for len in 0..HEADER_LEN as usize {
+            assert_eq!(
+                error_kind(&file[..len]),
+                io::ErrorKind::UnexpectedEof,
+                "{len}"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_truncated_toc() {
+        let file = fixture(&[Chunk(24, 1, 1, (0, 0), 0), Chunk(32, 1, 1, (0, 0), 0)]);
+        let toc_end = HEADER_LEN as usize + 2 * 12;
+        for len in HEADER_LEN as usize..toc_end {
+            assert_eq!(
+                error_kind(&file[..len]),
+                io::ErrorKind::UnexpectedEof,
+                "{len}"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_every_truncation() {
+        let file = fixture(&[Chunk(24, 2, 2, (0, 0), 0), Chunk(24, 2, 2, (0, 0), 0)]);
+        assert!(parse_cursor_file(&file, 24).is_ok());
+        for len in 0..file.len() {
+            assert!(parse_cursor_file(&file[..len], 24).is_err(), "{len}");
+        }
+    }
+
+    #[test]
+    fn rejects_toc_longer_than_the_file() {
+        let mut file = fixture(&[Chunk(24, 1, 1, (0, 0), 0)]);
+        file[12..16].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(error_kind(&file), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn rejects_out_of_bounds_offsets() {
+        // The header's length, then the image's position
+        for offset in [4, HEADER_LEN as usize + 8] {
+            let mut file = fixture(&[Chunk(24, 1, 1, (0, 0), 0)]);
+            file[offset..offset + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+            assert_eq!(error_kind(&file), io::ErrorKind::UnexpectedEof, "{offset}");
+        }
+    }
+
+    #[test]
+    fn rejects_files_without_images() {
+        let mut file = fixture(&[Chunk(24, 1, 1, (0, 0), 0)]);
+        let toc_type = HEADER_LEN as usize;
+        file[toc_type..toc_type + 4].copy_from_slice(&COMMENT_TYPE.to_le_bytes());
+        assert_eq!(error_kind(&file), io::ErrorKind::InvalidData);
+
+        assert_eq!(error_kind(&fixture(&[])), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn rejects_invalid_image_sizes() {
+        let chunk_width = HEADER_LEN as usize + 12 + 16;
+        for width in [0, MAX_IMAGE_SIZE + 1] {
+            let mut file = fixture(&[Chunk(24, 1, 1, (0, 0), 0)]);
+            file[chunk_width..chunk_width + 4].copy_from_slice(&width.to_le_bytes());
+            assert_eq!(error_kind(&file), io::ErrorKind::InvalidData, "{width}");
+        }
+    }
+}
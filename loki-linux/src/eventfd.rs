@@ -0,0 +1,54 @@
+//! Bindings to `eventfd(2)`, a file descriptor acting as a counter. It becomes readable when it
+//! gets notified, which makes it handy to wake up a thread blocked in `poll` from another thread.
+//!
+//! Docs: https://man7.org/linux/man-pages/man2/eventfd.2.html
+
+use std::ffi::{c_int, c_uint};
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::os::fd::{AsRawFd, FromRawFd, RawFd};
+
+pub const EFD_SEMAPHORE: c_int = 0o1;
+pub const EFD_CLOEXEC: c_int = 0o2000000;
+pub const EFD_NONBLOCK: c_int = 0o4000;
+
+extern "C" {
+    pub fn eventfd(initval: c_uint, flags: c_int) -> c_int;
+}
+
+/// A nonblocking eventfd, closed on drop.
+pub struct EventFd {
+    file: File,
+}
+
+impl EventFd {
+    pub fn new() -> io::Result<Self> {
+        let fd = unsafe { eventfd(0, EFD_CLOEXEC | EFD_NONBLOCK) };
+
+        if fd == -1 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(Self {
+            file: unsafe { File::from_raw_fd(fd) },
+        })
+    }
+
+    /// Makes the file descriptor readable until the next [`EventFd::reset`].
+    pub fn notify(&self) {
+        // This can only fail if the counter would overflow, in which case it's readable anyway
+        let _ = (&self.file).write(&1_u64.to_ne_bytes());
+    }
+
+    /// Resets the counter to zero. Returns whether the file descriptor had been notified.
+    pub fn reset(&self) -> bool {
+        let mut counter = [0; 8];
+        (&self.file).read(&mut counter).is_ok()
+    }
+}
+
+impl AsRawFd for EventFd {
+    fn as_raw_fd(&self) -> RawFd {
+        self.file.as_raw_fd()
+    }
+}
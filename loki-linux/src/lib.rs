@@ -6,9 +6,12 @@ use std::rc::Rc;
 
 use dl::{dlopen, dlsym, get_dlerror, RTLD_NOW};
 
+pub mod cursor_theme;
 pub mod dl;
+pub mod eventfd;
 pub mod hashnt;
 pub mod locale;
+pub mod poll;
 
 #[cfg(feature = "wayland")]
 pub mod wayland;
@@ -0,0 +1,80 @@
+//! Bindings to `poll(2)`, used to wait on the display server's connection without spinning.
+//!
+//! Docs: https://man7.org/linux/man-pages/man2/poll.2.html
+
+use std::ffi::{c_int, c_short, c_ulong};
+use std::io;
+use std::os::fd::RawFd;
+use std::time::Duration;
+
+pub const POLLIN: c_short = 0x001;
+pub const POLLPRI: c_short = 0x002;
+pub const POLLOUT: c_short = 0x004;
+pub const POLLERR: c_short = 0x008;
+pub const POLLHUP: c_short = 0x010;
+pub const POLLNVAL: c_short = 0x020;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PollFd {
+    pub fd: RawFd,
+    /// The events we're interested in.
+    pub events: c_short,
+    /// The events that actually occurred, filled in by `poll`.
+    pub revents: c_short,
+}
+
+impl PollFd {
+    pub fn new(fd: RawFd, events: c_short) -> Self {
+        Self {
+            fd,
+            events,
+            revents: 0,
+        }
+    }
+
+    /// Whether `poll` reported any of the given events for this file descriptor.
+    pub fn has(&self, events: c_short) -> bool {
+        self.revents & events != 0
+    }
+}
+
+extern "C" {
+    pub fn poll(fds: *mut PollFd, nfds: c_ulong, timeout: c_int) -> c_int;
+}
+
+/// Waits until one of the file descriptors is ready, or until the timeout expires.
+/// A timeout of `None` blocks indefinitely, and a timeout of zero returns right away.
+///
+/// Returns the number of file descriptors that are ready, which is 0 on timeout.
+/// Interrupted calls are retried.
+pub fn poll_fds(fds: &mut [PollFd], timeout: Option<Duration>) -> io::Result<usize> {
+    // Round up, otherwise a timeout under a millisecond would make us spin
+    let timeout = match timeout {
+        Some(timeout) => timeout
+            .as_nanos()
+            .div_ceil(1_000_000)
+            .min(c_int::MAX as u128) as c_int,
+        None => -1,
+    };
+
+    loop {
+        let ready = unsafe { poll(fds.as_mut_ptr(), fds.len() as c_ulong, timeout) };
+
+        if ready >= 0 {
+            return Ok(ready as usize);
+        }
+
+        let error = io::Error::last_os_error();
+        if error.kind() != io::ErrorKind::Interrupted {
+            return Err(error);
+        }
+    }
+}
+
+/// Waits until `fd` has data to read, or until the timeout expires.
+/// Returns `false` if the timeout expired first.
+pub fn wait_readable(fd: RawFd, timeout: Option<Duration>) -> io::Result<bool> {
+    let mut fds = [PollFd::new(fd, POLLIN)];
+    Ok(poll_fds(&mut fds, timeout)? > 0)
+}
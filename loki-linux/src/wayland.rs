@@ -18,7 +18,7 @@ use {
         collections::HashMap,
         env,
         fs::OpenOptions,
-        io::Read,
+        io::{self, Read},
         os::{
             fd::FromRawFd,
             unix::{fs::OpenOptionsExt, net::UnixStream},
@@ -39,16 +39,18 @@ pub struct WaylandClient {
     pub names: HashMap<Name, Id, Hashnt>,
     /// Stores callback functions by their object IDs.
     pub callbacks: HashMap<Id, Box<dyn Fn(u32)>, Hashnt>,
-    /// A reusable buffer for reading messages from the compositor. This avoids needless reallocations
-    /// for every message read.
+    /// The bytes read from the compositor that weren't decoded yet, which may end with a partial
+    /// message. This also avoids needless reallocations for every message read.
     msg_buffer: Vec<u8>,
 }
 impl WaylandClient {
     /// Tries to connect to a Wayland compositor. If successful, creates and returns
     /// a [`WaylandClient`]. This will also automatically create a [`WlRegistry`] singleton
     /// with an object ID of 2, and then perform a [`Self::roundtrip`].
-    pub fn new() -> std::io::Result<Self> {
-        let socket = Self::find_compositor().ok_or_else(|| {
+    ///
+    /// See [`Self::find_compositor`] for the meaning of `display`.
+    pub fn new(display: Option<&str>) -> std::io::Result<Self> {
+        let socket = Self::find_compositor(display).ok_or_else(|| {
             std::io::Error::new(std::io::ErrorKind::NotFound, "Could not find a compositor")
         })?;
 
@@ -79,23 +81,32 @@ impl WaylandClient {
 
     /// Attempts to locate the Wayland compositor. This method is taken from:
     /// https://wayland-book.com/protocol-design/wire-protocol.html#transports
-    pub fn find_compositor() -> Option<UnixStream> {
-        if let Ok(socket) = env::var("WAYLAND_SOCKET") {
-            if let Ok(socket) = socket.parse::<i32>() {
-                return Some(unsafe { UnixStream::from_raw_fd(socket) });
+    ///
+    /// If `display` is set, it is used instead of the `WAYLAND_SOCKET` and `WAYLAND_DISPLAY`
+    /// environment variables. Like the latter, it is either a socket name in `XDG_RUNTIME_DIR`, or
+    /// an absolute path.
+    pub fn find_compositor(display: Option<&str>) -> Option<UnixStream> {
+        if display.is_none() {
+            if let Ok(socket) = env::var("WAYLAND_SOCKET") {
+                if let Ok(socket) = socket.parse::<i32>() {
+                    return Some(unsafe { UnixStream::from_raw_fd(socket) });
+                }
             }
         }
 
-        if let Ok(runtime_dir) = env::var("XDG_RUNTIME_DIR") {
-            if let Ok(display) = env::var("WAYLAND_DISPLAY") {
-                let path = PathBuf::from(runtime_dir);
-                return UnixStream::connect(path.join(display)).ok();
-            } else {
-                return UnixStream::connect(runtime_dir + "wayland-0").ok();
-            }
-        }
+        let display = match display {
+            Some(display) => display.to_owned(),
+            None => env::var("WAYLAND_DISPLAY").unwrap_or_else(|_| "wayland-0".to_owned()),
+        };
+
+        // Joining an absolute path replaces the runtime directory
+        let path = match env::var("XDG_RUNTIME_DIR") {
+            Ok(runtime_dir) => PathBuf::from(runtime_dir).join(display),
+            Err(_) if display.starts_with('/') => PathBuf::from(display),
+            Err(_) => return None,
+        };
 
-        None
+        UnixStream::connect(path).ok()
     }
 
     /// Gets and returns a [`WaylandEvent`], if one is sent from the compositor. After
@@ -110,26 +121,54 @@ impl WaylandClient {
     /// - [`WlRegistryEvent::GlobalRemove`]: The global is removed from both the [`WaylandClient`]'s global
     /// singleton storage and object storage.
     /// - [`WlCallbackEvent::Done`]: The corresponding callback will be called, if there's a function for it.
-    pub fn next_event(&mut self, nonblocking: bool) -> Option<WaylandEvent> {
-        let mut header = [0_u8; 8];
-        self.socket.set_nonblocking(nonblocking).unwrap();
-        self.socket.borrow().read_exact(&mut header).ok()?;
-
-        let decoder = WireDecoder::new(&header);
-        let msg_len = decoder.len();
-        println!("Got message with len {msg_len}");
+    ///
+    /// If `nonblocking` is set, this returns `Ok(None)` when no complete message has arrived yet.
+    /// Partial messages are kept until the rest of them arrives. If the compositor closed the
+    /// connection, this returns an [`UnexpectedEof`](io::ErrorKind::UnexpectedEof) error.
+    pub fn next_event(&mut self, nonblocking: bool) -> io::Result<Option<WaylandEvent>> {
+        loop {
+            if self.msg_buffer.len() >= 8 {
+                let msg_len = WireDecoder::new(&self.msg_buffer).len() as usize;
+                if msg_len < 8 {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "The compositor sent a message shorter than its header",
+                    ));
+                }
+                if self.msg_buffer.len() >= msg_len {
+                    let event = self.decode_message(msg_len);
+                    self.msg_buffer.drain(..msg_len);
+                    match event {
+                        Some(event) => return Ok(Some(event)),
+                        // Skip the events of destroyed objects and the ones we can't decode
+                        None => continue,
+                    }
+                }
+            }
 
-        self.msg_buffer.extend(header);
-        self.msg_buffer.resize(msg_len as usize, 0);
-        self.socket
-            .borrow()
-            .read_exact(&mut self.msg_buffer[8..])
-            .expect("Failed to read from Wayland compositor.");
+            self.socket.set_nonblocking(nonblocking)?;
+            let mut chunk = [0_u8; 4096];
+            match self.socket.borrow().read(&mut chunk) {
+                Ok(0) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "The compositor closed the connection",
+                    ))
+                }
+                Ok(len) => self.msg_buffer.extend_from_slice(&chunk[..len]),
+                Err(error) if error.kind() == io::ErrorKind::WouldBlock => return Ok(None),
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+                Err(error) => return Err(error),
+            }
+        }
+    }
 
-        let mut decoder = WireDecoder::new(&self.msg_buffer);
+    /// Decodes the message of `msg_len` bytes at the start of the message buffer, and handles it
+    /// if it's one of the events listed in [`WaylandClient::next_event`].
+    fn decode_message(&mut self, msg_len: usize) -> Option<WaylandEvent> {
+        let mut decoder = WireDecoder::new(&self.msg_buffer[..msg_len]);
         let obj_id = decoder.object_id();
-        let interface = self.objects[obj_id.raw as usize]
-            .expect("Wayland compositor sent an invalid object ID in a message.");
+        let interface = (*self.objects.get(obj_id.raw as usize)?)?;
         let event = interface.decode_event(&mut decoder)?;
 
         match &event {
@@ -153,7 +192,6 @@ impl WaylandClient {
             _ => {}
         }
 
-        self.msg_buffer.clear();
         Some(event)
     }
 
@@ -61,6 +61,103 @@ pub enum WlSurfaceEvent {
     PreferredBufferTransform(transform: u32) = 3, // TODO: transform is an enum
 }
 
+pub enum WlSeatEvent {
+    type Interface = WlSeat;
+
+    Capabilities(capabilities: u32) = 0, // TODO: capabilities is an enum
+    Name(name: String) = 1,
+}
+
+pub enum WlPointerEvent {
+    type Interface = WlPointer;
+
+    Enter(serial: u32, surface: WlSurface, surface_x: Fixed, surface_y: Fixed) = 0,
+    Leave(serial: u32, surface: WlSurface) = 1,
+    Motion(time: u32, surface_x: Fixed, surface_y: Fixed) = 2,
+    Button(serial: u32, time: u32, button: u32, state: u32) = 3, // TODO: state is an enum
+    /// Stores the time, the axis (0 for vertical, 1 for horizontal) and how far it scrolled, in
+    /// the same unit as motion events. Positive values scroll down or right.
+    Axis(time: u32, axis: u32, value: Fixed) = 4,
+    /// Ends a group of events that happened at the same time, such as scrolling on both axes.
+    Frame = 5,
+    /// Stores what scrolled: 0 for a wheel, 1 for fingers, 2 for a continuous device such as a
+    /// trackball, 3 for a wheel tilt.
+    AxisSource(axis_source: u32) = 6,
+    /// Fingers left the device, on the given axis.
+    AxisStop(time: u32, axis: u32) = 7,
+    AxisDiscrete(axis: u32, discrete: i32) = 8,
+    /// Stores the axis and how far it scrolled, in 120ths of a wheel step.
+    AxisValue120(axis: u32, value120: i32) = 9,
+    /// Stores the axis and whether scrolling is inverted from the physical motion (1) or not (0).
+    AxisRelativeDirection(axis: u32, direction: u32) = 10,
+}
+
+pub enum WlKeyboardEvent {
+    type Interface = WlKeyboard;
+
+    /// Stores the keymap's format and size. The keymap itself is sent as a file descriptor, which
+    /// isn't part of the message's body and isn't received yet.
+    Keymap(format: u32, size: u32) = 0,
+    Enter(serial: u32, surface: WlSurface) = 1, // TODO: array type for the pressed keys
+    Leave(serial: u32, surface: WlSurface) = 2,
+    Key(serial: u32, time: u32, key: u32, state: u32) = 3, // TODO: state is an enum
+    /// Stores the serial, then the depressed, latched and locked modifier masks, then the
+    /// keyboard layout group.
+    Modifiers(serial: u32, mods_depressed: u32, mods_latched: u32, mods_locked: u32, group: u32) = 4,
+    RepeatInfo(rate: i32, delay: i32) = 5,
+}
+
+pub enum ZwpTextInputV3Event {
+    type Interface = ZwpTextInputV3;
+
+    Enter(surface: WlSurface) = 0,
+    Leave(surface: WlSurface) = 1,
+    /// Stores the pending preedit text, then the byte range of its cursor. The range is `-1, -1`
+    /// when the cursor is hidden. Applied by the next `Done` event.
+    PreeditString(text: String, cursor_begin: i32, cursor_end: i32) = 2,
+    /// Stores the pending text to insert. Applied by the next `Done` event.
+    CommitString(text: String) = 3,
+    /// Stores the pending number of bytes to delete before and after the cursor. Applied by
+    /// the next `Done` event.
+    DeleteSurroundingText(before_length: u32, after_length: u32) = 4,
+    /// Applies the pending changes, in the order: clear the preedit text, delete the surrounding
+    /// text, insert the commit text, and set the new preedit text.
+    Done(serial: u32) = 5,
+}
+
+pub enum ZwpRelativePointerV1Event {
+    type Interface = ZwpRelativePointerV1;
+
+    /// Stores the time in microseconds, split in its high and low 32 bits, then the motion
+    /// after acceleration, then the motion before acceleration.
+    RelativeMotion(
+        utime_hi: u32,
+        utime_lo: u32,
+        dx: Fixed,
+        dy: Fixed,
+        dx_unaccel: Fixed,
+        dy_unaccel: Fixed
+    ) = 0,
+}
+
+pub enum ZwpLockedPointerV1Event {
+    type Interface = ZwpLockedPointerV1;
+
+    /// The lock became active.
+    Locked = 0,
+    /// The lock became inactive. It's destroyed if it was a oneshot lock.
+    Unlocked = 1,
+}
+
+pub enum ZwpConfinedPointerV1Event {
+    type Interface = ZwpConfinedPointerV1;
+
+    /// The confinement became active.
+    Confined = 0,
+    /// The confinement became inactive. It's destroyed if it was a oneshot confinement.
+    Unconfined = 1,
+}
+
 pub enum XdgWmBaseEvent {
     type Interface = XdgWmBase;
 
@@ -1,8 +1,15 @@
 pub mod core;
+pub mod cursor_shape;
+pub mod pointer_constraints;
+pub mod relative_pointer;
+pub mod text_input;
 pub mod xdg;
 
 pub mod all {
-    pub use super::{core::*, xdg::*, Interface};
+    pub use super::{
+        core::*, cursor_shape::*, pointer_constraints::*, relative_pointer::*, text_input::*,
+        xdg::*, Interface,
+    };
 }
 
 mod interface_prelude {
@@ -81,6 +88,15 @@ pub enum Interface {
     XdgSurface,
     XdgToplevel,
     XdgPopup,
+    ZwpTextInputManagerV3,
+    ZwpTextInputV3,
+    ZwpRelativePointerManagerV1,
+    ZwpRelativePointerV1,
+    ZwpPointerConstraintsV1,
+    ZwpLockedPointerV1,
+    ZwpConfinedPointerV1,
+    WpCursorShapeManagerV1,
+    WpCursorShapeDeviceV1,
 }
 impl Interface {
     /// The version of this interface that Loki supports.
@@ -111,6 +127,15 @@ impl Interface {
             Self::XdgSurface => 6,
             Self::XdgToplevel => 6,
             Self::XdgPopup => 6,
+            Self::ZwpTextInputManagerV3 => 1,
+            Self::ZwpTextInputV3 => 1,
+            Self::ZwpRelativePointerManagerV1 => 1,
+            Self::ZwpRelativePointerV1 => 1,
+            Self::ZwpPointerConstraintsV1 => 1,
+            Self::ZwpLockedPointerV1 => 1,
+            Self::ZwpConfinedPointerV1 => 1,
+            Self::WpCursorShapeManagerV1 => 1,
+            Self::WpCursorShapeDeviceV1 => 1,
         }
     }
 }
@@ -142,6 +167,15 @@ impl std::fmt::Display for Interface {
             Self::XdgSurface => "xdg_surface",
             Self::XdgToplevel => "xdg_toplevel",
             Self::XdgPopup => "xdg_popup",
+            Self::ZwpTextInputManagerV3 => "zwp_text_input_manager_v3",
+            Self::ZwpTextInputV3 => "zwp_text_input_v3",
+            Self::ZwpRelativePointerManagerV1 => "zwp_relative_pointer_manager_v1",
+            Self::ZwpRelativePointerV1 => "zwp_relative_pointer_v1",
+            Self::ZwpPointerConstraintsV1 => "zwp_pointer_constraints_v1",
+            Self::ZwpLockedPointerV1 => "zwp_locked_pointer_v1",
+            Self::ZwpConfinedPointerV1 => "zwp_confined_pointer_v1",
+            Self::WpCursorShapeManagerV1 => "wp_cursor_shape_manager_v1",
+            Self::WpCursorShapeDeviceV1 => "wp_cursor_shape_device_v1",
         };
 
         write!(f, "{name}")
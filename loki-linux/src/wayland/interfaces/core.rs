@@ -67,19 +67,19 @@ decl_interfaces! {
     }
 
     WlSeat {
-        Events = (); // TODO
-        Methods = (); // TODO
+        Events = WlSeatEvent;
+        Methods = WlSeatMethod;
         GlobalSingleton = true;
     }
 
     WlPointer {
-        Events = (); // TODO
-        Methods = (); // TODO
+        Events = WlPointerEvent;
+        Methods = WlPointerMethod;
     }
 
     WlKeyboard {
-        Events = (); // TODO
-        Methods = (); // TODO
+        Events = WlKeyboardEvent;
+        Methods = WlKeyboardMethod;
     }
 
     WlTouch {
@@ -95,7 +95,7 @@ decl_interfaces! {
 
     WlRegion {
         Events = ();
-        Methods = (); // TODO
+        Methods = WlRegionMethod;
     }
 
     WlSubcompositor {
@@ -155,6 +155,23 @@ impl WlCompositor {
     }
 }
 
+impl WlSeat {
+    pub fn get_pointer(&self, client: &mut WaylandClient) -> WlPointer {
+        let id = client.next_object_id();
+        client.call_method(self, WlSeatMethod::GetPointer(id));
+        client.objects[id.raw as usize] = Some(Interface::WlPointer);
+
+        WlPointer { id }
+    }
+    pub fn get_keyboard(&self, client: &mut WaylandClient) -> WlKeyboard {
+        let id = client.next_object_id();
+        client.call_method(self, WlSeatMethod::GetKeyboard(id));
+        client.objects[id.raw as usize] = Some(Interface::WlKeyboard);
+
+        WlKeyboard { id }
+    }
+}
+
 impl WlShm {
     pub fn create_pool(&self, client: &mut WaylandClient, fd: Fd, size: i32) -> WlShmPool {
         let id = client.next_object_id();
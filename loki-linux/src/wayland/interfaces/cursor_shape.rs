@@ -0,0 +1,68 @@
+//! The `cursor-shape-v1` protocol, which shows cursors from the compositor's cursor theme.
+
+use super::interface_prelude::*;
+
+decl_interfaces! {
+    WpCursorShapeManagerV1 {
+        Events = ();
+        Methods = WpCursorShapeManagerV1Method;
+        GlobalSingleton = true;
+    }
+
+    WpCursorShapeDeviceV1 {
+        Events = ();
+        Methods = WpCursorShapeDeviceV1Method;
+    }
+}
+
+/// The shapes for [`WpCursorShapeDeviceV1Method::SetShape`], named after the CSS cursors.
+pub mod shape {
+    pub const DEFAULT: u32 = 1;
+    pub const CONTEXT_MENU: u32 = 2;
+    pub const HELP: u32 = 3;
+    pub const POINTER: u32 = 4;
+    pub const PROGRESS: u32 = 5;
+    pub const WAIT: u32 = 6;
+    pub const CELL: u32 = 7;
+    pub const CROSSHAIR: u32 = 8;
+    pub const TEXT: u32 = 9;
+    pub const VERTICAL_TEXT: u32 = 10;
+    pub const ALIAS: u32 = 11;
+    pub const COPY: u32 = 12;
+    pub const MOVE: u32 = 13;
+    pub const NO_DROP: u32 = 14;
+    pub const NOT_ALLOWED: u32 = 15;
+    pub const GRAB: u32 = 16;
+    pub const GRABBING: u32 = 17;
+    pub const E_RESIZE: u32 = 18;
+    pub const N_RESIZE: u32 = 19;
+    pub const NE_RESIZE: u32 = 20;
+    pub const NW_RESIZE: u32 = 21;
+    pub const S_RESIZE: u32 = 22;
+    pub const SE_RESIZE: u32 = 23;
+    pub const SW_RESIZE: u32 = 24;
+    pub const W_RESIZE: u32 = 25;
+    pub const EW_RESIZE: u32 = 26;
+    pub const NS_RESIZE: u32 = 27;
+    pub const NESW_RESIZE: u32 = 28;
+    pub const NWSE_RESIZE: u32 = 29;
+    pub const COL_RESIZE: u32 = 30;
+    pub const ROW_RESIZE: u32 = 31;
+    pub const ALL_SCROLL: u32 = 32;
+    pub const ZOOM_IN: u32 = 33;
+    pub const ZOOM_OUT: u32 = 34;
+}
+
+impl WpCursorShapeManagerV1 {
+    pub fn get_pointer(
+        &self,
+        client: &mut WaylandClient,
+        pointer: WlPointer,
+    ) -> WpCursorShapeDeviceV1 {
+        let id = client.next_object_id();
+        client.call_method(self, WpCursorShapeManagerV1Method::GetPointer(id, pointer));
+        client.objects[id.raw as usize] = Some(Interface::WpCursorShapeDeviceV1);
+
+        WpCursorShapeDeviceV1 { id }
+    }
+}
@@ -0,0 +1,72 @@
+//! The `pointer-constraints-unstable-v1` protocol, which locks the pointer in place or confines
+//! it to a region of a surface.
+
+use super::interface_prelude::*;
+
+decl_interfaces! {
+    ZwpPointerConstraintsV1 {
+        Events = ();
+        Methods = ZwpPointerConstraintsV1Method;
+        GlobalSingleton = true;
+    }
+
+    ZwpLockedPointerV1 {
+        Events = ZwpLockedPointerV1Event;
+        Methods = ZwpLockedPointerV1Method;
+    }
+
+    ZwpConfinedPointerV1 {
+        Events = ZwpConfinedPointerV1Event;
+        Methods = ZwpConfinedPointerV1Method;
+    }
+}
+
+/// How long a pointer constraint lasts, for [`ZwpPointerConstraintsV1`]'s methods.
+pub mod constraint_lifetime {
+    /// The constraint is destroyed once it's deactivated, such as when the surface loses the
+    /// pointer focus.
+    pub const ONESHOT: u32 = 1;
+    /// The constraint is activated again whenever the surface gets the pointer focus back.
+    pub const PERSISTENT: u32 = 2;
+}
+
+impl ZwpPointerConstraintsV1 {
+    /// Locks the pointer in place while it's over `region` of `surface`, or anywhere over
+    /// `surface` if `region` is `None`.
+    pub fn lock_pointer(
+        &self,
+        client: &mut WaylandClient,
+        surface: WlSurface,
+        pointer: WlPointer,
+        region: Option<WlRegion>,
+        lifetime: u32,
+    ) -> ZwpLockedPointerV1 {
+        let id = client.next_object_id();
+        client.call_method(
+            self,
+            ZwpPointerConstraintsV1Method::LockPointer(id, surface, pointer, region, lifetime),
+        );
+        client.objects[id.raw as usize] = Some(Interface::ZwpLockedPointerV1);
+
+        ZwpLockedPointerV1 { id }
+    }
+
+    /// Keeps the pointer in `region` of `surface`, or in all of `surface` if `region` is `None`.
+    pub fn confine_pointer(
+        &self,
+        client: &mut WaylandClient,
+        surface: WlSurface,
+        pointer: WlPointer,
+        region: Option<WlRegion>,
+        lifetime: u32,
+    ) -> ZwpConfinedPointerV1 {
+        let id = client.next_object_id();
+        client.call_method(
+            self,
+            ZwpPointerConstraintsV1Method::ConfinePointer(id, surface, pointer, region, lifetime),
+        );
+        client.objects[id.raw as usize] = Some(Interface::ZwpConfinedPointerV1);
+
+        ZwpConfinedPointerV1 { id }
+    }
+}
@@ -0,0 +1,33 @@
+//! The `relative-pointer-unstable-v1` protocol, which reports pointer motion before acceleration.
+
+use super::interface_prelude::*;
+
+decl_interfaces! {
+    ZwpRelativePointerManagerV1 {
+        Events = ();
+        Methods = ZwpRelativePointerManagerV1Method;
+        GlobalSingleton = true;
+    }
+
+    ZwpRelativePointerV1 {
+        Events = ZwpRelativePointerV1Event;
+        Methods = ZwpRelativePointerV1Method;
+    }
+}
+
+impl ZwpRelativePointerManagerV1 {
+    pub fn get_relative_pointer(
+        &self,
+        client: &mut WaylandClient,
+        pointer: WlPointer,
+    ) -> ZwpRelativePointerV1 {
+        let id = client.next_object_id();
+        client.call_method(
+            self,
+            ZwpRelativePointerManagerV1Method::GetRelativePointer(id, pointer),
+        );
+        client.objects[id.raw as usize] = Some(Interface::ZwpRelativePointerV1);
+
+        ZwpRelativePointerV1 { id }
+    }
+}
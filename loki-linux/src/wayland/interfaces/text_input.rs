@@ -0,0 +1,26 @@
+//! The `text-input-unstable-v3` protocol, which lets input methods compose text in surfaces.
+
+use super::interface_prelude::*;
+
+decl_interfaces! {
+    ZwpTextInputManagerV3 {
+        Events = ();
+        Methods = ZwpTextInputManagerV3Method;
+        GlobalSingleton = true;
+    }
+
+    ZwpTextInputV3 {
+        Events = ZwpTextInputV3Event;
+        Methods = ZwpTextInputV3Method;
+    }
+}
+
+impl ZwpTextInputManagerV3 {
+    pub fn get_text_input(&self, client: &mut WaylandClient, seat: WlSeat) -> ZwpTextInputV3 {
+        let id = client.next_object_id();
+        client.call_method(self, ZwpTextInputManagerV3Method::GetTextInput(id, seat));
+        client.objects[id.raw as usize] = Some(Interface::ZwpTextInputV3);
+
+        ZwpTextInputV3 { id }
+    }
+}
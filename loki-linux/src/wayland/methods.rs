@@ -59,6 +59,109 @@ pub enum WlSurfaceMethod {
     Offset(x: i32, y: i32) = 10
 }
 
+pub enum WlRegionMethod {
+    Destroy = 0,
+    Add(x: i32, y: i32, width: i32, height: i32) = 1,
+    Subtract(x: i32, y: i32, width: i32, height: i32) = 2,
+}
+
+pub enum WlSeatMethod {
+    /// Create a [`WlPointer`] with the given ID.
+    GetPointer(id: Id) = 0,
+    /// Create a [`WlKeyboard`] with the given ID.
+    GetKeyboard(id: Id) = 1,
+    /// Create a [`WlTouch`] with the given ID.
+    GetTouch(id: Id) = 2,
+    Release = 3,
+}
+
+pub enum WlPointerMethod {
+    SetCursor(serial: u32, surface: Option<WlSurface>, hotspot_x: i32, hotspot_y: i32) = 0,
+    Release = 1,
+}
+
+pub enum WlKeyboardMethod {
+    Release = 0,
+}
+
+pub enum ZwpTextInputManagerV3Method {
+    Destroy = 0,
+    /// Create a [`ZwpTextInputV3`] for the given seat.
+    GetTextInput(id: Id, seat: WlSeat) = 1,
+}
+
+pub enum ZwpTextInputV3Method {
+    Destroy = 0,
+    /// Request input method support for the focused surface. Takes effect on the next `Commit`.
+    Enable = 1,
+    Disable = 2,
+    SetSurroundingText(text: String, cursor: i32, anchor: i32) = 3,
+    SetTextChangeCause(cause: u32) = 4, // TODO: cause is an enum
+    SetContentType(hint: u32, purpose: u32) = 5, // TODO: hint and purpose are enums
+    SetCursorRectangle(x: i32, y: i32, width: i32, height: i32) = 6,
+    /// Apply the pending state.
+    Commit = 7,
+}
+
+pub enum ZwpRelativePointerManagerV1Method {
+    Destroy = 0,
+    /// Create a [`ZwpRelativePointerV1`] for the given pointer.
+    GetRelativePointer(id: Id, pointer: WlPointer) = 1,
+}
+
+pub enum ZwpRelativePointerV1Method {
+    Destroy = 0,
+}
+
+pub enum ZwpPointerConstraintsV1Method {
+    Destroy = 0,
+    /// Create a [`ZwpLockedPointerV1`]. The region is in surface coordinates, and the lifetime
+    /// is one of the [`constraint_lifetime`] values.
+    LockPointer(
+        id: Id,
+        surface: WlSurface,
+        pointer: WlPointer,
+        region: Option<WlRegion>,
+        lifetime: u32
+    ) = 1,
+    /// Create a [`ZwpConfinedPointerV1`]. The region is in surface coordinates, and the lifetime
+    /// is one of the [`constraint_lifetime`] values.
+    ConfinePointer(
+        id: Id,
+        surface: WlSurface,
+        pointer: WlPointer,
+        region: Option<WlRegion>,
+        lifetime: u32
+    ) = 2,
+}
+
+pub enum ZwpLockedPointerV1Method {
+    Destroy = 0,
+    /// Where the cursor should appear when the lock ends, in surface coordinates. Takes effect
+    /// on the surface's next commit.
+    SetCursorPositionHint(surface_x: Fixed, surface_y: Fixed) = 1,
+    /// Change the region the lock applies in. Takes effect on the surface's next commit.
+    SetRegion(region: Option<WlRegion>) = 2,
+}
+
+pub enum ZwpConfinedPointerV1Method {
+    Destroy = 0,
+    /// Change the region the pointer is confined to. Takes effect on the surface's next commit.
+    SetRegion(region: Option<WlRegion>) = 1,
+}
+
+pub enum WpCursorShapeManagerV1Method {
+    Destroy = 0,
+    /// Create a [`WpCursorShapeDeviceV1`] for the given pointer.
+    GetPointer(id: Id, pointer: WlPointer) = 1,
+}
+
+pub enum WpCursorShapeDeviceV1Method {
+    Destroy = 0,
+    /// Show one of the [`shape`]s, with the serial of the latest `wl_pointer.enter` event.
+    SetShape(serial: u32, shape: u32) = 1,
+}
+
 pub enum XdgWmBaseMethod {
     Destroy = 0,
     CreatePositioner(id: Id) = 1,
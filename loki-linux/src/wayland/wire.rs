@@ -107,19 +107,29 @@ impl WriteWire for u32 {
 
 // fixed
 
-// TODO: implement fixed decimal point functionalities
-#[derive(Clone, Copy)]
-#[allow(dead_code)]
-pub struct Fixed(i32);
+/// A signed 24.8 fixed-point number.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Fixed(pub i32);
+
+impl Fixed {
+    pub fn from_f64(value: f64) -> Self {
+        Self((value * 256.0).round() as i32)
+    }
+
+    pub fn to_f64(self) -> f64 {
+        f64::from(self.0) / 256.0
+    }
+}
 
 impl<'a> ReadWire<'a> for Fixed {
-    fn read_wire(_data: &'a [u8]) -> (Self, usize) {
-        todo!()
+    fn read_wire(data: &'a [u8]) -> (Self, usize) {
+        let (raw, used_bytes) = i32::read_wire(data);
+        (Self(raw), used_bytes)
     }
 }
 impl WriteWire for Fixed {
-    fn write_wire(&self, _encoder: &mut WireEncoder) {
-        todo!()
+    fn write_wire(&self, encoder: &mut WireEncoder) {
+        self.0.write_wire(encoder)
     }
 }
 
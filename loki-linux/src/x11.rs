@@ -1,8 +1,10 @@
 #![allow(non_snake_case, clippy::upper_case_acronyms)]
 
-use std::ffi::{c_char, c_int, c_long, c_short, c_uint, c_ulong, c_void};
+use std::ffi::{c_char, c_int, c_long, c_short, c_uint, c_ulong, c_ushort, c_void};
 
+pub mod xcursor;
 pub mod xevents;
+pub mod xinput2;
 
 pub use xevents::*;
 
@@ -16,6 +18,8 @@ pub struct XDisplay([u8; 0]);
 pub struct XID(pub(crate) c_ulong);
 
 impl XID {
+    pub const NONE: Self = XID(0);
+
     /// Creates a [`XID`] from a raw ID.
     ///
     /// # Safety
@@ -74,6 +78,17 @@ pub mod bool {
     pub const TRUE: Bool = 0;
 }
 
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct XColor {
+    pub pixel: c_ulong,
+    pub red: c_ushort,
+    pub green: c_ushort,
+    pub blue: c_ushort,
+    pub flags: c_char,
+    pad: c_char,
+}
+
 #[repr(C)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct XPoint {
@@ -107,6 +122,75 @@ pub type XIM = *mut _XIM;
 pub struct _XIC([u8; 0]);
 pub type XIC = *mut _XIC;
 
+/// The signature of input context callbacks. The return value is only used by the preedit start
+/// callback, as the maximum length of the preedit text (or -1 for no limit).
+pub type XICProc =
+    unsafe extern "C" fn(ic: XIC, client_data: XPointer, call_data: XPointer) -> c_int;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct XICCallback {
+    pub client_data: XPointer,
+    pub callback: Option<XICProc>,
+}
+
+pub type XIMFeedback = c_ulong;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union XIMTextString {
+    pub multi_byte: *mut c_char,
+    pub wide_char: *mut u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct XIMText {
+    /// The length of the text, in characters.
+    pub length: u16,
+    pub feedback: *mut XIMFeedback,
+    pub encoding_is_wchar: Bool,
+    pub string: XIMTextString,
+}
+
+/// Sent to the preedit draw callback: `chg_length` characters starting at `chg_first` are
+/// replaced by `text`, which is null when they're only deleted.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct XIMPreeditDrawCallbackStruct {
+    pub caret: c_int,
+    pub chg_first: c_int,
+    pub chg_length: c_int,
+    pub text: *mut XIMText,
+}
+
+pub type XIMCaretDirection = c_int;
+
+pub mod xim_caret {
+    use super::XIMCaretDirection;
+
+    pub const FORWARD_CHAR: XIMCaretDirection = 0;
+    pub const BACKWARD_CHAR: XIMCaretDirection = 1;
+    pub const FORWARD_WORD: XIMCaretDirection = 2;
+    pub const BACKWARD_WORD: XIMCaretDirection = 3;
+    pub const CARET_UP: XIMCaretDirection = 4;
+    pub const CARET_DOWN: XIMCaretDirection = 5;
+    pub const NEXT_LINE: XIMCaretDirection = 6;
+    pub const PREVIOUS_LINE: XIMCaretDirection = 7;
+    pub const LINE_START: XIMCaretDirection = 8;
+    pub const LINE_END: XIMCaretDirection = 9;
+    pub const ABSOLUTE_POSITION: XIMCaretDirection = 10;
+    pub const DONT_CHANGE: XIMCaretDirection = 11;
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct XIMPreeditCaretCallbackStruct {
+    pub position: c_int,
+    pub direction: XIMCaretDirection,
+    pub style: c_int,
+}
+
 #[repr(C)]
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct XComposeStatus {
@@ -265,6 +349,25 @@ pub mod xevent_mask {
     pub const OWNER_GRAB_BUTTON: c_long = 0x0100_0000;
 }
 
+/// Masks of the `state` field of key, button, motion and crossing events.
+pub mod xkey_mask {
+    use std::ffi::c_uint;
+
+    pub const SHIFT: c_uint = 0x0001;
+    pub const LOCK: c_uint = 0x0002;
+    pub const CONTROL: c_uint = 0x0004;
+    pub const MOD1: c_uint = 0x0008;
+    pub const MOD2: c_uint = 0x0010;
+    pub const MOD3: c_uint = 0x0020;
+    pub const MOD4: c_uint = 0x0040;
+    pub const MOD5: c_uint = 0x0080;
+    pub const BUTTON1: c_uint = 0x0100;
+    pub const BUTTON2: c_uint = 0x0200;
+    pub const BUTTON3: c_uint = 0x0400;
+    pub const BUTTON4: c_uint = 0x0800;
+    pub const BUTTON5: c_uint = 0x1000;
+}
+
 pub mod xim {
     use std::ffi::c_long;
 
@@ -342,6 +445,54 @@ pub mod property {
     pub const DELETE: c_int = 1;
 }
 
+/// Shapes of the core cursor font, for `XCreateFontCursor`.
+pub mod cursor_font {
+    use std::ffi::c_uint;
+
+    pub const X_CURSOR: c_uint = 0;
+    pub const ARROW: c_uint = 2;
+    pub const BOTTOM_LEFT_CORNER: c_uint = 12;
+    pub const BOTTOM_RIGHT_CORNER: c_uint = 14;
+    pub const BOTTOM_SIDE: c_uint = 16;
+    pub const CROSSHAIR: c_uint = 34;
+    pub const FLEUR: c_uint = 52;
+    pub const HAND1: c_uint = 58;
+    pub const HAND2: c_uint = 60;
+    pub const LEFT_PTR: c_uint = 68;
+    pub const LEFT_SIDE: c_uint = 70;
+    pub const QUESTION_ARROW: c_uint = 92;
+    pub const RIGHT_SIDE: c_uint = 96;
+    pub const SB_H_DOUBLE_ARROW: c_uint = 108;
+    pub const SB_V_DOUBLE_ARROW: c_uint = 116;
+    pub const TOP_LEFT_CORNER: c_uint = 134;
+    pub const TOP_RIGHT_CORNER: c_uint = 136;
+    pub const TOP_SIDE: c_uint = 138;
+    pub const WATCH: c_uint = 150;
+    pub const XTERM: c_uint = 152;
+}
+
+/// A time that stands for the server's current time, in requests that take one.
+pub const CURRENT_TIME: Time = 0;
+
+/// Whether a grab freezes the grabbed device until the client allows events again.
+pub mod grab_mode {
+    use std::ffi::c_int;
+
+    pub const SYNC: c_int = 0;
+    pub const ASYNC: c_int = 1;
+}
+
+/// The results of `XGrabPointer` and `XGrabKeyboard`.
+pub mod grab_status {
+    use std::ffi::c_int;
+
+    pub const SUCCESS: c_int = 0;
+    pub const ALREADY_GRABBED: c_int = 1;
+    pub const INVALID_TIME: c_int = 2;
+    pub const NOT_VIEWABLE: c_int = 3;
+    pub const FROZEN: c_int = 4;
+}
+
 library! {
     [LibX11 <-> "X11"];
 
@@ -354,6 +505,7 @@ library! {
 
     pub fn XOpenDisplay(display_name: *const c_char) -> *mut XDisplay;
     pub fn XCloseDisplay(display: *mut XDisplay);
+    pub fn XConnectionNumber(display: *mut XDisplay) -> c_int;
     pub fn XDefaultScreen(display: *mut XDisplay) -> c_int;
     pub fn XDefaultRootWindow(display: *mut XDisplay) -> XWindow;
     pub fn XDestroyWindow(display: *mut XDisplay, win: XWindow);
@@ -474,6 +626,67 @@ library! {
         event_mask: c_long,
         event_send: *mut XEvent,
     ) -> c_int;
+    pub fn XGetEventData(display: *mut XDisplay, cookie: *mut XGenericEventCookie) -> Bool;
+    pub fn XFreeEventData(display: *mut XDisplay, cookie: *mut XGenericEventCookie);
+
+    // Pointer
+
+    pub fn XCreateFontCursor(display: *mut XDisplay, shape: c_uint) -> Cursor;
+    pub fn XDefineCursor(display: *mut XDisplay, window: XWindow, cursor: Cursor) -> c_int;
+    pub fn XUndefineCursor(display: *mut XDisplay, window: XWindow) -> c_int;
+    pub fn XFreeCursor(display: *mut XDisplay, cursor: Cursor) -> c_int;
+    pub fn XCreatePixmapCursor(
+        display: *mut XDisplay,
+        source: Pixmap,
+        mask: Pixmap,
+        foreground_color: *mut XColor,
+        background_color: *mut XColor,
+        x: c_uint,
+        y: c_uint,
+    ) -> Cursor;
+
+    pub fn XCreateBitmapFromData(
+        display: *mut XDisplay,
+        drawable: XWindow,
+        data: *const c_char,
+        width: c_uint,
+        height: c_uint,
+    ) -> Pixmap;
+    pub fn XFreePixmap(display: *mut XDisplay, pixmap: Pixmap) -> c_int;
+
+    pub fn XGrabPointer(
+        display: *mut XDisplay,
+        grab_window: XWindow,
+        owner_events: Bool,
+        event_mask: c_uint,
+        pointer_mode: c_int,
+        keyboard_mode: c_int,
+        confine_to: XWindow,
+        cursor: Cursor,
+        time: Time,
+    ) -> c_int;
+    pub fn XUngrabPointer(display: *mut XDisplay, time: Time) -> c_int;
+    pub fn XWarpPointer(
+        display: *mut XDisplay,
+        src_w: XWindow,
+        dest_w: XWindow,
+        src_x: c_int,
+        src_y: c_int,
+        src_width: c_uint,
+        src_height: c_uint,
+        dest_x: c_int,
+        dest_y: c_int,
+    ) -> c_int;
+
+    // Extensions
+
+    pub fn XQueryExtension(
+        display: *mut XDisplay,
+        name: *const c_char,
+        major_opcode: *mut c_int,
+        first_event: *mut c_int,
+        first_error: *mut c_int,
+    ) -> Bool;
 
     // XKB
 
@@ -494,7 +707,9 @@ library! {
     pub fn XCreateIC(im: XIM, args: ...) -> XIC;
     pub fn XSetICFocus(ic: XIC);
     pub fn XUnsetICFocus(ic: XIC);
+    pub fn XDestroyIC(ic: XIC);
     pub fn XSetICValues(ic: XIC, args: ...) -> *const c_char;
+    pub fn Xutf8ResetIC(ic: XIC) -> *mut c_char;
     pub fn XCloseIM(im: XIM) -> Status;
 
     pub fn XSelectInput(display: *mut XDisplay, window: XWindow, event_mask: c_long);
@@ -0,0 +1,50 @@
+//! The Xcursor library, `libXcursor`, which loads cursors from the user's cursor theme.
+
+#![allow(non_snake_case)]
+
+use std::ffi::{c_char, c_int, c_uint};
+
+use super::{Cursor, XDisplay};
+use crate::library;
+
+/// An image of a cursor, created by [`LibXcursor::XcursorImageCreate`].
+#[repr(C)]
+pub struct XcursorImage {
+    pub version: c_uint,
+    /// The nominal size, which themes use to pick an image.
+    pub size: c_uint,
+    pub width: c_uint,
+    pub height: c_uint,
+    pub xhot: c_uint,
+    pub yhot: c_uint,
+    /// How long the image is shown for in an animated cursor, in milliseconds.
+    pub delay: c_uint,
+    /// `width * height` premultiplied ARGB pixels, allocated along with the image.
+    pub pixels: *mut u32,
+}
+
+/// The frames of an animated cursor, created by [`LibXcursor::XcursorImagesCreate`].
+#[repr(C)]
+pub struct XcursorImages {
+    /// How many of `images` are set.
+    pub nimage: c_int,
+    pub images: *mut *mut XcursorImage,
+    pub name: *mut c_char,
+}
+
+library! {
+    [LibXcursor <-> "Xcursor"];
+
+    // Returns `XID::NONE` if the theme doesn't have a cursor with this name
+    pub fn XcursorLibraryLoadCursor(display: *mut XDisplay, name: *const c_char) -> Cursor;
+
+    pub fn XcursorImageCreate(width: c_int, height: c_int) -> *mut XcursorImage;
+    pub fn XcursorImageDestroy(image: *mut XcursorImage);
+    // Has room for `size` images, which it takes ownership of once they're added to `images`
+    pub fn XcursorImagesCreate(size: c_int) -> *mut XcursorImages;
+    pub fn XcursorImagesDestroy(images: *mut XcursorImages);
+
+    pub fn XcursorImageLoadCursor(display: *mut XDisplay, image: *const XcursorImage) -> Cursor;
+    // Makes an animated cursor, if the server supports them, or a cursor of the first image
+    pub fn XcursorImagesLoadCursor(display: *mut XDisplay, images: *const XcursorImages) -> Cursor;
+}
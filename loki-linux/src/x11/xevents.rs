@@ -1,8 +1,30 @@
-use std::ffi::{c_char, c_int, c_long, c_short, c_uint, c_ulong};
+use std::ffi::{c_char, c_int, c_long, c_short, c_uint, c_ulong, c_void};
 use std::fmt::Debug;
 
 use super::{Atom, Bool, Colormap, Drawable, Time, XDisplay, XWindow, XID};
 
+/// An event sent by an extension, such as XInput2. Its data has to be fetched with
+/// `XGetEventData`, then freed with `XFreeEventData`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct XGenericEventCookie {
+    /// GenericEvent
+    pub type_id: c_int,
+    /// \# of last request processed by server
+    pub serial: c_ulong,
+    /// true if this came from a SendEvent request
+    pub send_event: Bool,
+    /// Display the event was read from
+    pub display: *mut XDisplay,
+    /// major opcode of the extension that sent the event
+    pub extension: c_int,
+    /// the extension's event type
+    pub evtype: c_int,
+    pub cookie: c_uint,
+    /// the event's data, once fetched
+    pub data: *mut c_void,
+}
+
 #[repr(C)]
 #[derive(Clone, Copy, Debug)]
 pub struct XAnyEvent {
@@ -736,6 +758,7 @@ pub mod et {
 #[derive(Clone, Copy)]
 pub union XEvent {
     pub type_id: c_int,
+    pub xcookie: XGenericEventCookie,
     pub xany: XAnyEvent,
     pub xkey: XKeyEvent,
     pub xbutton: XButtonEvent,
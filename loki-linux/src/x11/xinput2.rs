@@ -0,0 +1,248 @@
+//! The XInput2 extension, from `libXi`. Its events arrive as `GenericEvent`s, whose data is a
+//! [`XIDeviceEvent`] for device events.
+
+#![allow(non_snake_case, clippy::upper_case_acronyms)]
+
+use std::ffi::{c_char, c_double, c_int, c_uchar, c_ulong};
+
+use super::{Atom, Bool, Status, Time, XDisplay, XWindow};
+use crate::library;
+
+/// The name to give to `XQueryExtension`.
+pub const EXTENSION_NAME: &[u8] = b"XInputExtension\0";
+
+pub const XI_ALL_DEVICES: c_int = 0;
+pub const XI_ALL_MASTER_DEVICES: c_int = 1;
+
+/// Event types, for [`XIEventMask`]s and the `evtype` of event cookies.
+pub mod xi {
+    use std::ffi::c_int;
+
+    pub const DEVICE_CHANGED: c_int = 1;
+    pub const KEY_PRESS: c_int = 2;
+    pub const KEY_RELEASE: c_int = 3;
+    pub const BUTTON_PRESS: c_int = 4;
+    pub const BUTTON_RELEASE: c_int = 5;
+    pub const MOTION: c_int = 6;
+    pub const ENTER: c_int = 7;
+    pub const LEAVE: c_int = 8;
+    pub const FOCUS_IN: c_int = 9;
+    pub const FOCUS_OUT: c_int = 10;
+    pub const HIERARCHY_CHANGED: c_int = 11;
+    pub const PROPERTY_EVENT: c_int = 12;
+    pub const RAW_KEY_PRESS: c_int = 13;
+    pub const RAW_KEY_RELEASE: c_int = 14;
+    pub const RAW_BUTTON_PRESS: c_int = 15;
+    pub const RAW_BUTTON_RELEASE: c_int = 16;
+    pub const RAW_MOTION: c_int = 17;
+    pub const TOUCH_BEGIN: c_int = 18;
+    pub const TOUCH_UPDATE: c_int = 19;
+    pub const TOUCH_END: c_int = 20;
+    pub const TOUCH_OWNERSHIP: c_int = 21;
+    pub const RAW_TOUCH_BEGIN: c_int = 22;
+    pub const RAW_TOUCH_UPDATE: c_int = 23;
+    pub const RAW_TOUCH_END: c_int = 24;
+    pub const BARRIER_HIT: c_int = 25;
+    pub const BARRIER_LEAVE: c_int = 26;
+
+    /// must be bigger than any event #
+    pub const LAST_EVENT: c_int = BARRIER_LEAVE;
+}
+
+/// Device class types, in [`XIAnyClassInfo::type_`].
+pub mod xi_class {
+    use std::ffi::c_int;
+
+    pub const KEY: c_int = 0;
+    pub const BUTTON: c_int = 1;
+    pub const VALUATOR: c_int = 2;
+    pub const SCROLL: c_int = 3;
+    pub const TOUCH: c_int = 8;
+}
+
+pub const XI_SCROLL_TYPE_VERTICAL: c_int = 1;
+pub const XI_SCROLL_TYPE_HORIZONTAL: c_int = 2;
+
+/// Set in the flags of events emulated from another kind of input, like wheel buttons emulated
+/// from scroll valuators.
+pub const XI_POINTER_EMULATED: c_int = 1 << 16;
+
+/// The length of an event mask that can hold every event type.
+pub const fn mask_len() -> usize {
+    (xi::LAST_EVENT as usize >> 3) + 1
+}
+
+/// Sets the bit of an event type in an event mask.
+pub fn set_mask(mask: &mut [c_uchar], event: c_int) {
+    mask[event as usize >> 3] |= 1 << (event & 7);
+}
+
+/// Whether the bit of a valuator is set in a valuator mask.
+///
+/// # Safety
+///
+/// `mask` must point to at least `mask_len` bytes.
+pub unsafe fn mask_is_set(mask: *const c_uchar, mask_len: c_int, bit: c_int) -> bool {
+    bit >= 0 && (bit >> 3) < mask_len && *mask.add(bit as usize >> 3) & (1 << (bit & 7)) != 0
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct XIEventMask {
+    pub deviceid: c_int,
+    pub mask_len: c_int,
+    pub mask: *mut c_uchar,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct XIAnyClassInfo {
+    pub type_: c_int,
+    pub sourceid: c_int,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct XIValuatorClassInfo {
+    pub type_: c_int,
+    pub sourceid: c_int,
+    pub number: c_int,
+    pub label: Atom,
+    pub min: c_double,
+    pub max: c_double,
+    pub value: c_double,
+    pub resolution: c_int,
+    pub mode: c_int,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct XIScrollClassInfo {
+    pub type_: c_int,
+    pub sourceid: c_int,
+    /// the valuator that scrolls
+    pub number: c_int,
+    pub scroll_type: c_int,
+    /// how much the valuator changes for one scroll unit, like a wheel notch
+    pub increment: c_double,
+    pub flags: c_int,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct XIDeviceInfo {
+    pub deviceid: c_int,
+    pub name: *mut c_char,
+    pub use_: c_int,
+    pub attachment: c_int,
+    pub enabled: Bool,
+    pub num_classes: c_int,
+    pub classes: *mut *mut XIAnyClassInfo,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct XIButtonState {
+    pub mask_len: c_int,
+    pub mask: *mut c_uchar,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct XIValuatorState {
+    pub mask_len: c_int,
+    pub mask: *mut c_uchar,
+    /// the values of the valuators set in `mask`, in order
+    pub values: *mut c_double,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct XIModifierState {
+    pub base: c_int,
+    pub latched: c_int,
+    pub locked: c_int,
+    pub effective: c_int,
+}
+
+pub type XIGroupState = XIModifierState;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct XIDeviceEvent {
+    /// GenericEvent
+    pub type_id: c_int,
+    /// \# of last request processed by server
+    pub serial: c_ulong,
+    /// true if this came from a SendEvent request
+    pub send_event: Bool,
+    /// Display the event was read from
+    pub display: *mut XDisplay,
+    /// XI extension offset
+    pub extension: c_int,
+    pub evtype: c_int,
+    pub time: Time,
+    /// the master device
+    pub deviceid: c_int,
+    /// the physical device that caused the event
+    pub sourceid: c_int,
+    /// the key or button
+    pub detail: c_int,
+    pub root: XWindow,
+    pub event: XWindow,
+    pub child: XWindow,
+    pub root_x: c_double,
+    pub root_y: c_double,
+    pub event_x: c_double,
+    pub event_y: c_double,
+    pub flags: c_int,
+    pub buttons: XIButtonState,
+    pub valuators: XIValuatorState,
+    pub mods: XIModifierState,
+    pub group: XIGroupState,
+}
+
+/// A raw event, such as `XI_RawMotion`, which is only sent to the root window.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct XIRawEvent {
+    /// GenericEvent
+    pub type_id: c_int,
+    /// \# of last request processed by server
+    pub serial: c_ulong,
+    /// true if this came from a SendEvent request
+    pub send_event: Bool,
+    /// Display the event was read from
+    pub display: *mut XDisplay,
+    /// XI extension offset
+    pub extension: c_int,
+    pub evtype: c_int,
+    pub time: Time,
+    pub deviceid: c_int,
+    /// the physical device that caused the event
+    pub sourceid: c_int,
+    pub detail: c_int,
+    pub flags: c_int,
+    /// the valuators as transformed by the server, without pointer acceleration
+    pub valuators: XIValuatorState,
+    /// the valuators as reported by the device, in the same order as `valuators.values`
+    pub raw_values: *mut c_double,
+}
+
+library! {
+    [LibXi <-> "Xi"];
+
+    pub fn XIQueryVersion(display: *mut XDisplay, major: *mut c_int, minor: *mut c_int) -> Status;
+    pub fn XISelectEvents(
+        display: *mut XDisplay,
+        window: XWindow,
+        masks: *mut XIEventMask,
+        num_masks: c_int,
+    ) -> Status;
+    pub fn XIQueryDevice(
+        display: *mut XDisplay,
+        deviceid: c_int,
+        ndevices: *mut c_int,
+    ) -> *mut XIDeviceInfo;
+    pub fn XIFreeDeviceInfo(info: *mut XIDeviceInfo);
+}
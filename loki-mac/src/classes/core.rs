@@ -119,6 +119,8 @@ mod ffi {
         #[selector = "distantPast"]
         fn distant_past() -> Self;
         fn now() -> Self;
+        #[selector = "dateWithTimeIntervalSinceNow:"]
+        fn from_now(seconds: f64) -> Self;
     }
 
     /// https://developer.apple.com/documentation/foundation/nsrunloopmode?language=objc
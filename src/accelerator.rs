@@ -0,0 +1,351 @@
+//! Keyboard shortcuts, such as `Ctrl+Shift+P`.
+//!
+//! ```
+//! use lokinit::prelude::*;
+//!
+//! let palette: Accelerator = "Ctrl+Shift+P".parse().unwrap();
+//! assert_eq!(palette.to_string(), "Ctrl+Shift+P");
+//!
+//! // The modifiers of the press, or the ones from the last `ModifiersChanged` event
+//! let modifiers = Modifiers::CTRL | Modifiers::SHIFT | Modifiers::NUM_LOCK;
+//! let event = KeyboardEvent::KeyPress(KeyCode::P, ScanCode(25), modifiers);
+//! assert!(palette.matches(&event, modifiers));
+//!
+//! // Ctrl on Linux and Windows, Command on macOS
+//! let save: Accelerator = "Primary+S".parse().unwrap();
+//! assert_eq!(save.modifiers, Modifiers::PRIMARY);
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::event::{KeyboardEvent, Modifiers};
+use crate::keycode::KeyCode;
+
+/// The modifiers that take part in shortcuts. Lock modifiers are ignored, so that shortcuts still
+/// work with Caps Lock or Num Lock on.
+const SHORTCUT_MODIFIERS: Modifiers = Modifiers::SHIFT
+    .union(Modifiers::CTRL)
+    .union(Modifiers::ALT)
+    .union(Modifiers::SUPER);
+
+/// A keyboard shortcut: a key, and the modifiers to hold while pressing it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Accelerator {
+    pub modifiers: Modifiers,
+    pub key: KeyCode,
+}
+
+impl Accelerator {
+    pub fn new(modifiers: Modifiers, key: KeyCode) -> Self {
+        Self { modifiers, key }
+    }
+
+    /// Whether a key press or repeat triggers this shortcut, with the given modifiers held.
+    ///
+    /// Left and right modifier keys are interchangeable, both as modifiers and as the key itself:
+    /// `Ctrl+Shift` matches pressing `RShift` while holding `RCtrl`. Caps Lock and Num Lock are
+    /// ignored.
+    pub fn matches(&self, event: &KeyboardEvent, modifiers: Modifiers) -> bool {
+        let key = match event {
+            KeyboardEvent::KeyPress(key, _, _) | KeyboardEvent::KeyRepeat(key, _, _) => *key,
+            _ => return false,
+        };
+
+        canonical_key(key) == canonical_key(self.key)
+            && modifiers.intersection(SHORTCUT_MODIFIERS)
+                == self.modifiers.intersection(SHORTCUT_MODIFIERS)
+    }
+}
+
+impl fmt::Display for Accelerator {
+    /// Formats the shortcut like `Ctrl+Shift+P`, in a way [`FromStr`] can parse back.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (flag, name) in [
+            (Modifiers::CTRL, "Ctrl"),
+            (Modifiers::ALT, "Alt"),
+            (Modifiers::SHIFT, "Shift"),
+            (Modifiers::SUPER, "Super"),
+        ] {
+            if self.modifiers.contains(flag) {
+                write!(f, "{name}+")?;
+            }
+        }
+
+        match self.key {
+            KeyCode::Key0 => write!(f, "0"),
+            KeyCode::Key1 => write!(f, "1"),
+            KeyCode::Key2 => write!(f, "2"),
+            KeyCode::Key3 => write!(f, "3"),
+            KeyCode::Key4 => write!(f, "4"),
+            KeyCode::Key5 => write!(f, "5"),
+            KeyCode::Key6 => write!(f, "6"),
+            KeyCode::Key7 => write!(f, "7"),
+            KeyCode::Key8 => write!(f, "8"),
+            KeyCode::Key9 => write!(f, "9"),
+            key => write!(f, "{key:?}"),
+        }
+    }
+}
+
+impl FromStr for Accelerator {
+    type Err = AcceleratorError;
+
+    /// Parses a shortcut like `Ctrl+Shift+P` or `Super+Enter`.
+    ///
+    /// Modifiers are case-insensitive, and accept a few aliases: `Control`, `Option`, `Meta`,
+    /// `Win`, `Cmd` and `Command`. `Primary` (or `CmdOrCtrl`) stands for [`Modifiers::PRIMARY`].
+    ///
+    /// The key is the name of a [`KeyCode`] variant, a single letter or digit, or one of `Esc`,
+    /// `Return`, `Del`, `Ins`, `Up`, `Down`, `Left`, `Right`, `PgUp` and `PgDn`. Since `+`
+    /// separates keys, `Ctrl++` is read as `Ctrl+Plus`. The key can also be a modifier, which
+    /// stands for its left key: `Ctrl+Shift` is read as `Ctrl+LShift`.
+    fn from_str(accelerator: &str) -> Result<Self, Self::Err> {
+        let accelerator = accelerator.trim();
+        let (modifiers, key) = match accelerator.strip_suffix("++") {
+            Some(modifiers) => (modifiers, "+"),
+            None => match accelerator.rsplit_once('+') {
+                Some((modifiers, key)) => (modifiers, key),
+                None => ("", accelerator),
+            },
+        };
+
+        let mut flags = Modifiers::empty();
+        for modifier in modifiers.split('+').filter(|modifier| !modifier.is_empty()) {
+            flags |= parse_modifier(modifier.trim())
+                .ok_or_else(|| AcceleratorError::UnknownModifier(modifier.into()))?;
+        }
+
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(AcceleratorError::MissingKey);
+        }
+        let key = parse_key(key).ok_or_else(|| AcceleratorError::UnknownKey(key.into()))?;
+
+        Ok(Self::new(flags, key))
+    }
+}
+
+/// Why an [`Accelerator`] couldn't be parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AcceleratorError {
+    /// The shortcut only has modifiers, or nothing at all.
+    MissingKey,
+    UnknownModifier(String),
+    UnknownKey(String),
+}
+
+impl fmt::Display for AcceleratorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingKey => write!(f, "The shortcut has no key"),
+            Self::UnknownModifier(modifier) => write!(f, "Unknown modifier `{modifier}`"),
+            Self::UnknownKey(key) => write!(f, "Unknown key `{key}`"),
+        }
+    }
+}
+
+impl Error for AcceleratorError {}
+
+fn parse_modifier(modifier: &str) -> Option<Modifiers> {
+    Some(match modifier.to_ascii_lowercase().as_str() {
+        "ctrl" | "control" => Modifiers::CTRL,
+        "shift" => Modifiers::SHIFT,
+        "alt" | "option" => Modifiers::ALT,
+        "super" | "meta" | "win" | "cmd" | "command" => Modifiers::SUPER,
+        "primary" | "cmdorctrl" => Modifiers::PRIMARY,
+        _ => return None,
+    })
+}
+
+fn parse_key(key: &str) -> Option<KeyCode> {
+    if let Ok(key) = key.parse() {
+        return Some(key);
+    }
+
+    let mut chars = key.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return match c.to_ascii_uppercase() {
+            '0'..='9' => format!("Key{c}").parse().ok(),
+            c @ 'A'..='Z' => c.to_string().parse().ok(),
+            '+' => Some(KeyCode::Plus),
+            '-' => Some(KeyCode::Minus),
+            '=' => Some(KeyCode::Equals),
+            ',' => Some(KeyCode::Comma),
+            '.' => Some(KeyCode::Point),
+            '/' => Some(KeyCode::Slash),
+            '\\' => Some(KeyCode::Backslash),
+            ';' => Some(KeyCode::Semicolon),
+            '\'' => Some(KeyCode::SingleQuote),
+            '`' => Some(KeyCode::Backtick),
+            '[' => Some(KeyCode::LBracket),
+            ']' => Some(KeyCode::RBracket),
+            _ => None,
+        };
+    }
+
+    Some(match key.to_ascii_lowercase().as_str() {
+        "esc" => KeyCode::Escape,
+        "return" => KeyCode::Enter,
+        "del" => KeyCode::Delete,
+        "ins" => KeyCode::Insert,
+        "up" => KeyCode::ArrowUp,
+        "down" => KeyCode::ArrowDown,
+        "left" => KeyCode::ArrowLeft,
+        "right" => KeyCode::ArrowRight,
+        "pgup" => KeyCode::PageUp,
+        "pgdn" => KeyCode::PageDown,
+        "ctrl" | "control" => KeyCode::LCtrl,
+        "shift" => KeyCode::LShift,
+        "alt" | "option" => KeyCode::LAlt,
+        "super" | "meta" | "win" | "cmd" | "command" => KeyCode::LSuper,
+        _ => return None,
+    })
+}
+
+/// Maps right-hand modifier keys and platform-specific names to a single key, so that they match
+/// each other.
+fn canonical_key(key: KeyCode) -> KeyCode {
+    match key {
+        KeyCode::RShift => KeyCode::LShift,
+        KeyCode::RCtrl => KeyCode::LCtrl,
+        KeyCode::RAlt => KeyCode::LAlt,
+        KeyCode::RSuper | KeyCode::LWin | KeyCode::RWin | KeyCode::LCommand | KeyCode::RCommand => {
+            KeyCode::LSuper
+        }
+        key => key,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keycode::ScanCode;
+
+    fn parse(accelerator: &str) -> Result<Accelerator, AcceleratorError> {
+        accelerator.parse()
+    }
+
+    fn press(key: KeyCode) -> KeyboardEvent {
+        KeyboardEvent::KeyPress(key, ScanCode(0), Modifiers::empty())
+    }
+
+    #[test]
+    fn parses_modifiers_and_key() {
+        assert_eq!(
+            parse("Ctrl+Shift+P"),
+            Ok(Accelerator::new(
+                Modifiers::CTRL | Modifiers::SHIFT,
+                KeyCode::P
+            ))
+        );
+        assert_eq!(
+            parse(" control + Option+k "),
+            Ok(Accelerator::new(
+                Modifiers::CTRL | Modifiers::ALT,
+                KeyCode::K
+            ))
+        );
+        assert_eq!(
+            parse("Cmd+Enter"),
+            Ok(Accelerator::new(Modifiers::SUPER, KeyCode::Enter))
+        );
+        assert_eq!(
+            parse("Primary+S"),
+            Ok(Accelerator::new(Modifiers::PRIMARY, KeyCode::S))
+        );
+        assert_eq!(
+            parse("F5"),
+            Ok(Accelerator::new(Modifiers::empty(), KeyCode::F5))
+        );
+    }
+
+    #[test]
+    fn parses_key_aliases() {
+        assert_eq!(parse("Alt+1").unwrap().key, KeyCode::Key1);
+        assert_eq!(parse("Esc").unwrap().key, KeyCode::Escape);
+        assert_eq!(parse("Shift+PgDn").unwrap().key, KeyCode::PageDown);
+        assert_eq!(parse("Ctrl+/").unwrap().key, KeyCode::Slash);
+        assert_eq!(parse("Ctrl++").unwrap().key, KeyCode::Plus);
+        assert_eq!(parse("Ctrl+-").unwrap().key, KeyCode::Minus);
+    }
+
+    #[test]
+    fn parses_modifier_as_key() {
+        assert_eq!(
+            parse("Ctrl+Shift"),
+            Ok(Accelerator::new(Modifiers::CTRL, KeyCode::LShift))
+        );
+        assert_eq!(
+            parse("Alt"),
+            Ok(Accelerator::new(Modifiers::empty(), KeyCode::LAlt))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_shortcuts() {
+        assert_eq!(parse(""), Err(AcceleratorError::MissingKey));
+        assert_eq!(parse("Ctrl+"), Err(AcceleratorError::MissingKey));
+        assert_eq!(
+            parse("Hyper+A"),
+            Err(AcceleratorError::UnknownModifier("Hyper".into()))
+        );
+        assert_eq!(
+            parse("Ctrl+Foo"),
+            Err(AcceleratorError::UnknownKey("Foo".into()))
+        );
+    }
+
+    #[test]
+    fn display_parses_back() {
+        for accelerator in [
+            "Ctrl+Shift+P",
+            "Alt+1",
+            "Super+Enter",
+            "Ctrl+Plus",
+            "Ctrl+LShift",
+        ] {
+            let parsed = parse(accelerator).unwrap();
+            assert_eq!(parsed.to_string(), accelerator);
+            assert_eq!(parse(&parsed.to_string()), Ok(parsed));
+        }
+    }
+
+    #[test]
+    fn matches_exact_modifiers() {
+        let accelerator = parse("Ctrl+Shift+P").unwrap();
+        let modifiers = Modifiers::CTRL | Modifiers::SHIFT;
+
+        assert!(accelerator.matches(&press(KeyCode::P), modifiers));
+        assert!(accelerator.matches(
+            &KeyboardEvent::KeyRepeat(KeyCode::P, ScanCode(0), modifiers),
+            modifiers
+        ));
+        assert!(!accelerator.matches(&press(KeyCode::P), Modifiers::CTRL));
+        assert!(!accelerator.matches(&press(KeyCode::P), modifiers | Modifiers::ALT));
+        assert!(!accelerator.matches(&press(KeyCode::O), modifiers));
+        assert!(!accelerator.matches(
+            &KeyboardEvent::KeyRelease(KeyCode::P, ScanCode(0), modifiers),
+            modifiers
+        ));
+    }
+
+    #[test]
+    fn matches_ignore_lock_modifiers() {
+        let accelerator = parse("Ctrl+S").unwrap();
+        let modifiers = Modifiers::CTRL | Modifiers::CAPS_LOCK | Modifiers::NUM_LOCK;
+        assert!(accelerator.matches(&press(KeyCode::S), modifiers));
+    }
+
+    #[test]
+    fn matches_either_side_of_modifier_keys() {
+        let accelerator = parse("Ctrl+Shift").unwrap();
+        assert!(accelerator.matches(&press(KeyCode::RShift), Modifiers::CTRL));
+        assert!(accelerator.matches(&press(KeyCode::LShift), Modifiers::CTRL));
+        assert!(!accelerator.matches(&press(KeyCode::RShift), Modifiers::empty()));
+
+        let accelerator = parse("Super").unwrap();
+        assert!(accelerator.matches(&press(KeyCode::RWin), Modifiers::empty()));
+    }
+}
@@ -0,0 +1,195 @@
+//! Cursor appearance.
+
+use std::time::Duration;
+
+use crate::error::LokinitError;
+
+/// A cursor from the system's cursor theme, set with
+/// [`WindowHandle::set_cursor_icon`](crate::window::WindowHandle::set_cursor_icon). The icons
+/// follow the CSS `cursor` property, and are named after its values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum CursorIcon {
+    /// The platform's default cursor, usually an arrow.
+    #[default]
+    Default,
+    /// A link or a button.
+    Pointer,
+    /// Text that can be selected.
+    Text,
+    Crosshair,
+    Help,
+    /// The app is busy, and the window can't be interacted with.
+    Wait,
+    /// The app is busy, but the window can still be interacted with.
+    Progress,
+    NotAllowed,
+    /// Something that can be moved.
+    Move,
+    /// Something that can be grabbed and dragged.
+    Grab,
+    /// Something that is being dragged.
+    Grabbing,
+    NResize,
+    EResize,
+    SResize,
+    WResize,
+    NeResize,
+    NwResize,
+    SeResize,
+    SwResize,
+    EwResize,
+    NsResize,
+    NeswResize,
+    NwseResize,
+    ColResize,
+    RowResize,
+}
+
+impl CursorIcon {
+    /// The CSS name of the icon, which is also its name in cursor themes.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::Pointer => "pointer",
+            Self::Text => "text",
+            Self::Crosshair => "crosshair",
+            Self::Help => "help",
+            Self::Wait => "wait",
+            Self::Progress => "progress",
+            Self::NotAllowed => "not-allowed",
+            Self::Move => "move",
+            Self::Grab => "grab",
+            Self::Grabbing => "grabbing",
+            Self::NResize => "n-resize",
+            Self::EResize => "e-resize",
+            Self::SResize => "s-resize",
+            Self::WResize => "w-resize",
+            Self::NeResize => "ne-resize",
+            Self::NwResize => "nw-resize",
+            Self::SeResize => "se-resize",
+            Self::SwResize => "sw-resize",
+            Self::EwResize => "ew-resize",
+            Self::NsResize => "ns-resize",
+            Self::NeswResize => "nesw-resize",
+            Self::NwseResize => "nwse-resize",
+            Self::ColResize => "col-resize",
+            Self::RowResize => "row-resize",
+        }
+    }
+
+    /// The names older cursor themes use for the icon, from before they adopted the CSS names.
+    pub const fn legacy_names(self) -> &'static [&'static str] {
+        match self {
+            Self::Default => &["left_ptr", "arrow"],
+            Self::Pointer => &["hand2", "hand"],
+            Self::Text => &["xterm", "ibeam"],
+            Self::Crosshair => &["cross", "tcross"],
+            Self::Help => &["question_arrow", "whats_this"],
+            Self::Wait => &["watch"],
+            Self::Progress => &["left_ptr_watch", "half-busy"],
+            Self::NotAllowed => &["crossed_circle", "forbidden"],
+            Self::Move => &["fleur", "size_all"],
+            Self::Grab => &["openhand", "hand1"],
+            Self::Grabbing => &["closedhand", "fleur"],
+            Self::NResize => &["top_side"],
+            Self::EResize => &["right_side"],
+            Self::SResize => &["bottom_side"],
+            Self::WResize => &["left_side"],
+            Self::NeResize => &["top_right_corner"],
+            Self::NwResize => &["top_left_corner"],
+            Self::SeResize => &["bottom_right_corner"],
+            Self::SwResize => &["bottom_left_corner"],
+            Self::EwResize => &["sb_h_double_arrow", "h_double_arrow", "size_hor"],
+            Self::NsResize => &["sb_v_double_arrow", "v_double_arrow", "size_ver"],
+            Self::NeswResize => &["fd_double_arrow", "size_bdiag"],
+            Self::NwseResize => &["bd_double_arrow", "size_fdiag"],
+            Self::ColResize => &["split_h", "sb_h_double_arrow"],
+            Self::RowResize => &["split_v", "sb_v_double_arrow"],
+        }
+    }
+}
+
+/// A cursor made from images, created with [`lok::create_cursor`](crate::lok::create_cursor) or
+/// [`lok::create_animated_cursor`](crate::lok::create_animated_cursor) and set with
+/// [`WindowHandle::set_custom_cursor`](crate::window::WindowHandle::set_custom_cursor).
+///
+/// The cursor stays alive until it's destroyed with
+/// [`lok::destroy_cursor`](crate::lok::destroy_cursor), and can be shown by several windows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CustomCursor(pub(crate) u64);
+
+/// An image of a custom cursor, and how long it's shown for if the cursor is animated.
+#[derive(Clone, Copy, Debug)]
+pub struct CursorFrame<'a> {
+    rgba: &'a [u8],
+    width: u32,
+    height: u32,
+    hotspot: (u32, u32),
+    delay: Duration,
+}
+
+impl<'a> CursorFrame<'a> {
+    /// Creates a frame from the pixels of an image, in rows from top to bottom, with 4 bytes per
+    /// pixel in RGBA order. The alpha isn't premultiplied. The hotspot is the pixel that points
+    /// at things.
+    ///
+    /// Fails if the image is empty, if `rgba` doesn't have `width * height * 4` bytes, or if the
+    /// hotspot is outside of the image.
+    pub fn new(
+        rgba: &'a [u8],
+        width: u32,
+        height: u32,
+        hotspot: (u32, u32),
+        delay: Duration,
+    ) -> Result<Self, LokinitError> {
+        if width == 0 || height == 0 {
+            return Err(LokinitError::InvalidCursorImage("the image is empty"));
+        }
+        let len = (width as usize)
+            .checked_mul(height as usize)
+            .and_then(|len| len.checked_mul(4));
+        if len != Some(rgba.len()) {
+            return Err(LokinitError::InvalidCursorImage(
+                "the image doesn't have 4 bytes per pixel",
+            ));
+        }
+        if hotspot.0 >= width || hotspot.1 >= height {
+            return Err(LokinitError::InvalidCursorImage(
+                "the hotspot is outside of the image",
+            ));
+        }
+
+        Ok(Self {
+            rgba,
+            width,
+            height,
+            hotspot,
+            delay,
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn hotspot(&self) -> (u32, u32) {
+        self.hotspot
+    }
+
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    /// The frame's pixels as premultiplied ARGB, the format X11 and Wayland cursors use.
+    pub(crate) fn argb_pixels(&self) -> impl Iterator<Item = u32> + 'a {
+        self.rgba.as_chunks::<4>().0.iter().map(|pixel| {
+            let [r, g, b, a] = pixel.map(u32::from);
+            let premultiply = |channel: u32| (channel * a + 127) / 255;
+            a << 24 | premultiply(r) << 16 | premultiply(g) << 8 | premultiply(b)
+        })
+    }
+}
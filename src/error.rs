@@ -0,0 +1,69 @@
+//! Lokinit's error type.
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::rc::Rc;
+
+#[cfg(target_os = "linux")]
+use loki_linux::LoadingError;
+
+use crate::cursor::CustomCursor;
+use crate::window::WindowHandle;
+
+/// An error reported by a Lokinit backend.
+#[derive(Clone, Debug)]
+pub enum LokinitError {
+    /// A system library couldn't be loaded, or is missing a function.
+    #[cfg(target_os = "linux")]
+    LibLoading(LoadingError),
+    /// Communicating with the OS' display server failed.
+    Io(Rc<io::Error>),
+    /// The OS' display server reported an error, or sent something that doesn't make sense.
+    /// The connection to it is usually unusable afterwards.
+    Protocol(Rc<str>),
+    /// The OS' display server refused to create a window.
+    CreateWindow(Rc<str>),
+    /// The OS' display server refused to grab the cursor.
+    GrabCursor(&'static str),
+    /// The backend doesn't support this operation.
+    Unsupported(&'static str),
+    /// The window handle doesn't refer to an open window.
+    InvalidWindow(WindowHandle),
+    /// The cursor doesn't refer to a custom cursor that is still alive.
+    InvalidCursor(CustomCursor),
+    /// The image of a custom cursor isn't valid.
+    InvalidCursorImage(&'static str),
+}
+
+impl fmt::Display for LokinitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            #[cfg(target_os = "linux")]
+            Self::LibLoading(error) => write!(f, "Failed to load a system library: {error}"),
+            Self::Io(error) => write!(f, "I/O error: {error}"),
+            Self::Protocol(msg) => write!(f, "Protocol error: {msg}"),
+            Self::CreateWindow(msg) => write!(f, "Failed to create a window: {msg}"),
+            Self::GrabCursor(reason) => write!(f, "Failed to grab the cursor: {reason}"),
+            Self::Unsupported(operation) => write!(f, "Unsupported operation: {operation}"),
+            Self::InvalidWindow(handle) => write!(f, "Invalid window handle: {handle:?}"),
+            Self::InvalidCursor(cursor) => write!(f, "Invalid custom cursor: {cursor:?}"),
+            Self::InvalidCursorImage(reason) => write!(f, "Invalid cursor image: {reason}"),
+        }
+    }
+}
+
+impl Error for LokinitError {}
+
+#[cfg(target_os = "linux")]
+impl From<LoadingError> for LokinitError {
+    fn from(value: LoadingError) -> Self {
+        Self::LibLoading(value)
+    }
+}
+
+impl From<io::Error> for LokinitError {
+    fn from(value: io::Error) -> Self {
+        Self::Io(Rc::new(value))
+    }
+}
@@ -1,7 +1,12 @@
+use std::fmt;
+use std::ops::{BitOr, BitOrAssign};
+#[cfg(unix)]
+use std::os::fd::RawFd;
 use std::path::PathBuf;
 use std::time::Duration;
 
-use crate::keycode::KeyCode;
+use crate::event_loop::{Interest, TimerId};
+use crate::keycode::{KeyCode, ScanCode};
 use crate::window::WindowHandle;
 
 #[derive(Clone, Debug)]
@@ -24,21 +29,185 @@ pub enum EventKind {
     FileHoveredCancelled(PathBuf),
 
     Keyboard(KeyboardEvent),
+    /// The set of active modifiers changed, for instance because a modifier key was pressed or
+    /// released, or because the window got the keyboard focus while some were held.
+    ModifiersChanged(Modifiers),
     Mouse(MouseEvent),
+    /// How far the mouse moved, in device units, before pointer acceleration. It keeps coming
+    /// when the cursor is stuck against the edge of the screen, which suits camera controls.
+    /// Enable it with [`WindowHandle::set_raw_mouse_motion`].
+    RawMouseMotion {
+        dx: f64,
+        dy: f64,
+    },
     Touch(TouchEvent),
 
     Redraw,
 
     FocusIn,
     FocusOut,
+
+    /// An event sent through an [`EventLoopProxy`](crate::event_loop::EventLoopProxy), with its payload.
+    /// It isn't tied to any window, so it is sent to [`WindowHandle::NONE`].
+    User(u64),
+    /// A timer created with [`lok::set_timer`](crate::lok::set_timer) expired.
+    /// It isn't tied to any window, so it is sent to [`WindowHandle::NONE`].
+    Timer(TimerId),
+    /// A file descriptor registered with [`lok::watch_fd`](crate::lok::watch_fd) is ready for the
+    /// given operations. It isn't tied to any window, so it is sent to [`WindowHandle::NONE`].
+    #[cfg(unix)]
+    FdReady(RawFd, Interest),
+}
+
+/// The modifiers held or locked when an event happened. Flags can be combined with `|`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Modifiers(u8);
+
+impl Modifiers {
+    pub const SHIFT: Self = Self(0b1);
+    pub const CTRL: Self = Self(0b10);
+    pub const ALT: Self = Self(0b100);
+    /// The Windows key, or Command on macOS.
+    pub const SUPER: Self = Self(0b1000);
+    pub const CAPS_LOCK: Self = Self(0b10000);
+    pub const NUM_LOCK: Self = Self(0b100000);
+
+    /// The modifier used by most shortcuts on this platform: Ctrl, or Command on macOS.
+    #[cfg(target_os = "macos")]
+    pub const PRIMARY: Self = Self::SUPER;
+    /// The modifier used by most shortcuts on this platform: Ctrl, or Command on macOS.
+    #[cfg(not(target_os = "macos"))]
+    pub const PRIMARY: Self = Self::CTRL;
+
+    const NAMES: [(Self, &'static str); 6] = [
+        (Self::SHIFT, "SHIFT"),
+        (Self::CTRL, "CTRL"),
+        (Self::ALT, "ALT"),
+        (Self::SUPER, "SUPER"),
+        (Self::CAPS_LOCK, "CAPS_LOCK"),
+        (Self::NUM_LOCK, "NUM_LOCK"),
+    ];
+
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// The flags set in either `self` or `other`. This is `|`, usable in constants.
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    /// The flags set in both `self` and `other`.
+    pub const fn intersection(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    /// Whether every flag of `other` is set.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn insert(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+
+    pub fn remove(&mut self, other: Self) {
+        self.0 &= !other.0;
+    }
+
+    /// The flag controlled by a modifier key, or no flag for other keys.
+    pub fn for_key(key: KeyCode) -> Self {
+        match key {
+            KeyCode::LShift | KeyCode::RShift => Self::SHIFT,
+            KeyCode::LCtrl | KeyCode::RCtrl => Self::CTRL,
+            KeyCode::LAlt | KeyCode::RAlt => Self::ALT,
+            KeyCode::LSuper
+            | KeyCode::RSuper
+            | KeyCode::LWin
+            | KeyCode::RWin
+            | KeyCode::LCommand
+            | KeyCode::RCommand => Self::SUPER,
+            KeyCode::CapsLock => Self::CAPS_LOCK,
+            KeyCode::NumLock => Self::NUM_LOCK,
+            _ => Self::empty(),
+        }
+    }
+
+    /// The names of the set flags, as spelled by their constants.
+    pub fn names(self) -> impl Iterator<Item = &'static str> {
+        Self::NAMES
+            .into_iter()
+            .filter(move |(flag, _)| self.contains(*flag))
+            .map(|(_, name)| name)
+    }
+
+    /// Parses a flag from the name of its constant.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::NAMES
+            .into_iter()
+            .find(|(_, flag_name)| *flag_name == name)
+            .map(|(flag, _)| flag)
+    }
 }
 
+impl BitOr for Modifiers {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for Modifiers {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl fmt::Debug for Modifiers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "Modifiers(empty)");
+        }
+
+        write!(f, "Modifiers(")?;
+        for (i, name) in self.names().enumerate() {
+            if i > 0 {
+                write!(f, " | ")?;
+            }
+            write!(f, "{name}")?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// Keyboard input. Key events carry both the logical key, which depends on the keyboard layout,
+/// and the physical [`ScanCode`], which doesn't.
+///
+/// They also carry the modifiers that were active right before the key changed, so pressing
+/// `LShift` doesn't report [`Modifiers::SHIFT`] yet: a [`EventKind::ModifiersChanged`] event
+/// follows instead.
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub enum KeyboardEvent {
-    KeyPress(KeyCode),
-    KeyRelease(KeyCode),
-    KeyRepeat(KeyCode),
+    KeyPress(KeyCode, ScanCode, Modifiers),
+    KeyRelease(KeyCode, ScanCode, Modifiers),
+    KeyRepeat(KeyCode, ScanCode, Modifiers),
+    /// Text to insert, either typed directly or finished composing with an input method.
     ImeCommit(String),
+    /// The text being composed with an input method changed. It should be shown inline, where
+    /// the text would be inserted, and replaces the previous preedit text. An empty `text` means
+    /// that composition ended.
+    ///
+    /// `cursor_range` is the byte range of the cursor in `text`, or `None` to hide the cursor.
+    /// It's empty when the cursor is a caret rather than a selection.
+    ImePreedit {
+        text: String,
+        cursor_range: Option<(usize, usize)>,
+    },
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
@@ -46,17 +215,59 @@ pub enum MouseButton {
     Left,
     Right,
     Middle,
+    /// The side button that navigates back, usually under the thumb.
+    Back,
+    /// The side button that navigates forward.
+    Forward,
     Other(u16),
 }
 
+/// Pointer input. Positions are in logical pixels from the window's top-left corner, with the
+/// sub-pixel precision the platform reports. Button events carry the modifiers held when the
+/// button changed, so that Ctrl+Click can be told apart from a plain click.
 #[derive(Clone, Copy, Debug)]
 pub enum MouseEvent {
-    ButtonPress(MouseButton, i32, i32),
-    ButtonRelease(MouseButton, i32, i32),
-    CursorMove(i32, i32),
-    CursorIn(i32, i32),
-    CursorOut(i32, i32),
-    Wheel(f64, f64),
+    ButtonPress(MouseButton, f64, f64, Modifiers),
+    ButtonRelease(MouseButton, f64, f64, Modifiers),
+    CursorMove(f64, f64),
+    CursorIn(f64, f64),
+    CursorOut(f64, f64),
+    Scroll(ScrollEvent),
+}
+
+/// How far a [`ScrollEvent`] scrolled, horizontally then vertically. The vertical delta is
+/// positive when scrolling up, and the horizontal one when scrolling right.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ScrollDelta {
+    /// Steps of a notched mouse wheel, which usually scroll a few lines each. High-resolution
+    /// wheels report fractions of steps.
+    Lines(f64, f64),
+    /// Pixels, from devices that scroll continuously such as touchpads.
+    Pixels(f64, f64),
+}
+
+/// Scrolling, from a mouse wheel or a touchpad.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ScrollEvent {
+    pub delta: ScrollDelta,
+    /// Continuous scrolling is `Started` when fingers touch the touchpad and `Ended` when they
+    /// leave it, which is when kinetic scrolling can take over. Wheel steps are always `Moved`.
+    pub phase: TouchPhase,
+    /// Whether the deltas go the opposite way of the physical motion, as with "natural"
+    /// scrolling. The deltas already account for it: this is only a hint, for example to scroll
+    /// a slider the way the fingers move.
+    pub inverted: bool,
+}
+
+impl ScrollEvent {
+    /// A step of a mouse wheel, which is never inverted and has no phase.
+    pub fn lines(x: f64, y: f64) -> Self {
+        Self {
+            delta: ScrollDelta::Lines(x, y),
+            phase: TouchPhase::Moved,
+            inverted: false,
+        }
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
@@ -69,7 +280,7 @@ pub enum TouchPhase {
 
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub struct TouchEvent {
-    phase: TouchPhase,
-    x: f64,
-    y: f64,
+    pub(crate) phase: TouchPhase,
+    pub(crate) x: f64,
+    pub(crate) y: f64,
 }
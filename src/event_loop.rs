@@ -0,0 +1,222 @@
+//! Types used to drive Lokinit's event loop and interact with it.
+
+use std::collections::VecDeque;
+use std::ops::{BitOr, BitOrAssign};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::event::{Event, EventKind};
+use crate::window::WindowHandle;
+
+/// How [`lok::run`](crate::lok::run) should wait once it has handled every pending event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum ControlFlow {
+    /// Sleep until the next event arrives.
+    #[default]
+    Wait,
+    /// Don't sleep, and loop again right away. Useful for apps that redraw continuously.
+    Poll,
+    /// Sleep until the next event arrives, or until the given instant is reached.
+    WaitUntil(Instant),
+    /// Stop the event loop, making [`lok::run`](crate::lok::run) return.
+    Exit,
+}
+
+/// Handles the events dispatched by [`lok::run_handler`](crate::lok::run_handler).
+///
+/// It is implemented for every `FnMut(Event, &mut ControlFlow)` closure, so a closure can be used
+/// directly when there's no need for [`EventHandler::about_to_wait`].
+pub trait EventHandler {
+    /// Handles an event. The control flow can be changed at any point to alter how the loop waits.
+    fn event(&mut self, event: Event, control: &mut ControlFlow);
+
+    /// Called once every pending event has been handled, right before the loop waits for more.
+    /// This is a good place to draw a frame.
+    fn about_to_wait(&mut self, _control: &mut ControlFlow) {}
+}
+
+impl<F: FnMut(Event, &mut ControlFlow)> EventHandler for F {
+    fn event(&mut self, event: Event, control: &mut ControlFlow) {
+        (self)(event, control)
+    }
+}
+
+/// Identifies a timer created with [`lok::set_timer`](crate::lok::set_timer).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TimerId(pub(crate) u64);
+
+struct Timer {
+    id: TimerId,
+    deadline: Instant,
+    /// The time between two expirations, if the timer repeats.
+    interval: Option<Duration>,
+}
+
+/// The shortest interval of a repeating timer.
+pub(crate) const MIN_TIMER_INTERVAL: Duration = Duration::from_millis(1);
+
+/// The timers created with [`lok::set_timer`](crate::lok::set_timer), shared by every backend.
+#[derive(Default)]
+pub(crate) struct Timers {
+    timers: Vec<Timer>,
+    next_id: u64,
+}
+
+impl Timers {
+    pub fn set(&mut self, duration: Duration, repeat: bool) -> TimerId {
+        let id = TimerId(self.next_id);
+        self.next_id += 1;
+
+        self.timers.push(Timer {
+            id,
+            deadline: Instant::now() + duration,
+            interval: repeat.then_some(duration.max(MIN_TIMER_INTERVAL)),
+        });
+
+        id
+    }
+
+    pub fn cancel(&mut self, timer: TimerId) {
+        self.timers.retain(|t| t.id != timer);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.timers.is_empty()
+    }
+
+    /// When the next timer expires, if there is one.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.timers.iter().map(|timer| timer.deadline).min()
+    }
+
+    /// Pushes an [`EventKind::Timer`] event for every timer that expired.
+    pub fn dispatch(&mut self, event_queue: &mut VecDeque<Event>) {
+        let now = Instant::now();
+        self.timers.retain_mut(|timer| {
+            if timer.deadline > now {
+                return true;
+            }
+
+            event_queue.push_back(Event {
+                time: Duration::ZERO,
+                window: WindowHandle::NONE,
+                kind: EventKind::Timer(timer.id),
+            });
+
+            match timer.interval {
+                Some(interval) => {
+                    // If we're late by more than one interval, skip the missed expirations
+                    // instead of sending a burst of events.
+                    timer.deadline += interval;
+                    if timer.deadline <= now {
+                        timer.deadline = now + interval;
+                    }
+                    true
+                }
+                None => false,
+            }
+        });
+    }
+}
+
+/// The readiness of a file descriptor registered with [`lok::watch_fd`](crate::lok::watch_fd).
+/// Flags can be combined with `|`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Interest(u8);
+
+impl Interest {
+    pub const READABLE: Self = Self(0b01);
+    pub const WRITABLE: Self = Self(0b10);
+
+    pub fn is_readable(self) -> bool {
+        self.0 & Self::READABLE.0 != 0
+    }
+
+    pub fn is_writable(self) -> bool {
+        self.0 & Self::WRITABLE.0 != 0
+    }
+}
+
+impl BitOr for Interest {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for Interest {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// A handle to Lokinit's event loop that can be sent to other threads.
+///
+/// It can wake up a thread blocked in [`lok::poll_event`](crate::lok::poll_event), and hand it
+/// [`EventKind::User`](crate::event::EventKind::User) events.
+#[derive(Clone)]
+pub struct EventLoopProxy {
+    shared: Arc<ProxyShared>,
+}
+
+struct ProxyShared {
+    events: Mutex<VecDeque<u64>>,
+    wake: Box<dyn Fn() + Send + Sync>,
+}
+
+impl EventLoopProxy {
+    /// Creates a proxy that calls `wake` to interrupt the backend's event loop.
+    pub(crate) fn new(wake: impl Fn() + Send + Sync + 'static) -> Self {
+        Self {
+            shared: Arc::new(ProxyShared {
+                events: Mutex::new(VecDeque::new()),
+                wake: Box::new(wake),
+            }),
+        }
+    }
+
+    /// Queues an [`EventKind::User`](crate::event::EventKind::User) event with the given payload,
+    /// and wakes up the event loop so it can deliver it.
+    pub fn send_event(&self, payload: u64) {
+        self.shared.events.lock().unwrap().push_back(payload);
+        (self.shared.wake)();
+    }
+
+    /// Whether a clone of this proxy exists, which means that events can still be sent to it.
+    pub(crate) fn is_shared(&self) -> bool {
+        Arc::strong_count(&self.shared) > 1
+    }
+
+    /// Pushes an [`EventKind::User`] event for every payload that was sent since the last call,
+    /// in order.
+    pub(crate) fn dispatch(&self, event_queue: &mut VecDeque<Event>) {
+        let payloads = std::mem::take(&mut *self.shared.events.lock().unwrap());
+        event_queue.extend(payloads.into_iter().map(|payload| Event {
+            time: Duration::ZERO,
+            window: WindowHandle::NONE,
+            kind: EventKind::User(payload),
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeating_timers_have_a_minimum_interval() {
+        let mut timers = Timers::default();
+        let set_at = Instant::now();
+        let timer = timers.set(Duration::ZERO, true);
+
+        let mut event_queue = VecDeque::new();
+        timers.dispatch(&mut event_queue);
+
+        assert!(matches!(
+            event_queue.pop_front().map(|event| event.kind),
+            Some(EventKind::Timer(t)) if t == timer
+        ));
+        assert!(timers.next_deadline().unwrap() >= set_at + MIN_TIMER_INTERVAL);
+    }
+}
@@ -2,22 +2,26 @@
 
 pub use crate::native::WindowSurface;
 use crate::{
+    error::LokinitError,
     lok::{self, LokinitBackend},
     prelude::WindowHandle,
 };
 
 impl WindowHandle {
-    pub fn create_surface(&self, cfg: OpenGlConfig) -> WindowSurface {
+    pub fn create_surface(&self, cfg: OpenGlConfig) -> Result<WindowSurface, LokinitError> {
         lok::with(|backend| backend.create_window_surface(*self, cfg))
     }
 
     #[cfg(feature = "opengl")]
-    pub fn make_surface_active(&self, surface: crate::native::WindowSurface) {
+    pub fn make_surface_active(
+        &self,
+        surface: crate::native::WindowSurface,
+    ) -> Result<(), LokinitError> {
         lok::with(|backend| backend.make_surface_active(*self, surface))
     }
 
     #[cfg(feature = "opengl")]
-    pub fn flush_surface(&self, surface: crate::native::WindowSurface) {
+    pub fn flush_surface(&self, surface: crate::native::WindowSurface) -> Result<(), LokinitError> {
         lok::with(|backend| backend.flush_surface(*self, surface))
     }
 }
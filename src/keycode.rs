@@ -1,3 +1,5 @@
+use std::str::FromStr;
+
 /// Symbolic name for a keyboard key.
 #[derive(Debug, Hash, Ord, PartialOrd, PartialEq, Eq, Clone, Copy)]
 #[repr(u32)]
@@ -104,6 +106,8 @@ pub enum KeyCode {
     RSuper,
     LCommand,
     RCommand,
+    /// The context menu key, usually next to the right Super key.
+    Menu,
 
     ArrowLeft,
     ArrowUp,
@@ -130,13 +134,63 @@ pub enum KeyCode {
     F10,
     F11,
     F12,
+    F13,
+    F14,
+    F15,
+    F16,
+    F17,
+    F18,
+    F19,
+    F20,
+    F21,
+    F22,
+    F23,
+    F24,
+
+    Insert,
 
     Delete,
     Play,
     Stop,
     PrevTrack,
     NextTrack,
+    VolumeUp,
+    VolumeDown,
+    VolumeMute,
+    BrowserBack,
+    BrowserForward,
+    BrightnessUp,
+    BrightnessDown,
+
     PrintScreen,
+    ScrollLock,
+    Pause,
+
+    /// The extra key between the left Shift key and `Z` on ISO keyboards.
+    ///
+    /// On X11, this key types the same characters as other keys, so it's only reported in
+    /// [`ScanCode`]s.
+    IntlBackslash,
+    /// The `ろ` key, left of the right Shift key on Japanese keyboards.
+    ///
+    /// On X11, this is only reported when the key types kana. Otherwise, it types a backslash and
+    /// can only be identified by its [`ScanCode`].
+    IntlRo,
+    /// The `¥` key, left of Backspace on Japanese keyboards.
+    ///
+    /// On X11, this is only reported when the layout makes the key type `¥`. Most Japanese layouts
+    /// make it type a backslash instead, so it can only be identified by its [`ScanCode`].
+    IntlYen,
+    /// The key that switches between Hiragana and Katakana on Japanese keyboards.
+    KanaMode,
+    /// The `変換` key, right of the space bar on Japanese keyboards.
+    Henkan,
+    /// The `無変換` key, left of the space bar on Japanese keyboards.
+    Muhenkan,
+    /// The key that switches between Hangul and English on Korean keyboards.
+    Hangul,
+    /// The key that converts Hangul to Hanja on Korean keyboards.
+    Hanja,
 
     NumLock,
     Numpad0,
@@ -157,4 +211,206 @@ pub enum KeyCode {
     NumpadEquals,
     NumpadMultiply,
     NumpadSubtract,
+
+    /// A key Lokinit doesn't know about, with the OS' own code for it: the keysym on X11, and the
+    /// virtual key code on macOS.
+    Unidentified(u32),
+}
+
+/// A physical key, regardless of the keyboard layout. On AZERTY keyboards, the key that types `Z`
+/// has the same scancode as the one that types `W` on QWERTY keyboards.
+///
+/// It holds the OS' own scancode: the evdev code on Linux, and the virtual key code on macOS.
+#[derive(Debug, Hash, Ord, PartialOrd, PartialEq, Eq, Clone, Copy)]
+pub struct ScanCode(pub u32);
+
+impl ScanCode {
+    /// The key at this position on a US QWERTY keyboard, for instance to bind game controls to
+    /// WASD whatever the layout: `scancode.qwerty_key() == Some(KeyCode::W)`.
+    pub fn qwerty_key(self) -> Option<KeyCode> {
+        #[cfg(any(target_os = "linux", target_os = "macos"))]
+        return crate::native::scancode_to_keycode(self.0);
+
+        #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+        None
+    }
+}
+
+impl FromStr for KeyCode {
+    type Err = ();
+
+    /// Parses a key code from its name, as printed by its `Debug` implementation.
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Ok(match name {
+            "Key1" => Self::Key1,
+            "Key2" => Self::Key2,
+            "Key3" => Self::Key3,
+            "Key4" => Self::Key4,
+            "Key5" => Self::Key5,
+            "Key6" => Self::Key6,
+            "Key7" => Self::Key7,
+            "Key8" => Self::Key8,
+            "Key9" => Self::Key9,
+            "Key0" => Self::Key0,
+            "Tilde" => Self::Tilde,
+            "Backtick" => Self::Backtick,
+            "ExclamationMark" => Self::ExclamationMark,
+            "At" => Self::At,
+            "Hashtag" => Self::Hashtag,
+            "Dollar" => Self::Dollar,
+            "Percent" => Self::Percent,
+            "Caret" => Self::Caret,
+            "Ampersand" => Self::Ampersand,
+            "Asterisk" => Self::Asterisk,
+            "LParens" => Self::LParens,
+            "RParens" => Self::RParens,
+            "Underscore" => Self::Underscore,
+            "Minus" => Self::Minus,
+            "Plus" => Self::Plus,
+            "Equals" => Self::Equals,
+            "Backspace" => Self::Backspace,
+            "A" => Self::A,
+            "B" => Self::B,
+            "C" => Self::C,
+            "D" => Self::D,
+            "E" => Self::E,
+            "F" => Self::F,
+            "G" => Self::G,
+            "H" => Self::H,
+            "I" => Self::I,
+            "J" => Self::J,
+            "K" => Self::K,
+            "L" => Self::L,
+            "M" => Self::M,
+            "N" => Self::N,
+            "O" => Self::O,
+            "P" => Self::P,
+            "Q" => Self::Q,
+            "R" => Self::R,
+            "S" => Self::S,
+            "T" => Self::T,
+            "U" => Self::U,
+            "V" => Self::V,
+            "W" => Self::W,
+            "X" => Self::X,
+            "Y" => Self::Y,
+            "Z" => Self::Z,
+            "Space" => Self::Space,
+            "LBracket" => Self::LBracket,
+            "RBracket" => Self::RBracket,
+            "LBrace" => Self::LBrace,
+            "RBrace" => Self::RBrace,
+            "Pipe" => Self::Pipe,
+            "Backslash" => Self::Backslash,
+            "Colon" => Self::Colon,
+            "Semicolon" => Self::Semicolon,
+            "DoubleQuote" => Self::DoubleQuote,
+            "SingleQuote" => Self::SingleQuote,
+            "LAngleBracket" => Self::LAngleBracket,
+            "RAngleBracket" => Self::RAngleBracket,
+            "QuestionMark" => Self::QuestionMark,
+            "Comma" => Self::Comma,
+            "Point" => Self::Point,
+            "Slash" => Self::Slash,
+            "Tab" => Self::Tab,
+            "CapsLock" => Self::CapsLock,
+            "Enter" => Self::Enter,
+            "LShift" => Self::LShift,
+            "RShift" => Self::RShift,
+            "LCtrl" => Self::LCtrl,
+            "RCtrl" => Self::RCtrl,
+            "LAlt" => Self::LAlt,
+            "RAlt" => Self::RAlt,
+            "LWin" => Self::LWin,
+            "RWin" => Self::RWin,
+            "LSuper" => Self::LSuper,
+            "RSuper" => Self::RSuper,
+            "LCommand" => Self::LCommand,
+            "RCommand" => Self::RCommand,
+            "Menu" => Self::Menu,
+            "ArrowLeft" => Self::ArrowLeft,
+            "ArrowUp" => Self::ArrowUp,
+            "ArrowRight" => Self::ArrowRight,
+            "ArrowDown" => Self::ArrowDown,
+            "Home" => Self::Home,
+            "PageUp" => Self::PageUp,
+            "End" => Self::End,
+            "PageDown" => Self::PageDown,
+            "Escape" => Self::Escape,
+            "F1" => Self::F1,
+            "F2" => Self::F2,
+            "F3" => Self::F3,
+            "F4" => Self::F4,
+            "F5" => Self::F5,
+            "F6" => Self::F6,
+            "F7" => Self::F7,
+            "F8" => Self::F8,
+            "F9" => Self::F9,
+            "F10" => Self::F10,
+            "F11" => Self::F11,
+            "F12" => Self::F12,
+            "F13" => Self::F13,
+            "F14" => Self::F14,
+            "F15" => Self::F15,
+            "F16" => Self::F16,
+            "F17" => Self::F17,
+            "F18" => Self::F18,
+            "F19" => Self::F19,
+            "F20" => Self::F20,
+            "F21" => Self::F21,
+            "F22" => Self::F22,
+            "F23" => Self::F23,
+            "F24" => Self::F24,
+            "Insert" => Self::Insert,
+            "Delete" => Self::Delete,
+            "Play" => Self::Play,
+            "Stop" => Self::Stop,
+            "PrevTrack" => Self::PrevTrack,
+            "NextTrack" => Self::NextTrack,
+            "VolumeUp" => Self::VolumeUp,
+            "VolumeDown" => Self::VolumeDown,
+            "VolumeMute" => Self::VolumeMute,
+            "BrowserBack" => Self::BrowserBack,
+            "BrowserForward" => Self::BrowserForward,
+            "BrightnessUp" => Self::BrightnessUp,
+            "BrightnessDown" => Self::BrightnessDown,
+            "PrintScreen" => Self::PrintScreen,
+            "ScrollLock" => Self::ScrollLock,
+            "Pause" => Self::Pause,
+            "IntlBackslash" => Self::IntlBackslash,
+            "IntlRo" => Self::IntlRo,
+            "IntlYen" => Self::IntlYen,
+            "KanaMode" => Self::KanaMode,
+            "Henkan" => Self::Henkan,
+            "Muhenkan" => Self::Muhenkan,
+            "Hangul" => Self::Hangul,
+            "Hanja" => Self::Hanja,
+            "NumLock" => Self::NumLock,
+            "Numpad0" => Self::Numpad0,
+            "Numpad1" => Self::Numpad1,
+            "Numpad2" => Self::Numpad2,
+            "Numpad3" => Self::Numpad3,
+            "Numpad4" => Self::Numpad4,
+            "Numpad5" => Self::Numpad5,
+            "Numpad6" => Self::Numpad6,
+            "Numpad7" => Self::Numpad7,
+            "Numpad8" => Self::Numpad8,
+            "Numpad9" => Self::Numpad9,
+            "NumpadAdd" => Self::NumpadAdd,
+            "NumpadDivide" => Self::NumpadDivide,
+            "NumpadDecimal" => Self::NumpadDecimal,
+            "NumpadComma" => Self::NumpadComma,
+            "NumpadEnter" => Self::NumpadEnter,
+            "NumpadEquals" => Self::NumpadEquals,
+            "NumpadMultiply" => Self::NumpadMultiply,
+            "NumpadSubtract" => Self::NumpadSubtract,
+            _ => {
+                let code = name
+                    .strip_prefix("Unidentified(")
+                    .and_then(|code| code.strip_suffix(')'))
+                    .ok_or(())?;
+                Self::Unidentified(code.parse().map_err(|_| ())?)
+            }
+        })
+    }
 }
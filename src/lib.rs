@@ -1,19 +1,34 @@
+pub mod accelerator;
+pub mod cursor;
+pub mod error;
 pub mod event;
+pub mod event_loop;
 #[cfg(feature = "opengl")]
 pub mod gl;
 pub mod keycode;
 pub mod lok;
 pub mod native;
+pub mod record;
 pub mod window;
 
 pub mod prelude {
     #[cfg(feature = "opengl")]
     pub use crate::gl::{OpenGlConfig, WindowSurface};
     pub use crate::{
-        event::{Event, EventKind, KeyboardEvent, MouseButton, MouseEvent, TouchEvent, TouchPhase},
-        keycode::KeyCode,
-        lok::{self, Monitor, MonitorId},
+        accelerator::Accelerator,
+        cursor::{CursorFrame, CursorIcon, CustomCursor},
+        error::LokinitError,
+        event::{
+            Event, EventKind, KeyboardEvent, Modifiers, MouseButton, MouseEvent, ScrollDelta,
+            ScrollEvent, TouchEvent, TouchPhase,
+        },
+        event_loop::{ControlFlow, EventHandler, EventLoopProxy, Interest, TimerId},
+        keycode::{KeyCode, ScanCode},
+        lok::{self, InitOptions, Lokinit, Monitor, MonitorId, Preference},
         native::DefaultLokinitBackend,
-        window::{WindowBorder, WindowBuilder, WindowHandle, WindowPos, WindowSize},
+        window::{
+            CursorGrab, WindowBorder, WindowBuilder, WindowHandle, WindowPos, WindowRect,
+            WindowSize,
+        },
     };
 }
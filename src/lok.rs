@@ -1,8 +1,17 @@
-//! The core of Lokinit, where a Lokinit backend is initialized as a global mutable state.
+//! The core of Lokinit, where a Lokinit backend is initialized, either as a [`Lokinit`] instance
+//! or as a global mutable state.
 
 #![allow(unused)]
 
+pub mod testing;
+
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Write};
+#[cfg(unix)]
+use std::os::fd::RawFd;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 
 use crate::{native::DefaultLokinitBackend, window::ScreenMode};
 #[cfg(feature = "opengl")]
@@ -13,8 +22,12 @@ use {
 
 use {
     crate::{
+        cursor::{CursorFrame, CursorIcon, CustomCursor},
+        error::LokinitError,
         event::Event,
-        window::{WindowBuilder, WindowHandle, WindowPos, WindowSize},
+        event_loop::{ControlFlow, EventHandler, EventLoopProxy, Interest, TimerId},
+        record::Recorder,
+        window::{CursorGrab, WindowBuilder, WindowHandle, WindowPos, WindowRect, WindowSize},
     },
     std::{cell::RefCell, rc::Rc},
 };
@@ -30,37 +43,438 @@ pub struct Monitor {
     hertz: u32,
 }
 
+/// Which backend Lokinit should try first on platforms that have several of them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum Preference {
+    /// Use the backend chosen by the `LOKINIT_BACKEND` environment variable, which is either `x11`,
+    /// `wayland` or `headless`. If it isn't set, Wayland is tried before X11.
+    #[default]
+    Auto,
+    X11,
+    Wayland,
+    /// Don't connect to any display server, see [`testing`]. Other backends aren't tried if it
+    /// fails. It can also be chosen by setting `LOKINIT_BACKEND` to `headless`, on every platform.
+    Headless,
+}
+
+/// Options for [`init_with`]. Platforms ignore the options that don't apply to them.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub struct InitOptions {
+    /// The backend to try first. The other ones are still tried if it fails.
+    pub backend: Preference,
+    /// The X display to connect to, such as `:1`. Defaults to the `DISPLAY` environment variable.
+    pub x11_display: Option<String>,
+    /// The Wayland socket to connect to, either a name in `XDG_RUNTIME_DIR` or an absolute path.
+    /// Defaults to the `WAYLAND_DISPLAY` environment variable.
+    pub wayland_display: Option<String>,
+}
+
+/// Why a backend failed to initialize.
 #[derive(Clone, Debug)]
-pub struct CreateWindowError(pub Rc<str>);
+pub struct InitAttempt {
+    pub backend: Preference,
+    pub reason: Rc<str>,
+}
+
+/// Returned when no backend could be initialized. It lists every backend that was tried, in order.
+#[derive(Clone, Debug)]
+pub struct InitError {
+    pub attempts: Vec<InitAttempt>,
+}
+
+impl fmt::Display for InitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Lokinit could not initialize any backend")?;
+        for attempt in &self.attempts {
+            write!(f, "\n- {:?}: {}", attempt.backend, attempt.reason)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for InitError {}
 
 pub trait LokinitBackend {
     fn init() -> Self
     where
         Self: Sized + 'static;
+    fn init_with(options: &InitOptions) -> Result<Self, InitError>
+    where
+        Self: Sized + 'static,
+    {
+        Ok(Self::init())
+    }
+
+    fn create_window(&mut self, builder: WindowBuilder) -> Result<WindowHandle, LokinitError>;
+    fn close_window(&mut self, handle: WindowHandle) -> Result<(), LokinitError>;
+
+    fn poll_event(&mut self) -> Result<Option<Event>, LokinitError>;
+    fn try_poll_event(&mut self) -> Result<Option<Event>, LokinitError>;
+    fn poll_event_timeout(&mut self, timeout: Duration) -> Result<Option<Event>, LokinitError>;
+    fn dispatch_pending(&mut self) -> Result<(), LokinitError>;
+    /// Returns the next event that is already queued, without processing anything new.
+    fn next_queued_event(&mut self) -> Option<Event>;
+    #[cfg(unix)]
+    fn connection_fd(&self) -> Result<RawFd, LokinitError>;
 
-    fn create_window(&mut self, builder: WindowBuilder) -> Result<WindowHandle, CreateWindowError>;
-    fn close_window(&mut self, handle: WindowHandle);
+    fn create_proxy(&mut self) -> Result<EventLoopProxy, LokinitError>;
+    fn set_timer(&mut self, duration: Duration, repeat: bool) -> Result<TimerId, LokinitError>;
+    fn cancel_timer(&mut self, timer: TimerId) -> Result<(), LokinitError>;
+    #[cfg(unix)]
+    fn watch_fd(&mut self, fd: RawFd, interest: Interest) -> Result<(), LokinitError>;
+    #[cfg(unix)]
+    fn unwatch_fd(&mut self, fd: RawFd) -> Result<(), LokinitError>;
 
-    fn poll_event(&mut self) -> Option<Event>;
+    fn set_screen_mode(
+        &mut self,
+        handle: WindowHandle,
+        screen_mode: ScreenMode,
+    ) -> Result<(), LokinitError>;
 
-    fn set_screen_mode(&mut self, handle: WindowHandle, screen_mode: ScreenMode);
+    fn fetch_monitors(&mut self) -> Result<Vec<Monitor>, LokinitError>;
 
-    fn fetch_monitors(&mut self) -> Vec<Monitor>;
+    fn set_ime_cursor_area(
+        &mut self,
+        handle: WindowHandle,
+        area: WindowRect,
+    ) -> Result<(), LokinitError>;
+    fn set_ime_allowed(&mut self, handle: WindowHandle, allowed: bool) -> Result<(), LokinitError>;
+
+    fn set_raw_mouse_motion(
+        &mut self,
+        handle: WindowHandle,
+        enabled: bool,
+    ) -> Result<(), LokinitError>;
+    fn set_cursor_grab(
+        &mut self,
+        handle: WindowHandle,
+        grab: CursorGrab,
+    ) -> Result<(), LokinitError>;
+    fn set_cursor_icon(
+        &mut self,
+        handle: WindowHandle,
+        icon: CursorIcon,
+    ) -> Result<(), LokinitError>;
+    fn set_cursor_visible(
+        &mut self,
+        handle: WindowHandle,
+        visible: bool,
+    ) -> Result<(), LokinitError>;
+    fn set_cursor_position(
+        &mut self,
+        handle: WindowHandle,
+        x: f64,
+        y: f64,
+    ) -> Result<(), LokinitError>;
+    fn create_cursor(&mut self, frames: &[CursorFrame]) -> Result<CustomCursor, LokinitError>;
+    fn destroy_cursor(&mut self, cursor: CustomCursor) -> Result<(), LokinitError>;
+    fn set_custom_cursor(
+        &mut self,
+        handle: WindowHandle,
+        cursor: CustomCursor,
+    ) -> Result<(), LokinitError>;
 
     #[cfg(feature = "opengl")]
-    fn create_window_surface(&mut self, window: WindowHandle, cfg: OpenGlConfig) -> WindowSurface;
+    fn create_window_surface(
+        &mut self,
+        window: WindowHandle,
+        cfg: OpenGlConfig,
+    ) -> Result<WindowSurface, LokinitError>;
     #[cfg(feature = "opengl")]
     fn load_opengl_func(&mut self, proc_name: *const c_char) -> *mut c_void;
     #[cfg(feature = "opengl")]
-    fn make_surface_active(&self, window: WindowHandle, surface: WindowSurface);
+    fn make_surface_active(
+        &self,
+        window: WindowHandle,
+        surface: WindowSurface,
+    ) -> Result<(), LokinitError>;
     #[cfg(feature = "opengl")]
-    fn flush_surface(&self, window: WindowHandle, surface: WindowSurface);
+    fn flush_surface(
+        &self,
+        window: WindowHandle,
+        surface: WindowSurface,
+    ) -> Result<(), LokinitError>;
     #[cfg(feature = "opengl")]
-    fn update_surface(&self, surface: WindowSurface);
+    fn update_surface(&self, surface: WindowSurface) -> Result<(), LokinitError>;
+}
+
+/// A Lokinit instance, owning a connection to the OS' display server.
+///
+/// Every window and event source it creates belongs to it, and they all go away when it gets
+/// dropped, which closes the connection. Instances are independent from each other and from the
+/// global instance used by the free functions of this module, which are thin wrappers around the
+/// same methods.
+pub struct Lokinit {
+    backend: DefaultLokinitBackend,
+    recorder: Option<Recorder<Box<dyn Write>>>,
+}
+
+impl Lokinit {
+    /// Connects to the OS' display server with a default backend.
+    pub fn new() -> Self {
+        Self {
+            backend: DefaultLokinitBackend::init(),
+            recorder: None,
+        }
+    }
+
+    /// Connects to the OS' display server with the first backend that works, following the
+    /// given options.
+    pub fn with_options(options: InitOptions) -> Result<Self, InitError> {
+        Ok(Self {
+            backend: DefaultLokinitBackend::init_with(&options)?,
+            recorder: None,
+        })
+    }
+
+    pub fn backend(&mut self) -> &mut DefaultLokinitBackend {
+        &mut self.backend
+    }
+
+    pub fn create_window(&mut self, builder: WindowBuilder) -> Result<WindowHandle, LokinitError> {
+        self.backend.create_window(builder)
+    }
+
+    pub fn close_window(&mut self, handle: WindowHandle) -> Result<(), LokinitError> {
+        self.backend.close_window(handle)
+    }
+
+    /// See [`lok::poll_event`](poll_event).
+    pub fn poll_event(&mut self) -> Result<Option<Event>, LokinitError> {
+        let event = self.backend.poll_event();
+        self.record(event)
+    }
+
+    /// See [`lok::try_poll_event`](try_poll_event).
+    pub fn try_poll_event(&mut self) -> Result<Option<Event>, LokinitError> {
+        let event = self.backend.try_poll_event();
+        self.record(event)
+    }
+
+    /// See [`lok::poll_event_timeout`](poll_event_timeout).
+    pub fn poll_event_timeout(&mut self, timeout: Duration) -> Result<Option<Event>, LokinitError> {
+        let event = self.backend.poll_event_timeout(timeout);
+        self.record(event)
+    }
+
+    /// See [`lok::dispatch_pending`](dispatch_pending).
+    pub fn dispatch_pending(&mut self) -> Result<(), LokinitError> {
+        self.backend.dispatch_pending()
+    }
+
+    /// See [`lok::connection_fd`](connection_fd).
+    #[cfg(unix)]
+    pub fn connection_fd(&self) -> Result<RawFd, LokinitError> {
+        self.backend.connection_fd()
+    }
+
+    /// See [`lok::run`](run). The callback can't use this instance while the loop is running.
+    pub fn run(
+        &mut self,
+        callback: impl FnMut(Event, &mut ControlFlow),
+    ) -> Result<(), LokinitError> {
+        self.run_handler(callback)
+    }
+
+    /// See [`lok::run_handler`](run_handler). The handler can't use this instance while the loop
+    /// is running.
+    pub fn run_handler(&mut self, handler: impl EventHandler) -> Result<(), LokinitError> {
+        run_loop(handler, |wait| self.wait_event(wait))
+    }
+
+    /// See [`lok::create_proxy`](create_proxy).
+    pub fn create_proxy(&mut self) -> Result<EventLoopProxy, LokinitError> {
+        self.backend.create_proxy()
+    }
+
+    /// See [`lok::set_timer`](set_timer).
+    pub fn set_timer(&mut self, duration: Duration, repeat: bool) -> Result<TimerId, LokinitError> {
+        self.backend.set_timer(duration, repeat)
+    }
+
+    /// See [`lok::cancel_timer`](cancel_timer).
+    pub fn cancel_timer(&mut self, timer: TimerId) -> Result<(), LokinitError> {
+        self.backend.cancel_timer(timer)
+    }
+
+    /// See [`lok::watch_fd`](watch_fd).
+    #[cfg(unix)]
+    pub fn watch_fd(&mut self, fd: RawFd, interest: Interest) -> Result<(), LokinitError> {
+        self.backend.watch_fd(fd, interest)
+    }
+
+    /// See [`lok::unwatch_fd`](unwatch_fd).
+    #[cfg(unix)]
+    pub fn unwatch_fd(&mut self, fd: RawFd) -> Result<(), LokinitError> {
+        self.backend.unwatch_fd(fd)
+    }
+
+    pub fn set_screen_mode(
+        &mut self,
+        handle: WindowHandle,
+        screen_mode: ScreenMode,
+    ) -> Result<(), LokinitError> {
+        self.backend.set_screen_mode(handle, screen_mode)
+    }
+
+    pub fn fetch_monitors(&mut self) -> Result<Vec<Monitor>, LokinitError> {
+        self.backend.fetch_monitors()
+    }
+
+    /// See [`WindowHandle::set_ime_cursor_area`].
+    pub fn set_ime_cursor_area(
+        &mut self,
+        handle: WindowHandle,
+        area: WindowRect,
+    ) -> Result<(), LokinitError> {
+        self.backend.set_ime_cursor_area(handle, area)
+    }
+
+    /// See [`WindowHandle::set_ime_allowed`].
+    pub fn set_ime_allowed(
+        &mut self,
+        handle: WindowHandle,
+        allowed: bool,
+    ) -> Result<(), LokinitError> {
+        self.backend.set_ime_allowed(handle, allowed)
+    }
+
+    /// See [`WindowHandle::set_raw_mouse_motion`].
+    pub fn set_raw_mouse_motion(
+        &mut self,
+        handle: WindowHandle,
+        enabled: bool,
+    ) -> Result<(), LokinitError> {
+        self.backend.set_raw_mouse_motion(handle, enabled)
+    }
+
+    /// See [`WindowHandle::set_cursor_grab`].
+    pub fn set_cursor_grab(
+        &mut self,
+        handle: WindowHandle,
+        grab: CursorGrab,
+    ) -> Result<(), LokinitError> {
+        self.backend.set_cursor_grab(handle, grab)
+    }
+
+    /// See [`WindowHandle::set_cursor_icon`].
+    pub fn set_cursor_icon(
+        &mut self,
+        handle: WindowHandle,
+        icon: CursorIcon,
+    ) -> Result<(), LokinitError> {
+        self.backend.set_cursor_icon(handle, icon)
+    }
+
+    /// See [`WindowHandle::set_cursor_visible`].
+    pub fn set_cursor_visible(
+        &mut self,
+        handle: WindowHandle,
+        visible: bool,
+    ) -> Result<(), LokinitError> {
+        self.backend.set_cursor_visible(handle, visible)
+    }
+
+    /// See [`WindowHandle::set_cursor_position`].
+    pub fn set_cursor_position(
+        &mut self,
+        handle: WindowHandle,
+        x: f64,
+        y: f64,
+    ) -> Result<(), LokinitError> {
+        self.backend.set_cursor_position(handle, x, y)
+    }
+
+    /// See [`lok::create_cursor`](create_cursor).
+    pub fn create_cursor(
+        &mut self,
+        rgba: &[u8],
+        width: u32,
+        height: u32,
+        hotspot: (u32, u32),
+    ) -> Result<CustomCursor, LokinitError> {
+        let frame = CursorFrame::new(rgba, width, height, hotspot, Duration::ZERO)?;
+        self.backend.create_cursor(&[frame])
+    }
+
+    /// See [`lok::create_animated_cursor`](create_animated_cursor).
+    pub fn create_animated_cursor(
+        &mut self,
+        frames: &[CursorFrame],
+    ) -> Result<CustomCursor, LokinitError> {
+        if frames.is_empty() {
+            return Err(LokinitError::InvalidCursorImage(
+                "animated cursors need at least one frame",
+            ));
+        }
+        self.backend.create_cursor(frames)
+    }
+
+    /// See [`lok::destroy_cursor`](destroy_cursor).
+    pub fn destroy_cursor(&mut self, cursor: CustomCursor) -> Result<(), LokinitError> {
+        self.backend.destroy_cursor(cursor)
+    }
+
+    /// See [`WindowHandle::set_custom_cursor`].
+    pub fn set_custom_cursor(
+        &mut self,
+        handle: WindowHandle,
+        cursor: CustomCursor,
+    ) -> Result<(), LokinitError> {
+        self.backend.set_custom_cursor(handle, cursor)
+    }
+
+    /// Starts writing every event returned by this instance to `writer`, as a trace that
+    /// [`Replay`](crate::record::Replay) can read back. Replaces any recording in progress.
+    pub fn start_recording(&mut self, writer: impl Write + 'static) -> io::Result<()> {
+        self.recorder = Some(Recorder::new(Box::new(writer) as Box<dyn Write>)?);
+        Ok(())
+    }
+
+    /// Stops the recording started by [`Lokinit::start_recording`], and returns the first error
+    /// that happened while writing the trace, if any.
+    pub fn stop_recording(&mut self) -> io::Result<()> {
+        match self.recorder.take() {
+            Some(recorder) => recorder.finish().map(drop),
+            None => Ok(()),
+        }
+    }
+
+    fn record(
+        &mut self,
+        event: Result<Option<Event>, LokinitError>,
+    ) -> Result<Option<Event>, LokinitError> {
+        if let (Some(recorder), Ok(Some(event))) = (&mut self.recorder, &event) {
+            recorder.record(event);
+        }
+        event
+    }
+
+    /// Returns the next event that is already queued, without processing anything new.
+    fn next_queued_event(&mut self) -> Result<Option<Event>, LokinitError> {
+        let event = self.backend.next_queued_event();
+        self.record(Ok(event))
+    }
+
+    /// Gets the next event the way [`run_loop`] asks for it.
+    fn wait_event(&mut self, wait: Wait) -> Result<Option<Event>, LokinitError> {
+        match wait {
+            Wait::Queued => self.next_queued_event(),
+            Wait::For(None) => self.poll_event(),
+            Wait::For(Some(Duration::ZERO)) => self.try_poll_event(),
+            Wait::For(Some(timeout)) => self.poll_event_timeout(timeout),
+        }
+    }
+}
+
+impl Default for Lokinit {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 thread_local! {
-    static INSTANCE: RefCell<Option<DefaultLokinitBackend>> = const { RefCell::new(None) };
+    static INSTANCE: RefCell<Option<Lokinit>> = const { RefCell::new(None) };
 }
 
 /// Initializes Lokinit with a default backend.
@@ -68,11 +482,29 @@ pub fn init() {
     INSTANCE.with(|instance| {
         let mut instance = instance.borrow_mut();
 
-        *instance = Some(DefaultLokinitBackend::init());
+        *instance = Some(Lokinit::new());
     })
 }
 
-pub fn with<R>(callback: impl FnOnce(&mut DefaultLokinitBackend) -> R) -> R {
+/// Initializes Lokinit with the first backend that works, following the given options.
+///
+/// Unlike [`init`], this doesn't panic when no backend works, and reports why each of them failed
+/// instead.
+pub fn init_with(options: InitOptions) -> Result<(), InitError> {
+    let lokinit = Lokinit::with_options(options)?;
+    INSTANCE.with(|instance| *instance.borrow_mut() = Some(lokinit));
+    Ok(())
+}
+
+/// Drops the instance created by [`init`] or [`init_with`], closing every window and the connection to the OS'
+/// display server. Lokinit can be initialized again afterwards.
+pub fn deinit() {
+    let instance = INSTANCE.with(|instance| instance.borrow_mut().take());
+    drop(instance);
+}
+
+/// Like [`with`], but with the whole instance rather than just its backend.
+fn with_instance<R>(callback: impl FnOnce(&mut Lokinit) -> R) -> R {
     INSTANCE.with(|instance| {
         let mut instance = instance.borrow_mut();
         let instance = instance.as_mut().expect("Lokinit is not initialized");
@@ -80,33 +512,278 @@ pub fn with<R>(callback: impl FnOnce(&mut DefaultLokinitBackend) -> R) -> R {
     })
 }
 
-pub fn create_window(builder: WindowBuilder) -> Result<WindowHandle, CreateWindowError> {
+pub fn with<R>(callback: impl FnOnce(&mut DefaultLokinitBackend) -> R) -> R {
+    INSTANCE.with(|instance| {
+        let mut instance = instance.borrow_mut();
+        let instance = instance.as_mut().expect("Lokinit is not initialized");
+        (callback)(instance.backend())
+    })
+}
+
+pub fn create_window(builder: WindowBuilder) -> Result<WindowHandle, LokinitError> {
     with(|instance| instance.create_window(builder))
 }
 
-pub fn close_window(handle: WindowHandle) {
+pub fn close_window(handle: WindowHandle) -> Result<(), LokinitError> {
     with(|instance| instance.close_window(handle))
 }
 
 /// Blocks the thread until it receives a new event from the OS' display server, then processes and
 /// returns that event. This will return `None` when there are no more events to be processed.
-pub fn poll_event() -> Option<Event> {
-    with(|instance| instance.poll_event())
+pub fn poll_event() -> Result<Option<Event>, LokinitError> {
+    with_instance(|instance| instance.poll_event())
+}
+
+/// Processes the events the OS' display server has already sent, and returns the next one
+/// without blocking. Returns `None` if no event is available right now.
+pub fn try_poll_event() -> Result<Option<Event>, LokinitError> {
+    with_instance(|instance| instance.try_poll_event())
+}
+
+/// Like [`poll_event`], but gives up and returns `None` if no event arrives within `timeout`.
+pub fn poll_event_timeout(timeout: Duration) -> Result<Option<Event>, LokinitError> {
+    with_instance(|instance| instance.poll_event_timeout(timeout))
+}
+
+/// Processes the data the OS' display server has already sent and Lokinit's own event sources,
+/// queueing the resulting events without blocking. They can then be fetched with
+/// [`try_poll_event`].
+///
+/// This is meant to drive Lokinit from an external reactor: call it whenever [`connection_fd`]
+/// becomes readable, then drain the events. Timers, watched file descriptors and
+/// [`EventLoopProxy`] events don't make the connection readable, so they are only picked up by
+/// the next call.
+pub fn dispatch_pending() -> Result<(), LokinitError> {
+    with(|instance| instance.dispatch_pending())
+}
+
+/// Returns the file descriptor of the connection to the OS' display server, so it can be
+/// registered with an external reactor such as epoll or an async executor. It becomes readable
+/// whenever [`dispatch_pending`] has new data to process.
+///
+/// Lokinit keeps ownership of the file descriptor: don't read from it, and don't close it.
+#[cfg(unix)]
+pub fn connection_fd() -> Result<RawFd, LokinitError> {
+    with(|instance| instance.connection_fd())
+}
+
+/// Starts writing every event returned by Lokinit to `writer`, as a trace that
+/// [`Replay`](crate::record::Replay) can read back. See [`record`](crate::record) for its format.
+///
+/// A trace can be replayed with [`testing::replay`].
+pub fn start_recording(writer: impl Write + 'static) -> io::Result<()> {
+    with_instance(|instance| instance.start_recording(writer))
+}
+
+/// Stops the recording started by [`start_recording`], and returns the first error that happened
+/// while writing the trace, if any.
+pub fn stop_recording() -> io::Result<()> {
+    with_instance(|instance| instance.stop_recording())
+}
+
+/// Runs the event loop until the callback sets the control flow to [`ControlFlow::Exit`], or until
+/// there are no more events to be processed, calling the callback for every event.
+///
+/// Once all pending events have been handled, the loop waits according to the current
+/// [`ControlFlow`], which starts as [`ControlFlow::Wait`].
+///
+/// Errors from the OS' display server stop the loop and are returned.
+pub fn run(callback: impl FnMut(Event, &mut ControlFlow)) -> Result<(), LokinitError> {
+    run_handler(callback)
+}
+
+/// Like [`run`], but with an [`EventHandler`], which can also hook into the loop right before
+/// it waits with [`EventHandler::about_to_wait`].
+pub fn run_handler(handler: impl EventHandler) -> Result<(), LokinitError> {
+    run_loop(handler, |wait| {
+        with_instance(|instance| instance.wait_event(wait))
+    })
+}
+
+/// How [`run_loop`] asks for its next event.
+#[derive(Clone, Copy, Debug)]
+enum Wait {
+    /// Only take an event that is already queued.
+    Queued,
+    /// Wait for at most the given timeout, or indefinitely if it is `None`.
+    For(Option<Duration>),
+}
+
+/// The loop behind [`run_handler`], getting its events from `wait_event`.
+///
+/// The backend is only borrowed while waiting, so that the handler can use it in between.
+fn run_loop(
+    mut handler: impl EventHandler,
+    mut wait_event: impl FnMut(Wait) -> Result<Option<Event>, LokinitError>,
+) -> Result<(), LokinitError> {
+    let mut control = ControlFlow::Wait;
+
+    loop {
+        // Event sources are only processed once per iteration, then the queue is drained. A
+        // watched file descriptor that is always ready would keep it from ever emptying otherwise.
+        let mut next = wait_event(Wait::For(Some(Duration::ZERO)))?;
+        while let Some(event) = next {
+            handler.event(event, &mut control);
+            if control == ControlFlow::Exit {
+                return Ok(());
+            }
+            next = wait_event(Wait::Queued)?;
+        }
+
+        handler.about_to_wait(&mut control);
+
+        let event = match control {
+            ControlFlow::Wait => match wait_event(Wait::For(None))? {
+                Some(event) => event,
+                None => return Ok(()),
+            },
+            ControlFlow::Poll => continue,
+            ControlFlow::WaitUntil(deadline) => {
+                let timeout = deadline.saturating_duration_since(Instant::now());
+                match wait_event(Wait::For(Some(timeout)))? {
+                    Some(event) => event,
+                    None => continue,
+                }
+            }
+            ControlFlow::Exit => return Ok(()),
+        };
+
+        handler.event(event, &mut control);
+        if control == ControlFlow::Exit {
+            return Ok(());
+        }
+    }
+}
+
+/// Creates a proxy that other threads can use to wake up the event loop and send it
+/// [`EventKind::User`](crate::event::EventKind::User) events.
+pub fn create_proxy() -> Result<EventLoopProxy, LokinitError> {
+    with(|instance| instance.create_proxy())
+}
+
+/// Creates a timer that sends an [`EventKind::Timer`](crate::event::EventKind::Timer) event
+/// once `duration` has elapsed. If `repeat` is true, it keeps doing so every `duration` until it
+/// gets cancelled.
+///
+/// Repeating timers fire at most once per millisecond, so a shorter `duration` is rounded up to
+/// that. Otherwise a zero duration would fire on every iteration of the event loop.
+pub fn set_timer(duration: Duration, repeat: bool) -> Result<TimerId, LokinitError> {
+    with(|instance| instance.set_timer(duration, repeat))
+}
+
+/// Cancels a timer, so that it doesn't send any more events.
+pub fn cancel_timer(timer: TimerId) -> Result<(), LokinitError> {
+    with(|instance| instance.cancel_timer(timer))
+}
+
+/// Makes the event loop wait on `fd` too, sending an
+/// [`EventKind::FdReady`](crate::event::EventKind::FdReady) event whenever it is ready for one of
+/// the operations in `interest`. Watching a file descriptor again replaces its interest.
+///
+/// Readiness is level-triggered: the event keeps being sent until the file descriptor has been
+/// read from or written to. The file descriptor must be unwatched before it gets closed.
+#[cfg(unix)]
+pub fn watch_fd(fd: RawFd, interest: Interest) -> Result<(), LokinitError> {
+    with(|instance| instance.watch_fd(fd, interest))
+}
+
+/// Stops watching a file descriptor registered with [`watch_fd`].
+#[cfg(unix)]
+pub fn unwatch_fd(fd: RawFd) -> Result<(), LokinitError> {
+    with(|instance| instance.unwatch_fd(fd))
 }
 
-pub fn set_screen_mode(handle: WindowHandle, screen_mode: ScreenMode) {
+pub fn set_screen_mode(handle: WindowHandle, screen_mode: ScreenMode) -> Result<(), LokinitError> {
     with(|instance| instance.set_screen_mode(handle, screen_mode))
 }
 
-pub fn fetch_monitors() -> Vec<Monitor> {
+pub fn fetch_monitors() -> Result<Vec<Monitor>, LokinitError> {
     with(|instance| instance.fetch_monitors())
 }
 
+/// Creates a cursor from an image, that windows can show with
+/// [`WindowHandle::set_custom_cursor`]. See [`CursorFrame::new`] for the format of the image.
+///
+/// Fails if the image isn't valid, as described in [`CursorFrame::new`].
+pub fn create_cursor(
+    rgba: &[u8],
+    width: u32,
+    height: u32,
+    hotspot: (u32, u32),
+) -> Result<CustomCursor, LokinitError> {
+    with_instance(|instance| instance.create_cursor(rgba, width, height, hotspot))
+}
+
+/// Creates a cursor that cycles through `frames`, showing each of them for its delay.
+///
+/// Fails if `frames` is empty.
+pub fn create_animated_cursor(frames: &[CursorFrame]) -> Result<CustomCursor, LokinitError> {
+    with_instance(|instance| instance.create_animated_cursor(frames))
+}
+
+/// Destroys a custom cursor. The windows that were showing it go back to the default cursor.
+pub fn destroy_cursor(cursor: CustomCursor) -> Result<(), LokinitError> {
+    with(|instance| instance.destroy_cursor(cursor))
+}
+
 #[cfg(feature = "opengl")]
-fn create_window_surface(window: WindowHandle, cfg: OpenGlConfig) -> WindowSurface {
+fn create_window_surface(
+    window: WindowHandle,
+    cfg: OpenGlConfig,
+) -> Result<WindowSurface, LokinitError> {
     with(|instance| instance.create_window_surface(window, cfg))
 }
 #[cfg(feature = "opengl")]
 pub fn load_opengl_func(name: *const c_char) -> *mut c_void {
     with(|instance| instance.load_opengl_func(name))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::EventKind;
+
+    struct CountWaits<'a> {
+        events: &'a mut usize,
+        waits: &'a mut usize,
+    }
+
+    impl EventHandler for CountWaits<'_> {
+        fn event(&mut self, _event: Event, _control: &mut ControlFlow) {
+            *self.events += 1;
+        }
+
+        fn about_to_wait(&mut self, control: &mut ControlFlow) {
+            *self.waits += 1;
+            *control = match *self.waits {
+                3 => ControlFlow::Exit,
+                _ => ControlFlow::Poll,
+            };
+        }
+    }
+
+    #[test]
+    fn always_ready_source_does_not_starve_the_loop() {
+        let (mut events, mut waits) = (0, 0);
+        let handler = CountWaits {
+            events: &mut events,
+            waits: &mut waits,
+        };
+
+        // Like a watched file descriptor that is always ready: processing event sources always
+        // queues one more event
+        let result = run_loop(handler, |wait| {
+            Ok(match wait {
+                Wait::Queued => None,
+                Wait::For(_) => Some(Event {
+                    time: Duration::ZERO,
+                    window: WindowHandle::NONE,
+                    kind: EventKind::User(0),
+                }),
+            })
+        });
+
+        assert!(result.is_ok());
+        assert_eq!((events, waits), (3, 3));
+    }
+}
@@ -0,0 +1,113 @@
+//! Helpers to drive Lokinit from automated tests, using the headless backend.
+//!
+//! The headless backend doesn't need a display server. Its windows only exist in memory, and input
+//! is scripted by injecting events:
+//!
+//! ```
+//! use lokinit::prelude::*;
+//!
+//! lok::testing::init().unwrap();
+//! let window = lok::create_window(WindowBuilder::new().size(800, 600)).unwrap();
+//!
+//! lok::testing::inject(Event {
+//!     time: std::time::Duration::ZERO,
+//!     window,
+//!     kind: EventKind::Resized(1024, 768),
+//! })
+//! .unwrap();
+//!
+//! assert!(matches!(lok::poll_event(), Ok(Some(_))));
+//! assert_eq!(lok::testing::window(window).unwrap().size.width, 1024);
+//! ```
+
+use std::collections::HashMap;
+use std::io::BufRead;
+
+use crate::error::LokinitError;
+use crate::event::Event;
+use crate::native::headless::{HeadlessBackend, HeadlessWindow};
+use crate::record::{Replay, ReplayError};
+use crate::window::WindowHandle;
+
+use super::{init_with, with, InitError, InitOptions, Preference};
+
+/// Initializes Lokinit with the headless backend, regardless of `LOKINIT_BACKEND`.
+pub fn init() -> Result<(), InitError> {
+    init_with(InitOptions {
+        backend: Preference::Headless,
+        ..Default::default()
+    })
+}
+
+/// Queues an event as if the display server had sent it, to be returned by the next poll.
+/// Resize and move events also update the state of the window they are sent to.
+pub fn inject(event: Event) -> Result<(), LokinitError> {
+    with_headless(|headless| headless.inject(event))
+}
+
+/// Returns the current state of a window.
+pub fn window(handle: WindowHandle) -> Result<HeadlessWindow, LokinitError> {
+    with_headless(|headless| headless.window(handle).cloned())?
+}
+
+/// Returns the handles of every open window, in creation order.
+pub fn windows() -> Result<Vec<WindowHandle>, LokinitError> {
+    with_headless(|headless| headless.windows().collect())
+}
+
+/// Injects every event of a trace recorded with [`lok::start_recording`](super::start_recording).
+///
+/// Window handles differ from one run to the next, so the windows of the trace are matched with
+/// the open windows in creation order: the first window that appears in the trace becomes the
+/// first open window, and so on. Create the same windows as the recorded app before replaying.
+///
+/// Nothing gets injected if the trace is invalid.
+///
+/// ```
+/// use lokinit::prelude::*;
+///
+/// lok::testing::init().unwrap();
+/// let window = lok::create_window(WindowBuilder::new()).unwrap();
+///
+/// // A click on a window that had another handle when it was recorded
+/// let trace = "lokinit-trace 1\n\
+///              10 42 Mouse ButtonPress Left 5 5 CTRL\n\
+///              20 42 Mouse ButtonRelease Left 5 5 CTRL\n";
+/// lok::testing::replay(trace.as_bytes()).unwrap();
+///
+/// let event = lok::poll_event().unwrap().unwrap();
+/// assert_eq!(event.window, window);
+/// assert!(matches!(
+///     event.kind,
+///     EventKind::Mouse(MouseEvent::ButtonPress(MouseButton::Left, x, y, Modifiers::CTRL))
+///         if (x, y) == (5.0, 5.0)
+/// ));
+/// ```
+pub fn replay(reader: impl BufRead) -> Result<(), ReplayError> {
+    let events = Replay::new(reader)?.collect::<Result<Vec<_>, _>>()?;
+
+    with_headless(|headless| {
+        let mut open_windows = headless.windows().collect::<Vec<_>>().into_iter();
+        let mut windows = HashMap::new();
+
+        for mut event in events {
+            if event.window != WindowHandle::NONE {
+                event.window = *windows
+                    .entry(event.window)
+                    .or_insert_with(|| open_windows.next().unwrap_or(event.window));
+            }
+            headless.inject(event);
+        }
+    })?;
+
+    Ok(())
+}
+
+fn with_headless<R>(callback: impl FnOnce(&mut HeadlessBackend) -> R) -> Result<R, LokinitError> {
+    with(|backend| match backend.headless() {
+        Some(headless) => Ok(callback(headless)),
+        None => Err(LokinitError::Unsupported(
+            "scripting events without the headless backend",
+        )),
+    })
+}
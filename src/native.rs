@@ -9,13 +9,18 @@ pub mod macos;
 #[cfg(target_os = "windows")]
 pub mod windows;
 
+pub mod backend;
+pub mod headless;
+
 #[cfg(target_os = "linux")]
 mod types {
     use super::linux;
 
-    pub type DefaultLokinitBackend = linux::LinuxBackend;
+    pub type NativeBackend = linux::LinuxBackend;
     pub type WindowId = usize;
 
+    pub use linux::scancode::to_keycode as scancode_to_keycode;
+
     #[cfg(feature = "opengl")]
     pub type WindowSurface = linux::opengl::GlSurface;
 }
@@ -23,14 +28,17 @@ mod types {
 mod types {
     use super::macos;
 
-    pub type DefaultLokinitBackend = macos::MacosBackend;
+    pub type NativeBackend = macos::MacosBackend;
     pub type WindowId = isize;
 
+    pub use macos::keysym::to_keycode as scancode_to_keycode;
+
     #[cfg(feature = "opengl")]
     pub type WindowSurface = macos::opengl::WindowSurface;
 }
 
 #[cfg(target_os = "windows")]
-pub type DefaultLokinitBackend = windows::WindowsBackend;
+pub type NativeBackend = windows::WindowsBackend;
 
+pub use backend::DefaultLokinitBackend;
 pub use types::*;
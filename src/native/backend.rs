@@ -0,0 +1,330 @@
+use crate::cursor::{CursorFrame, CursorIcon, CustomCursor};
+use crate::error::LokinitError;
+use crate::event::Event;
+use crate::event_loop::{EventLoopProxy, TimerId};
+use crate::lok::{InitAttempt, InitError, InitOptions, LokinitBackend, Monitor, Preference};
+use crate::window::{CursorGrab, ScreenMode, WindowBuilder, WindowHandle, WindowRect};
+
+#[cfg(unix)]
+use {crate::event_loop::Interest, std::os::fd::RawFd};
+
+use std::time::Duration;
+
+use super::headless::HeadlessBackend;
+use super::NativeBackend;
+
+#[cfg(feature = "opengl")]
+use crate::gl::*;
+
+/// Either the backend of the current platform, or the [`HeadlessBackend`].
+// There's only one per Lokinit instance, so boxing the native backend wouldn't save anything
+#[allow(clippy::large_enum_variant)]
+pub enum DefaultLokinitBackend {
+    Native(NativeBackend),
+    Headless(HeadlessBackend),
+}
+
+impl DefaultLokinitBackend {
+    /// The headless backend, if it's the one in use.
+    pub fn headless(&mut self) -> Option<&mut HeadlessBackend> {
+        match self {
+            Self::Headless(headless) => Some(headless),
+            _ => None,
+        }
+    }
+
+    fn wants_headless(options: &InitOptions) -> bool {
+        match options.backend {
+            Preference::Auto => std::env::var("LOKINIT_BACKEND").is_ok_and(|x| x == "headless"),
+            backend => backend == Preference::Headless,
+        }
+    }
+}
+
+impl LokinitBackend for DefaultLokinitBackend {
+    fn init() -> Self {
+        match Self::wants_headless(&InitOptions::default()) {
+            true => Self::Headless(HeadlessBackend::init()),
+            false => Self::Native(NativeBackend::init()),
+        }
+    }
+
+    fn init_with(options: &InitOptions) -> Result<Self, InitError> {
+        if !Self::wants_headless(options) {
+            return NativeBackend::init_with(options).map(Self::Native);
+        }
+
+        // Falling back on a real display server would defeat the point of running headless
+        HeadlessBackend::new()
+            .map(Self::Headless)
+            .map_err(|why| InitError {
+                attempts: vec![InitAttempt {
+                    backend: Preference::Headless,
+                    reason: why.to_string().into(),
+                }],
+            })
+    }
+
+    fn create_window(&mut self, builder: WindowBuilder) -> Result<WindowHandle, LokinitError> {
+        match self {
+            Self::Native(native) => native.create_window(builder),
+            Self::Headless(headless) => headless.create_window(builder),
+        }
+    }
+
+    fn close_window(&mut self, handle: WindowHandle) -> Result<(), LokinitError> {
+        match self {
+            Self::Native(native) => native.close_window(handle),
+            Self::Headless(headless) => headless.close_window(handle),
+        }
+    }
+
+    fn poll_event(&mut self) -> Result<Option<Event>, LokinitError> {
+        match self {
+            Self::Native(native) => native.poll_event(),
+            Self::Headless(headless) => headless.poll_event(),
+        }
+    }
+
+    fn try_poll_event(&mut self) -> Result<Option<Event>, LokinitError> {
+        match self {
+            Self::Native(native) => native.try_poll_event(),
+            Self::Headless(headless) => headless.try_poll_event(),
+        }
+    }
+
+    fn poll_event_timeout(&mut self, timeout: Duration) -> Result<Option<Event>, LokinitError> {
+        match self {
+            Self::Native(native) => native.poll_event_timeout(timeout),
+            Self::Headless(headless) => headless.poll_event_timeout(timeout),
+        }
+    }
+
+    fn dispatch_pending(&mut self) -> Result<(), LokinitError> {
+        match self {
+            Self::Native(native) => native.dispatch_pending(),
+            Self::Headless(headless) => headless.dispatch_pending(),
+        }
+    }
+
+    fn next_queued_event(&mut self) -> Option<Event> {
+        match self {
+            Self::Native(native) => native.next_queued_event(),
+            Self::Headless(headless) => headless.next_queued_event(),
+        }
+    }
+
+    #[cfg(unix)]
+    fn connection_fd(&self) -> Result<RawFd, LokinitError> {
+        match self {
+            Self::Native(native) => native.connection_fd(),
+            Self::Headless(headless) => headless.connection_fd(),
+        }
+    }
+
+    fn create_proxy(&mut self) -> Result<EventLoopProxy, LokinitError> {
+        match self {
+            Self::Native(native) => native.create_proxy(),
+            Self::Headless(headless) => headless.create_proxy(),
+        }
+    }
+
+    fn set_timer(&mut self, duration: Duration, repeat: bool) -> Result<TimerId, LokinitError> {
+        match self {
+            Self::Native(native) => native.set_timer(duration, repeat),
+            Self::Headless(headless) => headless.set_timer(duration, repeat),
+        }
+    }
+
+    fn cancel_timer(&mut self, timer: TimerId) -> Result<(), LokinitError> {
+        match self {
+            Self::Native(native) => native.cancel_timer(timer),
+            Self::Headless(headless) => headless.cancel_timer(timer),
+        }
+    }
+
+    #[cfg(unix)]
+    fn watch_fd(&mut self, fd: RawFd, interest: Interest) -> Result<(), LokinitError> {
+        match self {
+            Self::Native(native) => native.watch_fd(fd, interest),
+            Self::Headless(headless) => headless.watch_fd(fd, interest),
+        }
+    }
+
+    #[cfg(unix)]
+    fn unwatch_fd(&mut self, fd: RawFd) -> Result<(), LokinitError> {
+        match self {
+            Self::Native(native) => native.unwatch_fd(fd),
+            Self::Headless(headless) => headless.unwatch_fd(fd),
+        }
+    }
+
+    fn set_screen_mode(
+        &mut self,
+        handle: WindowHandle,
+        screen_mode: ScreenMode,
+    ) -> Result<(), LokinitError> {
+        match self {
+            Self::Native(native) => native.set_screen_mode(handle, screen_mode),
+            Self::Headless(headless) => headless.set_screen_mode(handle, screen_mode),
+        }
+    }
+
+    fn fetch_monitors(&mut self) -> Result<Vec<Monitor>, LokinitError> {
+        match self {
+            Self::Native(native) => native.fetch_monitors(),
+            Self::Headless(headless) => headless.fetch_monitors(),
+        }
+    }
+
+    fn set_ime_cursor_area(
+        &mut self,
+        handle: WindowHandle,
+        area: WindowRect,
+    ) -> Result<(), LokinitError> {
+        match self {
+            Self::Native(native) => native.set_ime_cursor_area(handle, area),
+            Self::Headless(headless) => headless.set_ime_cursor_area(handle, area),
+        }
+    }
+
+    fn set_ime_allowed(&mut self, handle: WindowHandle, allowed: bool) -> Result<(), LokinitError> {
+        match self {
+            Self::Native(native) => native.set_ime_allowed(handle, allowed),
+            Self::Headless(headless) => headless.set_ime_allowed(handle, allowed),
+        }
+    }
+
+    fn set_raw_mouse_motion(
+        &mut self,
+        handle: WindowHandle,
+        enabled: bool,
+    ) -> Result<(), LokinitError> {
+        match self {
+            Self::Native(native) => native.set_raw_mouse_motion(handle, enabled),
+            Self::Headless(headless) => headless.set_raw_mouse_motion(handle, enabled),
+        }
+    }
+
+    fn set_cursor_grab(
+        &mut self,
+        handle: WindowHandle,
+        grab: CursorGrab,
+    ) -> Result<(), LokinitError> {
+        match self {
+            Self::Native(native) => native.set_cursor_grab(handle, grab),
+            Self::Headless(headless) => headless.set_cursor_grab(handle, grab),
+        }
+    }
+
+    fn set_cursor_icon(
+        &mut self,
+        handle: WindowHandle,
+        icon: CursorIcon,
+    ) -> Result<(), LokinitError> {
+        match self {
+            Self::Native(native) => native.set_cursor_icon(handle, icon),
+            Self::Headless(headless) => headless.set_cursor_icon(handle, icon),
+        }
+    }
+
+    fn set_cursor_visible(
+        &mut self,
+        handle: WindowHandle,
+        visible: bool,
+    ) -> Result<(), LokinitError> {
+        match self {
+            Self::Native(native) => native.set_cursor_visible(handle, visible),
+            Self::Headless(headless) => headless.set_cursor_visible(handle, visible),
+        }
+    }
+
+    fn set_cursor_position(
+        &mut self,
+        handle: WindowHandle,
+        x: f64,
+        y: f64,
+    ) -> Result<(), LokinitError> {
+        match self {
+            Self::Native(native) => native.set_cursor_position(handle, x, y),
+            Self::Headless(headless) => headless.set_cursor_position(handle, x, y),
+        }
+    }
+
+    fn create_cursor(&mut self, frames: &[CursorFrame]) -> Result<CustomCursor, LokinitError> {
+        match self {
+            Self::Native(native) => native.create_cursor(frames),
+            Self::Headless(headless) => headless.create_cursor(frames),
+        }
+    }
+
+    fn destroy_cursor(&mut self, cursor: CustomCursor) -> Result<(), LokinitError> {
+        match self {
+            Self::Native(native) => native.destroy_cursor(cursor),
+            Self::Headless(headless) => headless.destroy_cursor(cursor),
+        }
+    }
+
+    fn set_custom_cursor(
+        &mut self,
+        handle: WindowHandle,
+        cursor: CustomCursor,
+    ) -> Result<(), LokinitError> {
+        match self {
+            Self::Native(native) => native.set_custom_cursor(handle, cursor),
+            Self::Headless(headless) => headless.set_custom_cursor(handle, cursor),
+        }
+    }
+
+    #[cfg(feature = "opengl")]
+    fn create_window_surface(
+        &mut self,
+        handle: WindowHandle,
+        config: OpenGlConfig,
+    ) -> Result<WindowSurface, LokinitError> {
+        match self {
+            Self::Native(native) => native.create_window_surface(handle, config),
+            Self::Headless(headless) => headless.create_window_surface(handle, config),
+        }
+    }
+
+    #[cfg(feature = "opengl")]
+    fn load_opengl_func(&mut self, proc_name: *const std::ffi::c_char) -> *mut std::ffi::c_void {
+        match self {
+            Self::Native(native) => native.load_opengl_func(proc_name),
+            Self::Headless(headless) => headless.load_opengl_func(proc_name),
+        }
+    }
+
+    #[cfg(feature = "opengl")]
+    fn make_surface_active(
+        &self,
+        handle: WindowHandle,
+        surface: WindowSurface,
+    ) -> Result<(), LokinitError> {
+        match self {
+            Self::Native(native) => native.make_surface_active(handle, surface),
+            Self::Headless(headless) => headless.make_surface_active(handle, surface),
+        }
+    }
+
+    #[cfg(feature = "opengl")]
+    fn flush_surface(
+        &self,
+        handle: WindowHandle,
+        surface: WindowSurface,
+    ) -> Result<(), LokinitError> {
+        match self {
+            Self::Native(native) => native.flush_surface(handle, surface),
+            Self::Headless(headless) => headless.flush_surface(handle, surface),
+        }
+    }
+
+    #[cfg(feature = "opengl")]
+    fn update_surface(&self, surface: WindowSurface) -> Result<(), LokinitError> {
+        match self {
+            Self::Native(native) => native.update_surface(surface),
+            Self::Headless(headless) => headless.update_surface(surface),
+        }
+    }
+}
@@ -0,0 +1,501 @@
+//! A backend that doesn't connect to any display server, and keeps its windows in memory instead.
+//! Input is scripted with [`lok::testing::inject`](crate::lok::testing::inject), which makes it
+//! handy to test event handling without a display server, on any platform.
+
+use {
+    crate::{
+        cursor::{CursorFrame, CursorIcon, CustomCursor},
+        error::LokinitError,
+        event::{Event, EventKind},
+        event_loop::{EventLoopProxy, TimerId, Timers},
+        lok::LokinitBackend,
+        native::WindowId,
+        prelude::{Monitor, WindowBuilder, WindowHandle},
+        window::{CursorGrab, ScreenMode, WindowPos, WindowRect, WindowSize},
+    },
+    std::{
+        collections::{BTreeMap, BTreeSet, VecDeque},
+        sync::{Arc, Condvar, Mutex},
+        time::{Duration, Instant},
+    },
+};
+
+#[cfg(unix)]
+use {crate::event_loop::Interest, std::os::fd::RawFd};
+
+/// The state of a window created by the [`HeadlessBackend`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct HeadlessWindow {
+    /// The builder the window was created with.
+    pub builder: WindowBuilder,
+    pub position: WindowPos,
+    pub size: WindowSize,
+    pub screen_mode: ScreenMode,
+    pub ime_allowed: bool,
+    /// The area set with [`WindowHandle::set_ime_cursor_area`], if any.
+    pub ime_cursor_area: Option<WindowRect>,
+    pub raw_mouse_motion: bool,
+    pub cursor_grab: CursorGrab,
+    pub cursor_icon: CursorIcon,
+    /// The cursor set with [`WindowHandle::set_custom_cursor`], shown instead of `cursor_icon`.
+    pub custom_cursor: Option<CustomCursor>,
+    pub cursor_visible: bool,
+    /// The position set with [`WindowHandle::set_cursor_position`], if any.
+    pub cursor_position: Option<(f64, f64)>,
+}
+
+/// Set by [`EventLoopProxy::send_event`], so that [`HeadlessBackend::wait_event`] wakes up.
+#[derive(Default)]
+struct Waker {
+    woken: Mutex<bool>,
+    condvar: Condvar,
+}
+
+impl Waker {
+    fn notify(&self) {
+        *self.woken.lock().unwrap() = true;
+        self.condvar.notify_one();
+    }
+
+    /// Waits until [`Waker::notify`] is called or `deadline` passes, whichever comes first.
+    fn wait(&self, deadline: Option<Instant>) {
+        let mut woken = self.woken.lock().unwrap();
+        while !*woken {
+            match deadline {
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        break;
+                    }
+                    woken = self.condvar.wait_timeout(woken, deadline - now).unwrap().0;
+                }
+                None => woken = self.condvar.wait(woken).unwrap(),
+            }
+        }
+        *woken = false;
+    }
+}
+
+/// Only relies on the standard library, so unlike the other backends it's available on every
+/// platform.
+pub struct HeadlessBackend {
+    windows: BTreeMap<WindowHandle, HeadlessWindow>,
+    next_window_id: WindowId,
+    cursors: BTreeSet<CustomCursor>,
+    next_cursor_id: u64,
+    event_queue: VecDeque<Event>,
+    waker: Arc<Waker>,
+    proxy: EventLoopProxy,
+    timers: Timers,
+}
+
+impl HeadlessBackend {
+    pub fn new() -> Result<Self, LokinitError> {
+        let waker = Arc::<Waker>::default();
+        let proxy_waker = waker.clone();
+        let proxy = EventLoopProxy::new(move || proxy_waker.notify());
+
+        Ok(Self {
+            windows: BTreeMap::new(),
+            next_window_id: 0,
+            cursors: BTreeSet::new(),
+            next_cursor_id: 0,
+            event_queue: VecDeque::new(),
+            waker,
+            proxy,
+            timers: Timers::default(),
+        })
+    }
+
+    /// Queues an event as if the display server had sent it. Resize and move events also update
+    /// the state of the window they are sent to.
+    pub fn inject(&mut self, event: Event) {
+        if let Some(window) = self.windows.get_mut(&event.window) {
+            match event.kind {
+                EventKind::Resized(width, height) => window.size = WindowSize::new(width, height),
+                EventKind::Moved(x, y) => window.position = WindowPos::new(x, y),
+                _ => {}
+            }
+        }
+
+        self.event_queue.push_back(event);
+    }
+
+    pub fn window(&self, handle: WindowHandle) -> Result<&HeadlessWindow, LokinitError> {
+        self.windows
+            .get(&handle)
+            .ok_or(LokinitError::InvalidWindow(handle))
+    }
+
+    /// The handles of every open window, in creation order.
+    pub fn windows(&self) -> impl Iterator<Item = WindowHandle> + '_ {
+        self.windows.keys().copied()
+    }
+
+    /// Waits for the next event for at most `timeout`, or indefinitely if `timeout` is `None`.
+    ///
+    /// Since there's no display server, only timers and proxies can produce events. This waits
+    /// while a timer is pending, or until the timeout while a proxy exists, and returns `None` as
+    /// soon as the queue is empty otherwise.
+    fn wait_event(&mut self, timeout: Option<Duration>) -> Result<Option<Event>, LokinitError> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+
+        loop {
+            if let Some(event) = self.event_queue.pop_front() {
+                return Ok(Some(event));
+            }
+
+            // Events sent through a proxy are queued even if nothing else is left to wait for
+            self.dispatch_pending()?;
+            if !self.event_queue.is_empty() {
+                continue;
+            }
+
+            let next_timer = self.timers.next_deadline();
+            let wake_at = match (deadline, next_timer) {
+                (Some(deadline), Some(timer)) => Some(deadline.min(timer)),
+                (Some(deadline), None) if self.proxy.is_shared() => Some(deadline),
+                (None, Some(timer)) => Some(timer),
+                _ => return Ok(None),
+            };
+
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                return Ok(None);
+            }
+
+            self.waker.wait(wake_at);
+        }
+    }
+}
+
+impl LokinitBackend for HeadlessBackend {
+    fn init() -> Self {
+        Self::new().unwrap_or_else(|why| panic!("{why}"))
+    }
+
+    fn create_window(&mut self, builder: WindowBuilder) -> Result<WindowHandle, LokinitError> {
+        let handle = WindowHandle(self.next_window_id);
+        self.next_window_id += 1;
+
+        self.windows.insert(
+            handle,
+            HeadlessWindow {
+                position: builder.position,
+                size: builder.size,
+                screen_mode: builder.screen_mode,
+                ime_allowed: true,
+                ime_cursor_area: None,
+                raw_mouse_motion: false,
+                cursor_grab: CursorGrab::None,
+                cursor_icon: CursorIcon::Default,
+                custom_cursor: None,
+                cursor_visible: true,
+                cursor_position: None,
+                builder,
+            },
+        );
+
+        Ok(handle)
+    }
+
+    fn close_window(&mut self, handle: WindowHandle) -> Result<(), LokinitError> {
+        self.windows
+            .remove(&handle)
+            .map(|_| ())
+            .ok_or(LokinitError::InvalidWindow(handle))
+    }
+
+    fn poll_event(&mut self) -> Result<Option<Event>, LokinitError> {
+        self.wait_event(None)
+    }
+
+    fn try_poll_event(&mut self) -> Result<Option<Event>, LokinitError> {
+        self.wait_event(Some(Duration::ZERO))
+    }
+
+    fn poll_event_timeout(&mut self, timeout: Duration) -> Result<Option<Event>, LokinitError> {
+        self.wait_event(Some(timeout))
+    }
+
+    fn dispatch_pending(&mut self) -> Result<(), LokinitError> {
+        self.proxy.dispatch(&mut self.event_queue);
+        self.timers.dispatch(&mut self.event_queue);
+        Ok(())
+    }
+
+    fn next_queued_event(&mut self) -> Option<Event> {
+        self.event_queue.pop_front()
+    }
+
+    #[cfg(unix)]
+    fn connection_fd(&self) -> Result<RawFd, LokinitError> {
+        Err(LokinitError::Unsupported(
+            "getting the connection to the display server with the headless backend",
+        ))
+    }
+
+    fn create_proxy(&mut self) -> Result<EventLoopProxy, LokinitError> {
+        Ok(self.proxy.clone())
+    }
+
+    fn set_timer(&mut self, duration: Duration, repeat: bool) -> Result<TimerId, LokinitError> {
+        Ok(self.timers.set(duration, repeat))
+    }
+
+    fn cancel_timer(&mut self, timer: TimerId) -> Result<(), LokinitError> {
+        self.timers.cancel(timer);
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    fn watch_fd(&mut self, _fd: RawFd, _interest: Interest) -> Result<(), LokinitError> {
+        Err(LokinitError::Unsupported(
+            "watching file descriptors with the headless backend",
+        ))
+    }
+
+    #[cfg(unix)]
+    fn unwatch_fd(&mut self, _fd: RawFd) -> Result<(), LokinitError> {
+        Err(LokinitError::Unsupported(
+            "watching file descriptors with the headless backend",
+        ))
+    }
+
+    fn set_screen_mode(
+        &mut self,
+        handle: WindowHandle,
+        screen_mode: ScreenMode,
+    ) -> Result<(), LokinitError> {
+        let window = self
+            .windows
+            .get_mut(&handle)
+            .ok_or(LokinitError::InvalidWindow(handle))?;
+        window.screen_mode = screen_mode;
+        Ok(())
+    }
+
+    fn fetch_monitors(&mut self) -> Result<Vec<Monitor>, LokinitError> {
+        Ok(Vec::new())
+    }
+
+    fn set_ime_cursor_area(
+        &mut self,
+        handle: WindowHandle,
+        area: WindowRect,
+    ) -> Result<(), LokinitError> {
+        let window = self
+            .windows
+            .get_mut(&handle)
+            .ok_or(LokinitError::InvalidWindow(handle))?;
+        window.ime_cursor_area = Some(area);
+        Ok(())
+    }
+
+    fn set_ime_allowed(&mut self, handle: WindowHandle, allowed: bool) -> Result<(), LokinitError> {
+        let window = self
+            .windows
+            .get_mut(&handle)
+            .ok_or(LokinitError::InvalidWindow(handle))?;
+        window.ime_allowed = allowed;
+        Ok(())
+    }
+
+    fn set_raw_mouse_motion(
+        &mut self,
+        handle: WindowHandle,
+        enabled: bool,
+    ) -> Result<(), LokinitError> {
+        let window = self
+            .windows
+            .get_mut(&handle)
+            .ok_or(LokinitError::InvalidWindow(handle))?;
+        window.raw_mouse_motion = enabled;
+        Ok(())
+    }
+
+    fn set_cursor_grab(
+        &mut self,
+        handle: WindowHandle,
+        grab: CursorGrab,
+    ) -> Result<(), LokinitError> {
+        let window = self
+            .windows
+            .get_mut(&handle)
+            .ok_or(LokinitError::InvalidWindow(handle))?;
+        window.cursor_grab = grab;
+        Ok(())
+    }
+
+    fn set_cursor_icon(
+        &mut self,
+        handle: WindowHandle,
+        icon: CursorIcon,
+    ) -> Result<(), LokinitError> {
+        let window = self
+            .windows
+            .get_mut(&handle)
+            .ok_or(LokinitError::InvalidWindow(handle))?;
+        window.cursor_icon = icon;
+        window.custom_cursor = None;
+        Ok(())
+    }
+
+    fn set_cursor_visible(
+        &mut self,
+        handle: WindowHandle,
+        visible: bool,
+    ) -> Result<(), LokinitError> {
+        let window = self
+            .windows
+            .get_mut(&handle)
+            .ok_or(LokinitError::InvalidWindow(handle))?;
+        window.cursor_visible = visible;
+        Ok(())
+    }
+
+    fn set_cursor_position(
+        &mut self,
+        handle: WindowHandle,
+        x: f64,
+        y: f64,
+    ) -> Result<(), LokinitError> {
+        let window = self
+            .windows
+            .get_mut(&handle)
+            .ok_or(LokinitError::InvalidWindow(handle))?;
+        window.cursor_position = Some((x, y));
+        Ok(())
+    }
+
+    fn create_cursor(&mut self, _frames: &[CursorFrame]) -> Result<CustomCursor, LokinitError> {
+        let cursor = CustomCursor(self.next_cursor_id);
+        self.next_cursor_id += 1;
+
+        self.cursors.insert(cursor);
+        Ok(cursor)
+    }
+
+    fn destroy_cursor(&mut self, cursor: CustomCursor) -> Result<(), LokinitError> {
+        if !self.cursors.remove(&cursor) {
+            return Err(LokinitError::InvalidCursor(cursor));
+        }
+
+        for window in self.windows.values_mut() {
+            if window.custom_cursor == Some(cursor) {
+                window.custom_cursor = None;
+            }
+        }
+        Ok(())
+    }
+
+    fn set_custom_cursor(
+        &mut self,
+        handle: WindowHandle,
+        cursor: CustomCursor,
+    ) -> Result<(), LokinitError> {
+        if !self.cursors.contains(&cursor) {
+            return Err(LokinitError::InvalidCursor(cursor));
+        }
+        let window = self
+            .windows
+            .get_mut(&handle)
+            .ok_or(LokinitError::InvalidWindow(handle))?;
+        window.custom_cursor = Some(cursor);
+        Ok(())
+    }
+
+    #[cfg(feature = "opengl")]
+    fn create_window_surface(
+        &mut self,
+        _window: WindowHandle,
+        _cfg: crate::prelude::OpenGlConfig,
+    ) -> Result<crate::native::WindowSurface, LokinitError> {
+        Err(LokinitError::Unsupported("OpenGL"))
+    }
+
+    #[cfg(feature = "opengl")]
+    fn load_opengl_func(&mut self, _proc_name: *const std::ffi::c_char) -> *mut std::ffi::c_void {
+        std::ptr::null_mut()
+    }
+
+    #[cfg(feature = "opengl")]
+    fn make_surface_active(
+        &self,
+        _window: WindowHandle,
+        _surface: crate::native::WindowSurface,
+    ) -> Result<(), LokinitError> {
+        Err(LokinitError::Unsupported("OpenGL"))
+    }
+
+    #[cfg(feature = "opengl")]
+    fn flush_surface(
+        &self,
+        _window: WindowHandle,
+        _surface: crate::native::WindowSurface,
+    ) -> Result<(), LokinitError> {
+        Err(LokinitError::Unsupported("OpenGL"))
+    }
+
+    #[cfg(feature = "opengl")]
+    fn update_surface(&self, _surface: crate::native::WindowSurface) -> Result<(), LokinitError> {
+        Err(LokinitError::Unsupported("OpenGL"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn next_kind(backend: &mut HeadlessBackend) -> Option<EventKind> {
+        backend.try_poll_event().unwrap().map(|event| event.kind)
+    }
+
+    #[test]
+    fn idle_backend_returns_proxy_events() {
+        let mut backend = HeadlessBackend::new().unwrap();
+        assert!(next_kind(&mut backend).is_none());
+
+        let proxy = backend.create_proxy().unwrap();
+        proxy.send_event(1);
+        proxy.send_event(2);
+
+        let event = backend.poll_event().unwrap();
+        assert!(matches!(
+            event.map(|event| event.kind),
+            Some(EventKind::User(1))
+        ));
+        assert!(matches!(next_kind(&mut backend), Some(EventKind::User(2))));
+        assert!(next_kind(&mut backend).is_none());
+    }
+
+    #[test]
+    fn proxy_wakes_up_a_waiting_backend() {
+        let mut backend = HeadlessBackend::new().unwrap();
+        let proxy = backend.create_proxy().unwrap();
+
+        let sender = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            proxy.send_event(7);
+        });
+
+        let event = backend.poll_event_timeout(Duration::from_secs(10)).unwrap();
+        assert!(matches!(
+            event.map(|event| event.kind),
+            Some(EventKind::User(7))
+        ));
+        sender.join().unwrap();
+    }
+
+    #[test]
+    fn timers_fire_and_can_be_cancelled() {
+        let mut backend = HeadlessBackend::new().unwrap();
+        let timer = backend.set_timer(Duration::from_millis(5), false).unwrap();
+        let cancelled = backend.set_timer(Duration::from_millis(5), false).unwrap();
+        backend.cancel_timer(cancelled).unwrap();
+
+        // Waits for the timer even without a timeout, since it's bound to fire
+        let event = backend.poll_event().unwrap();
+        assert!(matches!(event.map(|event| event.kind), Some(EventKind::Timer(t)) if t == timer));
+        assert!(backend.poll_event().unwrap().is_none());
+    }
+}
@@ -1,6 +1,15 @@
-use crate::event::Event;
-use crate::lok::{CreateWindowError, LokinitBackend};
-use crate::window::{ScreenMode, WindowBuilder, WindowHandle};
+use crate::cursor::{CursorFrame, CursorIcon, CustomCursor};
+use crate::error::LokinitError;
+use crate::event::{Event, Modifiers};
+use crate::event_loop::{EventLoopProxy, Interest, TimerId};
+use crate::lok::{InitAttempt, InitError, InitOptions, LokinitBackend, Preference};
+use crate::window::{CursorGrab, ScreenMode, WindowBuilder, WindowHandle, WindowRect};
+
+use loki_linux::x11::xkey_mask;
+
+use std::os::fd::RawFd;
+use std::rc::Rc;
+use std::time::Duration;
 
 use wayland::WaylandBackend;
 use x11::X11Backend;
@@ -11,6 +20,8 @@ use crate::gl::*;
 #[cfg(feature = "opengl")]
 pub mod opengl;
 
+pub mod event_loop;
+pub mod scancode;
 pub mod wayland;
 pub mod x11;
 
@@ -19,54 +30,256 @@ pub enum LinuxBackend {
     Wayland(WaylandBackend),
 }
 
+impl LinuxBackend {
+    fn try_init(backend: Preference, options: &InitOptions) -> Result<Self, Rc<str>> {
+        match backend {
+            Preference::X11 => X11Backend::init(options.x11_display.as_deref())
+                .map(Self::X11)
+                .map_err(|why| why.to_string().into()),
+            Preference::Wayland => WaylandBackend::new(options.wayland_display.as_deref())
+                .map(Self::Wayland)
+                .map_err(|why| why.to_string().into()),
+            Preference::Auto | Preference::Headless => unreachable!(),
+        }
+    }
+}
+
 impl LokinitBackend for LinuxBackend {
     fn init() -> Self {
-        match std::env::var("LOKINIT_BACKEND") {
-            Ok(x) if x == "wayland" => Self::Wayland(WaylandBackend::init()),
-            Ok(x) if x == "x11" => Self::X11(X11Backend::init().unwrap()),
-
-            _ => match WaylandBackend::new() {
-                Ok(x) => Self::Wayland(x),
-                Err(why) => {
-                    eprintln!(
-                        "Lokinit: Failed to initialize Wayland backend, falling back on X11\n\n{why}"
-                    );
-                    Self::X11(X11Backend::init().unwrap())
-                }
+        Self::init_with(&InitOptions::default()).unwrap_or_else(|why| panic!("{why}"))
+    }
+
+    fn init_with(options: &InitOptions) -> Result<Self, InitError> {
+        let preference = match options.backend {
+            Preference::Auto => match std::env::var("LOKINIT_BACKEND") {
+                Ok(x) if x == "x11" => Preference::X11,
+                _ => Preference::Wayland,
             },
+            backend => backend,
+        };
+
+        let order: &[Preference] = match preference {
+            Preference::X11 => &[Preference::X11, Preference::Wayland],
+            _ => &[Preference::Wayland, Preference::X11],
+        };
+
+        let mut attempts = Vec::new();
+        for &backend in order {
+            match Self::try_init(backend, options) {
+                Ok(this) => return Ok(this),
+                Err(reason) => attempts.push(InitAttempt { backend, reason }),
+            }
         }
+
+        Err(InitError { attempts })
     }
 
-    fn create_window(&mut self, builder: WindowBuilder) -> Result<WindowHandle, CreateWindowError> {
+    fn create_window(&mut self, builder: WindowBuilder) -> Result<WindowHandle, LokinitError> {
         match self {
             Self::X11(x11) => x11.create_window(builder),
             Self::Wayland(wl) => wl.create_window(builder),
         }
     }
 
-    fn close_window(&mut self, handle: WindowHandle) {
+    fn close_window(&mut self, handle: WindowHandle) -> Result<(), LokinitError> {
         match self {
             Self::X11(x11) => x11.close_window(handle),
             Self::Wayland(wl) => wl.close_window(handle),
         }
     }
 
-    fn poll_event(&mut self) -> Option<Event> {
+    fn poll_event(&mut self) -> Result<Option<Event>, LokinitError> {
         match self {
             Self::X11(x11) => x11.poll_event(),
             Self::Wayland(wl) => wl.poll_event(),
         }
     }
 
-    fn set_screen_mode(&mut self, handle: WindowHandle, screen_mode: ScreenMode) {
+    fn try_poll_event(&mut self) -> Result<Option<Event>, LokinitError> {
+        match self {
+            Self::X11(x11) => x11.try_poll_event(),
+            Self::Wayland(wl) => wl.try_poll_event(),
+        }
+    }
+
+    fn poll_event_timeout(&mut self, timeout: Duration) -> Result<Option<Event>, LokinitError> {
+        match self {
+            Self::X11(x11) => x11.poll_event_timeout(timeout),
+            Self::Wayland(wl) => wl.poll_event_timeout(timeout),
+        }
+    }
+
+    fn dispatch_pending(&mut self) -> Result<(), LokinitError> {
+        match self {
+            Self::X11(x11) => x11.dispatch_pending(),
+            Self::Wayland(wl) => wl.dispatch_pending(),
+        }
+    }
+
+    fn next_queued_event(&mut self) -> Option<Event> {
+        match self {
+            Self::X11(x11) => x11.next_queued_event(),
+            Self::Wayland(wl) => wl.next_queued_event(),
+        }
+    }
+
+    fn connection_fd(&self) -> Result<RawFd, LokinitError> {
+        match self {
+            Self::X11(x11) => x11.connection_fd(),
+            Self::Wayland(wl) => wl.connection_fd(),
+        }
+    }
+
+    fn create_proxy(&mut self) -> Result<EventLoopProxy, LokinitError> {
+        match self {
+            Self::X11(x11) => x11.create_proxy(),
+            Self::Wayland(wl) => wl.create_proxy(),
+        }
+    }
+
+    fn set_timer(&mut self, duration: Duration, repeat: bool) -> Result<TimerId, LokinitError> {
+        match self {
+            Self::X11(x11) => x11.set_timer(duration, repeat),
+            Self::Wayland(wl) => wl.set_timer(duration, repeat),
+        }
+    }
+
+    fn cancel_timer(&mut self, timer: TimerId) -> Result<(), LokinitError> {
+        match self {
+            Self::X11(x11) => x11.cancel_timer(timer),
+            Self::Wayland(wl) => wl.cancel_timer(timer),
+        }
+    }
+
+    fn watch_fd(&mut self, fd: RawFd, interest: Interest) -> Result<(), LokinitError> {
+        match self {
+            Self::X11(x11) => x11.watch_fd(fd, interest),
+            Self::Wayland(wl) => wl.watch_fd(fd, interest),
+        }
+    }
+
+    fn unwatch_fd(&mut self, fd: RawFd) -> Result<(), LokinitError> {
+        match self {
+            Self::X11(x11) => x11.unwatch_fd(fd),
+            Self::Wayland(wl) => wl.unwatch_fd(fd),
+        }
+    }
+
+    fn set_screen_mode(
+        &mut self,
+        handle: WindowHandle,
+        screen_mode: ScreenMode,
+    ) -> Result<(), LokinitError> {
         match self {
             Self::X11(x11) => x11.set_screen_mode(handle, screen_mode),
             Self::Wayland(wl) => wl.set_screen_mode(handle, screen_mode),
         }
     }
 
-    fn fetch_monitors(&mut self) -> Vec<crate::prelude::Monitor> {
-        todo!("fetch monitors")
+    fn fetch_monitors(&mut self) -> Result<Vec<crate::prelude::Monitor>, LokinitError> {
+        match self {
+            Self::X11(x11) => x11.fetch_monitors(),
+            Self::Wayland(wl) => wl.fetch_monitors(),
+        }
+    }
+
+    fn set_ime_cursor_area(
+        &mut self,
+        handle: WindowHandle,
+        area: WindowRect,
+    ) -> Result<(), LokinitError> {
+        match self {
+            Self::X11(x11) => x11.set_ime_cursor_area(handle, area),
+            Self::Wayland(wl) => wl.set_ime_cursor_area(handle, area),
+        }
+    }
+
+    fn set_ime_allowed(&mut self, handle: WindowHandle, allowed: bool) -> Result<(), LokinitError> {
+        match self {
+            Self::X11(x11) => x11.set_ime_allowed(handle, allowed),
+            Self::Wayland(wl) => wl.set_ime_allowed(handle, allowed),
+        }
+    }
+
+    fn set_raw_mouse_motion(
+        &mut self,
+        handle: WindowHandle,
+        enabled: bool,
+    ) -> Result<(), LokinitError> {
+        match self {
+            Self::X11(x11) => x11.set_raw_mouse_motion(handle, enabled),
+            Self::Wayland(wl) => wl.set_raw_mouse_motion(handle, enabled),
+        }
+    }
+
+    fn set_cursor_grab(
+        &mut self,
+        handle: WindowHandle,
+        grab: CursorGrab,
+    ) -> Result<(), LokinitError> {
+        match self {
+            Self::X11(x11) => x11.set_cursor_grab(handle, grab),
+            Self::Wayland(wl) => wl.set_cursor_grab(handle, grab),
+        }
+    }
+
+    fn set_cursor_icon(
+        &mut self,
+        handle: WindowHandle,
+        icon: CursorIcon,
+    ) -> Result<(), LokinitError> {
+        match self {
+            Self::X11(x11) => x11.set_cursor_icon(handle, icon),
+            Self::Wayland(wl) => wl.set_cursor_icon(handle, icon),
+        }
+    }
+
+    fn set_cursor_visible(
+        &mut self,
+        handle: WindowHandle,
+        visible: bool,
+    ) -> Result<(), LokinitError> {
+        match self {
+            Self::X11(x11) => x11.set_cursor_visible(handle, visible),
+            Self::Wayland(wl) => wl.set_cursor_visible(handle, visible),
+        }
+    }
+
+    fn set_cursor_position(
+        &mut self,
+        handle: WindowHandle,
+        x: f64,
+        y: f64,
+    ) -> Result<(), LokinitError> {
+        match self {
+            Self::X11(x11) => x11.set_cursor_position(handle, x, y),
+            Self::Wayland(wl) => wl.set_cursor_position(handle, x, y),
+        }
+    }
+
+    fn create_cursor(&mut self, frames: &[CursorFrame]) -> Result<CustomCursor, LokinitError> {
+        match self {
+            Self::X11(x11) => x11.create_cursor(frames),
+            Self::Wayland(wl) => wl.create_cursor(frames),
+        }
+    }
+
+    fn destroy_cursor(&mut self, cursor: CustomCursor) -> Result<(), LokinitError> {
+        match self {
+            Self::X11(x11) => x11.destroy_cursor(cursor),
+            Self::Wayland(wl) => wl.destroy_cursor(cursor),
+        }
+    }
+
+    fn set_custom_cursor(
+        &mut self,
+        handle: WindowHandle,
+        cursor: CustomCursor,
+    ) -> Result<(), LokinitError> {
+        match self {
+            Self::X11(x11) => x11.set_custom_cursor(handle, cursor),
+            Self::Wayland(wl) => wl.set_custom_cursor(handle, cursor),
+        }
     }
 
     #[cfg(feature = "opengl")]
@@ -74,7 +287,7 @@ impl LokinitBackend for LinuxBackend {
         &mut self,
         handle: WindowHandle,
         config: OpenGlConfig,
-    ) -> WindowSurface {
+    ) -> Result<WindowSurface, LokinitError> {
         match self {
             Self::X11(x11) => x11.create_window_surface(handle, config),
             Self::Wayland(wl) => wl.create_window_surface(handle, config),
@@ -90,7 +303,11 @@ impl LokinitBackend for LinuxBackend {
     }
 
     #[cfg(feature = "opengl")]
-    fn make_surface_active(&self, handle: WindowHandle, surface: super::WindowSurface) {
+    fn make_surface_active(
+        &self,
+        handle: WindowHandle,
+        surface: super::WindowSurface,
+    ) -> Result<(), LokinitError> {
         match self {
             Self::X11(x11) => x11.make_surface_active(handle, surface),
             Self::Wayland(wl) => wl.make_surface_active(handle, surface),
@@ -98,7 +315,11 @@ impl LokinitBackend for LinuxBackend {
     }
 
     #[cfg(feature = "opengl")]
-    fn flush_surface(&self, handle: WindowHandle, surface: super::WindowSurface) {
+    fn flush_surface(
+        &self,
+        handle: WindowHandle,
+        surface: super::WindowSurface,
+    ) -> Result<(), LokinitError> {
         match self {
             Self::X11(x11) => x11.flush_surface(handle, surface),
             Self::Wayland(wl) => wl.flush_surface(handle, surface),
@@ -106,7 +327,26 @@ impl LokinitBackend for LinuxBackend {
     }
 
     #[cfg(feature = "opengl")]
-    fn update_surface(&self, surface: super::WindowSurface) {
-        todo!()
+    fn update_surface(&self, surface: super::WindowSurface) -> Result<(), LokinitError> {
+        match self {
+            Self::X11(x11) => x11.update_surface(surface),
+            Self::Wayland(wl) => wl.update_surface(surface),
+        }
     }
 }
+
+/// Converts a core X11 modifier mask into [`Modifiers`]. Wayland compositors send the same masks in
+/// `wl_keyboard.modifiers`, since they follow xkb's default modifier mapping.
+pub(crate) fn modifiers_from_mask(mask: u32) -> Modifiers {
+    [
+        (xkey_mask::SHIFT, Modifiers::SHIFT),
+        (xkey_mask::LOCK, Modifiers::CAPS_LOCK),
+        (xkey_mask::CONTROL, Modifiers::CTRL),
+        (xkey_mask::MOD1, Modifiers::ALT),
+        (xkey_mask::MOD2, Modifiers::NUM_LOCK),
+        (xkey_mask::MOD4, Modifiers::SUPER),
+    ]
+    .into_iter()
+    .filter(|(bit, _)| mask & bit != 0)
+    .fold(Modifiers::empty(), |modifiers, (_, flag)| modifiers | flag)
+}
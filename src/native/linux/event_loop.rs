@@ -0,0 +1,172 @@
+use std::collections::VecDeque;
+use std::io;
+use std::os::fd::{AsRawFd, RawFd};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use loki_linux::eventfd::EventFd;
+use loki_linux::poll::{self, PollFd, POLLERR, POLLHUP, POLLIN, POLLNVAL, POLLOUT};
+
+use crate::event::{Event, EventKind};
+use crate::event_loop::{EventLoopProxy, Interest, TimerId, Timers};
+use crate::window::WindowHandle;
+
+/// The event sources that the X11 and Wayland backends wait on alongside their connection to the
+/// display server.
+pub struct EventLoop {
+    waker: Arc<EventFd>,
+    proxy: EventLoopProxy,
+    timers: Timers,
+    /// File descriptors registered with [`lok::watch_fd`](crate::lok::watch_fd).
+    watched: Vec<PollFd>,
+}
+
+impl EventLoop {
+    pub fn new() -> io::Result<Self> {
+        let waker = Arc::new(EventFd::new()?);
+
+        let proxy_waker = waker.clone();
+        let proxy = EventLoopProxy::new(move || proxy_waker.notify());
+
+        Ok(Self {
+            waker,
+            proxy,
+            timers: Timers::default(),
+            watched: Vec::new(),
+        })
+    }
+
+    pub fn create_proxy(&self) -> EventLoopProxy {
+        self.proxy.clone()
+    }
+
+    pub fn set_timer(&mut self, duration: Duration, repeat: bool) -> TimerId {
+        self.timers.set(duration, repeat)
+    }
+
+    pub fn cancel_timer(&mut self, timer: TimerId) {
+        self.timers.cancel(timer);
+    }
+
+    pub fn watch_fd(&mut self, fd: RawFd, interest: Interest) {
+        let mut events = 0;
+        if interest.is_readable() {
+            events |= POLLIN;
+        }
+        if interest.is_writable() {
+            events |= POLLOUT;
+        }
+
+        match self.watched.iter_mut().find(|watched| watched.fd == fd) {
+            Some(watched) => watched.events = events,
+            None => self.watched.push(PollFd::new(fd, events)),
+        }
+    }
+
+    pub fn unwatch_fd(&mut self, fd: RawFd) {
+        self.watched.retain(|watched| watched.fd != fd);
+    }
+
+    /// Whether there are no timers, watched file descriptors nor proxies that could produce an
+    /// event.
+    pub fn is_idle(&self) -> bool {
+        self.timers.is_empty() && self.watched.is_empty() && !self.proxy.is_shared()
+    }
+
+    /// Pushes the events coming from Lokinit's own event sources into the event queue.
+    pub fn dispatch(&mut self, event_queue: &mut VecDeque<Event>) {
+        self.proxy.dispatch(event_queue);
+        self.timers.dispatch(event_queue);
+        self.dispatch_watched(event_queue);
+    }
+
+    /// Checks the watched file descriptors without blocking, and pushes an event for each one
+    /// that is ready.
+    fn dispatch_watched(&mut self, event_queue: &mut VecDeque<Event>) {
+        if self.watched.is_empty()
+            || !matches!(
+                poll::poll_fds(&mut self.watched, Some(Duration::ZERO)),
+                Ok(1..)
+            )
+        {
+            return;
+        }
+
+        // A file descriptor that was closed without being unwatched would be reported forever
+        self.watched.retain(|watched| !watched.has(POLLNVAL));
+
+        for watched in &self.watched {
+            // Errors and hangups are reported as readiness, so that the next read or write
+            // returns them
+            let mut ready = None;
+            if watched.has(POLLIN | POLLHUP | POLLERR) && watched.events & POLLIN != 0 {
+                ready = Some(Interest::READABLE);
+            }
+            if watched.has(POLLOUT | POLLHUP | POLLERR) && watched.events & POLLOUT != 0 {
+                ready = Some(ready.map_or(Interest::WRITABLE, |ready| ready | Interest::WRITABLE));
+            }
+
+            if let Some(ready) = ready {
+                event_queue.push_back(Event {
+                    time: Duration::ZERO,
+                    window: WindowHandle::NONE,
+                    kind: EventKind::FdReady(watched.fd, ready),
+                });
+            }
+        }
+    }
+
+    /// Blocks the thread until the display server's `connection` has data to read, an event
+    /// source needs to be dispatched, a watched file descriptor is ready, or the deadline is
+    /// reached. A deadline of `None` waits indefinitely.
+    ///
+    /// Returns `false` if the deadline was reached first, and an error if `poll` failed or if the
+    /// display server hung up without leaving anything to read.
+    pub fn wait(&mut self, connection: RawFd, deadline: Option<Instant>) -> io::Result<bool> {
+        let next_timer = self.timers.next_deadline();
+        let wake_up = match (deadline, next_timer) {
+            (Some(deadline), Some(next_timer)) => Some(deadline.min(next_timer)),
+            (deadline, next_timer) => deadline.or(next_timer),
+        };
+
+        let timeout = match wake_up {
+            Some(wake_up) => match wake_up.checked_duration_since(Instant::now()) {
+                Some(remaining) if !remaining.is_zero() => Some(remaining),
+                _ => return Ok(!Self::is_reached(deadline)),
+            },
+            None => None,
+        };
+
+        // Watched file descriptors are only here to wake us up, their readiness is picked up by the
+        // next dispatch
+        let mut fds = vec![
+            PollFd::new(connection, POLLIN),
+            PollFd::new(self.waker.as_raw_fd(), POLLIN),
+        ];
+        fds.extend(
+            self.watched
+                .iter()
+                .map(|watched| PollFd::new(watched.fd, watched.events)),
+        );
+
+        if poll::poll_fds(&mut fds, timeout)? == 0 {
+            return Ok(!Self::is_reached(deadline));
+        }
+
+        // The data left before a hangup is still read, the error then comes from reading past it
+        if fds[0].has(POLLHUP | POLLERR | POLLNVAL) && !fds[0].has(POLLIN) {
+            return Err(io::Error::new(
+                io::ErrorKind::ConnectionAborted,
+                "The display server closed the connection",
+            ));
+        }
+        if fds[1].has(POLLIN) {
+            self.waker.reset();
+        }
+        Ok(true)
+    }
+
+    fn is_reached(deadline: Option<Instant>) -> bool {
+        deadline.is_some_and(|deadline| deadline <= Instant::now())
+    }
+}
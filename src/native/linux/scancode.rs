@@ -0,0 +1,162 @@
+use crate::keycode::KeyCode;
+
+/// Maps an evdev code to the key at that position on a US QWERTY keyboard.
+///
+/// The codes come from `linux/input-event-codes.h`. X servers using evdev report them with an
+/// offset of 8, and Wayland compositors report them as is.
+pub fn to_keycode(scancode: u32) -> Option<KeyCode> {
+    Some(match scancode {
+        1 => KeyCode::Escape,
+        2 => KeyCode::Key1,
+        3 => KeyCode::Key2,
+        4 => KeyCode::Key3,
+        5 => KeyCode::Key4,
+        6 => KeyCode::Key5,
+        7 => KeyCode::Key6,
+        8 => KeyCode::Key7,
+        9 => KeyCode::Key8,
+        10 => KeyCode::Key9,
+        11 => KeyCode::Key0,
+        12 => KeyCode::Minus,
+        13 => KeyCode::Equals,
+        14 => KeyCode::Backspace,
+        15 => KeyCode::Tab,
+
+        16 => KeyCode::Q,
+        17 => KeyCode::W,
+        18 => KeyCode::E,
+        19 => KeyCode::R,
+        20 => KeyCode::T,
+        21 => KeyCode::Y,
+        22 => KeyCode::U,
+        23 => KeyCode::I,
+        24 => KeyCode::O,
+        25 => KeyCode::P,
+        26 => KeyCode::LBracket,
+        27 => KeyCode::RBracket,
+        28 => KeyCode::Enter,
+        29 => KeyCode::LCtrl,
+
+        30 => KeyCode::A,
+        31 => KeyCode::S,
+        32 => KeyCode::D,
+        33 => KeyCode::F,
+        34 => KeyCode::G,
+        35 => KeyCode::H,
+        36 => KeyCode::J,
+        37 => KeyCode::K,
+        38 => KeyCode::L,
+        39 => KeyCode::Semicolon,
+        40 => KeyCode::SingleQuote,
+        41 => KeyCode::Backtick,
+        42 => KeyCode::LShift,
+        43 => KeyCode::Backslash,
+
+        44 => KeyCode::Z,
+        45 => KeyCode::X,
+        46 => KeyCode::C,
+        47 => KeyCode::V,
+        48 => KeyCode::B,
+        49 => KeyCode::N,
+        50 => KeyCode::M,
+        51 => KeyCode::Comma,
+        52 => KeyCode::Point,
+        53 => KeyCode::Slash,
+        54 => KeyCode::RShift,
+        55 => KeyCode::NumpadMultiply,
+        56 => KeyCode::LAlt,
+        57 => KeyCode::Space,
+        58 => KeyCode::CapsLock,
+
+        59 => KeyCode::F1,
+        60 => KeyCode::F2,
+        61 => KeyCode::F3,
+        62 => KeyCode::F4,
+        63 => KeyCode::F5,
+        64 => KeyCode::F6,
+        65 => KeyCode::F7,
+        66 => KeyCode::F8,
+        67 => KeyCode::F9,
+        68 => KeyCode::F10,
+
+        69 => KeyCode::NumLock,
+        70 => KeyCode::ScrollLock,
+        71 => KeyCode::Numpad7,
+        72 => KeyCode::Numpad8,
+        73 => KeyCode::Numpad9,
+        74 => KeyCode::NumpadSubtract,
+        75 => KeyCode::Numpad4,
+        76 => KeyCode::Numpad5,
+        77 => KeyCode::Numpad6,
+        78 => KeyCode::NumpadAdd,
+        79 => KeyCode::Numpad1,
+        80 => KeyCode::Numpad2,
+        81 => KeyCode::Numpad3,
+        82 => KeyCode::Numpad0,
+        83 => KeyCode::NumpadDecimal,
+
+        86 => KeyCode::IntlBackslash,
+        87 => KeyCode::F11,
+        88 => KeyCode::F12,
+        89 => KeyCode::IntlRo,
+        92 => KeyCode::Henkan,
+        93 => KeyCode::KanaMode,
+        94 => KeyCode::Muhenkan,
+
+        96 => KeyCode::NumpadEnter,
+        97 => KeyCode::RCtrl,
+        98 => KeyCode::NumpadDivide,
+        99 => KeyCode::PrintScreen,
+        100 => KeyCode::RAlt,
+
+        102 => KeyCode::Home,
+        103 => KeyCode::ArrowUp,
+        104 => KeyCode::PageUp,
+        105 => KeyCode::ArrowLeft,
+        106 => KeyCode::ArrowRight,
+        107 => KeyCode::End,
+        108 => KeyCode::ArrowDown,
+        109 => KeyCode::PageDown,
+        110 => KeyCode::Insert,
+        111 => KeyCode::Delete,
+
+        113 => KeyCode::VolumeMute,
+        114 => KeyCode::VolumeDown,
+        115 => KeyCode::VolumeUp,
+        117 => KeyCode::NumpadEquals,
+        119 => KeyCode::Pause,
+        121 => KeyCode::NumpadComma,
+        122 => KeyCode::Hangul,
+        123 => KeyCode::Hanja,
+        124 => KeyCode::IntlYen,
+        125 => KeyCode::LSuper,
+        126 => KeyCode::RSuper,
+        127 => KeyCode::Menu,
+
+        158 => KeyCode::BrowserBack,
+        159 => KeyCode::BrowserForward,
+
+        163 => KeyCode::NextTrack,
+        164 => KeyCode::Play,
+        165 => KeyCode::PrevTrack,
+        166 => KeyCode::Stop,
+
+        183 => KeyCode::F13,
+        184 => KeyCode::F14,
+        185 => KeyCode::F15,
+        186 => KeyCode::F16,
+        187 => KeyCode::F17,
+        188 => KeyCode::F18,
+        189 => KeyCode::F19,
+        190 => KeyCode::F20,
+        191 => KeyCode::F21,
+        192 => KeyCode::F22,
+        193 => KeyCode::F23,
+        194 => KeyCode::F24,
+
+        224 => KeyCode::BrightnessDown,
+        225 => KeyCode::BrightnessUp,
+
+        _ => return None,
+    })
+}
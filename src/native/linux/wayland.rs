@@ -1,22 +1,34 @@
 use {
+    super::event_loop::EventLoop,
     crate::{
-        event::Event,
-        lok::{CreateWindowError, LokinitBackend},
+        cursor::{CursorFrame, CursorIcon, CustomCursor},
+        error::LokinitError,
+        event::{Event, EventKind, KeyboardEvent, Modifiers},
+        event_loop::{EventLoopProxy, Interest, TimerId},
+        lok::LokinitBackend,
         native::WindowId,
         prelude::{Monitor, WindowBuilder, WindowHandle},
-        window::ScreenMode,
+        window::{CursorGrab, ScreenMode, WindowRect},
     },
     loki_linux::{
+        cursor_theme::{CursorImage, CursorTheme},
         hashnt::Hashnt,
-        wayland::{interfaces::all::*, methods::*, wire::Id, WaylandClient},
+        poll,
+        wayland::{
+            interfaces::all::*,
+            methods::*,
+            wire::{Fixed, Id},
+            WaylandClient,
+        },
     },
     shm::{Buffer, ImageInfo, ShmAllocatorAllocator},
     std::{
         cell::{Cell, OnceCell},
         collections::{HashMap, VecDeque},
+        io,
+        os::fd::{AsRawFd, RawFd},
         rc::Rc,
-        thread,
-        time::Duration,
+        time::{Duration, Instant},
     },
 };
 
@@ -29,11 +41,57 @@ pub struct WaylandBackend {
     pub windows: Vec<Option<WaylandWindow>>,
     pub object_to_window_map: HashMap<Id, WindowId, Hashnt>,
     pub shm: OnceCell<ShmAllocatorAllocator>,
+    pub event_loop: EventLoop,
+    /// The seat's pointer, once the compositor announced that it has one.
+    pub pointer: Option<WlPointer>,
+    /// The window that has the pointer focus.
+    pub pointer_focus: Option<WindowId>,
+    /// Where the pointer is in the focused window.
+    pub pointer_position: (f64, f64),
+    /// The serial of the last `wl_pointer.enter` event, which changing the cursor requires.
+    pub pointer_serial: u32,
+    /// Sets the pointer's cursor, if the compositor supports cursor shapes.
+    pub cursor_shape_device: Option<WpCursorShapeDeviceV1>,
+    /// The theme that cursor icons are drawn from when the compositor can't draw them.
+    pub cursor_theme: CursorTheme,
+    /// The cursors loaded from the theme for each buffer scale, or `None` for the icons it doesn't
+    /// have.
+    pub theme_cursors: HashMap<(CursorIcon, i32), Option<CustomCursor>>,
+    /// The surface that shows custom cursors, created with the first one.
+    pub cursor_surface: Option<WlSurface>,
+    /// The frames of the cursors created by `create_cursor`.
+    pub custom_cursors: HashMap<CustomCursor, Vec<ShmCursorFrame>>,
+    pub next_cursor_id: u64,
+    /// The animated cursor being shown, if any.
+    pub cursor_animation: Option<CursorAnimation>,
+    /// The scrolling received since the last `wl_pointer.frame` event.
+    pub scroll_frame: ScrollFrame,
+    /// Whether fingers are scrolling, between the first scroll event and `wl_pointer.axis_stop`.
+    pub scrolling: bool,
+    /// The pointer's relative motion, while some window wants raw motion events.
+    pub relative_pointer: Option<ZwpRelativePointerV1>,
+    /// The seat's keyboard, once the compositor announced that it has one.
+    pub keyboard: Option<WlKeyboard>,
+    /// The window that has the keyboard focus.
+    pub keyboard_focus: Option<WindowId>,
+    /// The modifiers reported by the last `ModifiersChanged` event.
+    pub modifiers: Modifiers,
+    /// The seat's text input, if the compositor supports input methods.
+    pub text_input: Option<ZwpTextInputV3>,
+    /// The window that the text input is focused on.
+    pub text_input_focus: Option<WindowId>,
+    /// The preedit text and its cursor range received since the last `done` event.
+    pub pending_preedit: Option<(String, Option<(usize, usize)>)>,
+    /// The text to commit received since the last `done` event.
+    pub pending_commit: Option<String>,
+    /// Whether the last `ImePreedit` event had some text.
+    pub has_preedit: bool,
 }
 
 impl WaylandBackend {
-    pub fn new() -> std::io::Result<Self> {
-        let client = WaylandClient::new()?;
+    /// Connects to the compositor, either the one named by `display` or the default one.
+    pub fn new(display: Option<&str>) -> Result<Self, LokinitError> {
+        let client = WaylandClient::new(display)?;
 
         let mut this = Self {
             client,
@@ -41,9 +99,38 @@ impl WaylandBackend {
             windows: Vec::default(),
             object_to_window_map: HashMap::default(),
             shm: OnceCell::new(),
+            event_loop: EventLoop::new()?,
+            pointer: None,
+            pointer_focus: None,
+            pointer_position: (0.0, 0.0),
+            pointer_serial: 0,
+            cursor_shape_device: None,
+            cursor_theme: CursorTheme::from_env(),
+            theme_cursors: HashMap::new(),
+            cursor_surface: None,
+            custom_cursors: HashMap::new(),
+            next_cursor_id: 0,
+            cursor_animation: None,
+            scroll_frame: ScrollFrame::default(),
+            scrolling: false,
+            relative_pointer: None,
+            keyboard: None,
+            keyboard_focus: None,
+            modifiers: Modifiers::empty(),
+            text_input: None,
+            text_input_focus: None,
+            pending_preedit: None,
+            pending_commit: None,
+            has_preedit: false,
         };
 
-        this.roundtrip();
+        this.roundtrip()?;
+
+        // The text input needs both of these globals, which may be announced in any order
+        let text_input_manager = this.client.try_get_global::<ZwpTextInputManagerV3>();
+        if let (Some(manager), Some(seat)) = (text_input_manager, this.client.try_get_global()) {
+            this.text_input = Some(manager.get_text_input(&mut this.client, seat));
+        }
         if this.shm.set(ShmAllocatorAllocator::default()).is_err() {
             unreachable!();
         }
@@ -55,7 +142,7 @@ impl WaylandBackend {
 
     /// Sends a [`WlDisplayMethod::Sync`] event to the compositor, and blocks the thread until
     /// it completes. While blocking, it processes and queues events to be handled later.
-    pub fn roundtrip(&mut self) {
+    pub fn roundtrip(&mut self) -> Result<(), LokinitError> {
         let finished = Rc::new(Cell::new(false));
         let finished_clone = finished.clone();
         let callback = WlCallback::new(
@@ -67,22 +154,358 @@ impl WaylandBackend {
             .call_method(&WlDisplay::global(), WlDisplayMethod::Sync(callback));
 
         while !finished.get() {
-            while let Some(event) = self.client.next_event(true) {
-                if let Some(event) = self.handle_event(event) {
-                    self.event_queue.push_back(event);
+            match self.client.next_event(true)? {
+                Some(event) => {
+                    if let Some(event) = self.handle_event(event)? {
+                        self.event_queue.push_back(event);
+                    }
+                }
+                None => {
+                    poll::wait_readable(self.client.socket.as_raw_fd(), None)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Enables the text input if the window it's focused on allows input methods, and disables
+    /// it otherwise.
+    pub fn update_text_input(&mut self) {
+        let Some(text_input) = self.text_input else {
+            return;
+        };
+
+        let window = self
+            .text_input_focus
+            .and_then(|window_id| self.windows[window_id].as_ref());
+        match window {
+            Some(window) if window.ime_allowed => {
+                // Enabling resets the text input's state, so the cursor area has to be sent again
+                self.client
+                    .call_method(&text_input, ZwpTextInputV3Method::Enable);
+                if let Some(area) = window.ime_cursor_area {
+                    self.client.call_method(&text_input, cursor_rectangle(area));
+                }
+            }
+            _ => {
+                self.client
+                    .call_method(&text_input, ZwpTextInputV3Method::Disable);
+                self.clear_preedit();
+            }
+        }
+        self.client
+            .call_method(&text_input, ZwpTextInputV3Method::Commit);
+    }
+
+    /// Creates the relative pointer if a window wants raw motion events, and destroys it
+    /// otherwise.
+    pub fn update_relative_pointer(&mut self) {
+        let wanted = self.windows.iter().flatten().any(|w| w.raw_mouse_motion);
+        match (wanted, self.relative_pointer) {
+            (true, None) => {
+                let manager = self.client.try_get_global::<ZwpRelativePointerManagerV1>();
+                if let (Some(manager), Some(pointer)) = (manager, self.pointer) {
+                    self.relative_pointer =
+                        Some(manager.get_relative_pointer(&mut self.client, pointer));
+                }
+            }
+            (false, Some(relative_pointer)) => {
+                self.client
+                    .call_method(&relative_pointer, ZwpRelativePointerV1Method::Destroy);
+                self.relative_pointer = None;
+            }
+            _ => (),
+        }
+    }
+
+    /// Shows the cursor of the window that has the pointer focus.
+    pub fn update_cursor(&mut self) {
+        self.cursor_animation = None;
+        let Some(window) = self
+            .pointer_focus
+            .and_then(|window_id| self.windows[window_id].as_ref())
+        else {
+            return;
+        };
+
+        let (icon, scale) = (window.cursor_icon, window.buffer_scale);
+        if !window.cursor_visible {
+            if let Some(pointer) = self.pointer {
+                self.client.call_method(
+                    &pointer,
+                    WlPointerMethod::SetCursor(self.pointer_serial, None, 0, 0),
+                );
+            }
+        } else if let Some(cursor) = window.custom_cursor {
+            self.show_cursor_frame(cursor, 0);
+        } else if let Some(device) = self.cursor_shape_device {
+            self.client.call_method(
+                &device,
+                WpCursorShapeDeviceV1Method::SetShape(self.pointer_serial, cursor_shape_of(icon)),
+            );
+        } else if let Some(cursor) = self
+            .load_theme_cursor(icon, scale)
+            .or_else(|| self.load_theme_cursor(CursorIcon::Default, scale))
+        {
+            self.show_cursor_frame(cursor, 0);
+        } else if let (Some(pointer), Some(_)) = (self.pointer, self.cursor_surface) {
+            // There's nothing to replace a custom cursor with, so the cursor gets hidden
+            self.client.call_method(
+                &pointer,
+                WlPointerMethod::SetCursor(self.pointer_serial, None, 0, 0),
+            );
+        }
+    }
+
+    /// Loads the cursor of an icon from the cursor theme, as a custom cursor drawn for the given
+    /// buffer scale. Returns `None` if the theme doesn't have it.
+    pub fn load_theme_cursor(&mut self, icon: CursorIcon, scale: i32) -> Option<CustomCursor> {
+        if let Some(cursor) = self.theme_cursors.get(&(icon, scale)) {
+            return *cursor;
+        }
+
+        let images = [icon.name()]
+            .into_iter()
+            .chain(icon.legacy_names().iter().copied())
+            .find_map(|name| self.cursor_theme.load_cursor(name, scale as u32).ok());
+        let cursor = images.and_then(|images| self.upload_cursor(&images, scale).ok());
+        self.theme_cursors.insert((icon, scale), cursor);
+        cursor
+    }
+
+    /// Draws the frames of a cursor into shm buffers, and registers it as a custom cursor. The
+    /// images are shown at the given buffer scale.
+    pub fn upload_cursor(
+        &mut self,
+        images: &[CursorImage],
+        scale: i32,
+    ) -> io::Result<CustomCursor> {
+        // The compositor rejects buffers whose size isn't a multiple of their scale, so a theme
+        // without a fitting size gets shown unscaled
+        let scale = match images
+            .iter()
+            .all(|image| image.width % scale as u32 == 0 && image.height % scale as u32 == 0)
+        {
+            true => scale,
+            false => 1,
+        };
+
+        let shm = self.shm.get_mut().unwrap();
+        let mut frames = Vec::with_capacity(images.len());
+        for image in images {
+            let buffer = ImageInfo::new(image.width, image.height, shm::Format::Argb8888)
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Cursor is too big"))
+                .and_then(|image_info| shm.allocate(&mut self.client, image_info));
+            let buffer = match buffer {
+                Ok(buffer) => buffer,
+                Err(error) => {
+                    for frame in frames {
+                        let ShmCursorFrame { buffer, .. } = frame;
+                        let _ = shm.free(&mut self.client, buffer);
+                    }
+                    return Err(error);
                 }
+            };
+
+            let data = shm.data_mut(&buffer).unwrap();
+            for (pixel, argb) in data.as_chunks_mut::<4>().0.iter_mut().zip(&image.pixels) {
+                *pixel = argb.to_le_bytes();
+            }
+            frames.push(ShmCursorFrame {
+                buffer,
+                width: image.width as i32,
+                height: image.height as i32,
+                hotspot: (image.hotspot.0 as i32, image.hotspot.1 as i32),
+                delay: image.delay,
+                scale,
+            });
+        }
+
+        let cursor = CustomCursor(self.next_cursor_id);
+        self.next_cursor_id += 1;
+        self.custom_cursors.insert(cursor, frames);
+        Ok(cursor)
+    }
+
+    /// Shows a frame of a custom cursor on the pointer, and schedules the next one if the cursor
+    /// is animated.
+    pub fn show_cursor_frame(&mut self, cursor: CustomCursor, frame: usize) {
+        let (Some(pointer), Some(images)) = (self.pointer, self.custom_cursors.get(&cursor)) else {
+            return;
+        };
+        let image = &images[frame];
+
+        let surface = *self.cursor_surface.get_or_insert_with(|| {
+            let compositor: WlCompositor = self.client.get_global();
+            compositor.create_surface(&mut self.client)
+        });
+        self.client
+            .call_method(&surface, WlSurfaceMethod::SetBufferScale(image.scale));
+        self.client.call_method(
+            &surface,
+            WlSurfaceMethod::Attach(Some(image.buffer.wl_buffer()), 0, 0),
+        );
+        self.client.call_method(
+            &surface,
+            WlSurfaceMethod::DamageBuffer(0, 0, image.width, image.height),
+        );
+        self.client.call_method(&surface, WlSurfaceMethod::Commit);
+        // The hotspot may change between frames, so the cursor is set again every time
+        self.client.call_method(
+            &pointer,
+            // The hotspot is in surface coordinates, which the buffer scale divides
+            WlPointerMethod::SetCursor(
+                self.pointer_serial,
+                Some(surface),
+                image.hotspot.0 / image.scale,
+                image.hotspot.1 / image.scale,
+            ),
+        );
+
+        self.cursor_animation = (images.len() > 1).then(|| CursorAnimation {
+            cursor,
+            frame,
+            // A frame without a delay would make the event loop spin
+            next_frame: Instant::now() + image.delay.max(Duration::from_millis(1)),
+        });
+    }
+
+    /// Shows the next frame of the animated cursor if it's time to.
+    pub fn animate_cursor(&mut self) {
+        let Some(animation) = self.cursor_animation else {
+            return;
+        };
+        if animation.next_frame > Instant::now() {
+            return;
+        }
+
+        let frames = self
+            .custom_cursors
+            .get(&animation.cursor)
+            .map_or(1, Vec::len);
+        self.show_cursor_frame(animation.cursor, (animation.frame + 1) % frames);
+    }
+
+    /// Replaces the pointer constraint of a window with the one its cursor grab asks for. The
+    /// compositor only applies it while the window has the pointer focus.
+    pub fn update_pointer_constraint(&mut self, window_id: WindowId) {
+        let Some(window) = self.windows[window_id].as_mut() else {
+            return;
+        };
+
+        match window.pointer_constraint.take() {
+            Some(PointerConstraint::Locked(locked)) => self
+                .client
+                .call_method(&locked, ZwpLockedPointerV1Method::Destroy),
+            Some(PointerConstraint::Confined(confined)) => self
+                .client
+                .call_method(&confined, ZwpConfinedPointerV1Method::Destroy),
+            None => (),
+        }
+
+        let constraints = self.client.try_get_global::<ZwpPointerConstraintsV1>();
+        let (Some(constraints), Some(pointer)) = (constraints, self.pointer) else {
+            return;
+        };
+        let surface = window.wl_surface;
+        let grab = window.cursor_grab;
+
+        // The compositor copies the region when the constraint is created, so it can go right away
+        let region = match grab {
+            CursorGrab::ConfinedTo(area) => {
+                let compositor: WlCompositor = self.client.get_global();
+                let region = compositor.create_region(&mut self.client);
+                self.client.call_method(
+                    &region,
+                    WlRegionMethod::Add(area.x, area.y, area.width as i32, area.height as i32),
+                );
+                Some(region)
+            }
+            _ => None,
+        };
+
+        let constraint = match grab {
+            CursorGrab::None => None,
+            CursorGrab::Confined | CursorGrab::ConfinedTo(_) => {
+                Some(PointerConstraint::Confined(constraints.confine_pointer(
+                    &mut self.client,
+                    surface,
+                    pointer,
+                    region,
+                    constraint_lifetime::PERSISTENT,
+                )))
+            }
+            CursorGrab::Locked => Some(PointerConstraint::Locked(constraints.lock_pointer(
+                &mut self.client,
+                surface,
+                pointer,
+                None,
+                constraint_lifetime::PERSISTENT,
+            ))),
+        };
+        if let Some(region) = region {
+            self.client.call_method(&region, WlRegionMethod::Destroy);
+        }
+        if let Some(window) = self.windows[window_id].as_mut() {
+            window.pointer_constraint = constraint;
+        }
+    }
+
+    /// Queues an empty `ImePreedit` event for the focused window if it has preedit text.
+    pub fn clear_preedit(&mut self) {
+        if !std::mem::take(&mut self.has_preedit) {
+            return;
+        }
+
+        self.event_queue.push_back(Event {
+            time: Duration::ZERO,
+            window: self
+                .text_input_focus
+                .map_or(WindowHandle::NONE, WindowHandle),
+            kind: EventKind::Keyboard(KeyboardEvent::ImePreedit {
+                text: String::new(),
+                cursor_range: None,
+            }),
+        });
+    }
+
+    /// Waits for the next Lokinit event for at most `timeout`, or indefinitely if `timeout` is `None`.
+    fn wait_event(&mut self, timeout: Option<Duration>) -> Result<Option<Event>, LokinitError> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+
+        loop {
+            if let Some(event) = self.event_queue.pop_front() {
+                return Ok(Some(event));
+            }
+
+            self.dispatch_pending()?;
+            if !self.event_queue.is_empty() {
+                continue;
+            }
+
+            // nothing to report yet, so sleep until the compositor or an event source wakes us up,
+            // or until the cursor's next frame
+            let next_frame = self.cursor_animation.map(|animation| animation.next_frame);
+            let wake_up = match (deadline, next_frame) {
+                (Some(deadline), Some(next_frame)) => Some(deadline.min(next_frame)),
+                (deadline, next_frame) => deadline.or(next_frame),
+            };
+            if !self.event_loop.wait(self.connection_fd()?, wake_up)?
+                && deadline.is_some_and(|deadline| deadline <= Instant::now())
+            {
+                return Ok(None);
             }
-            thread::sleep(Duration::from_millis(5));
         }
     }
 }
 
 impl LokinitBackend for WaylandBackend {
     fn init() -> Self {
-        Self::new().unwrap()
+        Self::new(None).unwrap_or_else(|why| panic!("{why}"))
     }
 
-    fn create_window(&mut self, builder: WindowBuilder) -> Result<WindowHandle, CreateWindowError> {
+    fn create_window(&mut self, builder: WindowBuilder) -> Result<WindowHandle, LokinitError> {
         let compositor: WlCompositor = self.client.get_global();
         let wm_base: XdgWmBase = self.client.get_global();
 
@@ -94,6 +517,15 @@ impl LokinitBackend for WaylandBackend {
             wl_surface,
             xdg_surface,
             xdg_toplevel,
+            ime_allowed: true,
+            ime_cursor_area: None,
+            raw_mouse_motion: false,
+            cursor_grab: CursorGrab::None,
+            cursor_icon: CursorIcon::Default,
+            custom_cursor: None,
+            cursor_visible: true,
+            pointer_constraint: None,
+            buffer_scale: 1,
             buffer: self
                 .shm
                 .get_mut()
@@ -105,9 +537,11 @@ impl LokinitBackend for WaylandBackend {
                         builder.size.height,
                         shm::Format::Argb8888,
                     )
-                    .ok_or_else(|| CreateWindowError("Failed to allocate image info".into()))?,
+                    .ok_or_else(|| {
+                        LokinitError::CreateWindow("Failed to allocate image info".into())
+                    })?,
                 )
-                .map_err(|x| CreateWindowError(x.to_string().into()))?,
+                .map_err(|x| LokinitError::CreateWindow(x.to_string().into()))?,
         };
 
         self.client
@@ -131,8 +565,16 @@ impl LokinitBackend for WaylandBackend {
         Ok(WindowHandle(window_id))
     }
 
-    fn close_window(&mut self, handle: WindowHandle) {
-        let window = self.windows[handle.0].take().unwrap();
+    fn close_window(&mut self, handle: WindowHandle) -> Result<(), LokinitError> {
+        if let Some(Some(window)) = self.windows.get_mut(handle.0) {
+            window.cursor_grab = CursorGrab::None;
+            self.update_pointer_constraint(handle.0);
+        }
+        let window = self
+            .windows
+            .get_mut(handle.0)
+            .and_then(Option::take)
+            .ok_or(LokinitError::InvalidWindow(handle))?;
         self.client
             .call_method(&window.xdg_toplevel, XdgToplevelMethod::Destroy);
         self.client
@@ -141,32 +583,299 @@ impl LokinitBackend for WaylandBackend {
             .call_method(&window.wl_surface, WlSurfaceMethod::Destroy);
         self.client
             .call_method(&window.buffer.wl_buffer(), WlBufferMethod::Destroy);
+        if window.raw_mouse_motion {
+            self.update_relative_pointer();
+        }
+        Ok(())
     }
 
-    fn fetch_monitors(&mut self) -> Vec<Monitor> {
-        todo!("Fetch monitors")
+    fn fetch_monitors(&mut self) -> Result<Vec<Monitor>, LokinitError> {
+        Err(LokinitError::Unsupported("fetching monitors"))
     }
 
-    fn poll_event(&mut self) -> Option<Event> {
-        loop {
-            if let Some(event) = self.event_queue.pop_front() {
-                return Some(event);
+    fn poll_event(&mut self) -> Result<Option<Event>, LokinitError> {
+        self.wait_event(None)
+    }
+
+    fn try_poll_event(&mut self) -> Result<Option<Event>, LokinitError> {
+        self.wait_event(Some(Duration::ZERO))
+    }
+
+    fn poll_event_timeout(&mut self, timeout: Duration) -> Result<Option<Event>, LokinitError> {
+        self.wait_event(Some(timeout))
+    }
+
+    fn dispatch_pending(&mut self) -> Result<(), LokinitError> {
+        while let Some(event) = self.client.next_event(true)? {
+            if let Some(event) = self.handle_event(event)? {
+                self.event_queue.push_back(event);
             }
+        }
 
-            if let Some(event) = self.client.next_event(false) {
-                if let Some(event) = self.handle_event(event) {
-                    return Some(event);
-                }
+        self.event_loop.dispatch(&mut self.event_queue);
+        self.animate_cursor();
+        Ok(())
+    }
+
+    fn next_queued_event(&mut self) -> Option<Event> {
+        self.event_queue.pop_front()
+    }
+
+    fn connection_fd(&self) -> Result<RawFd, LokinitError> {
+        Ok(self.client.socket.as_raw_fd())
+    }
+
+    fn create_proxy(&mut self) -> Result<EventLoopProxy, LokinitError> {
+        Ok(self.event_loop.create_proxy())
+    }
+
+    fn set_timer(&mut self, duration: Duration, repeat: bool) -> Result<TimerId, LokinitError> {
+        Ok(self.event_loop.set_timer(duration, repeat))
+    }
+
+    fn cancel_timer(&mut self, timer: TimerId) -> Result<(), LokinitError> {
+        self.event_loop.cancel_timer(timer);
+        Ok(())
+    }
+
+    fn watch_fd(&mut self, fd: RawFd, interest: Interest) -> Result<(), LokinitError> {
+        self.event_loop.watch_fd(fd, interest);
+        Ok(())
+    }
+
+    fn unwatch_fd(&mut self, fd: RawFd) -> Result<(), LokinitError> {
+        self.event_loop.unwatch_fd(fd);
+        Ok(())
+    }
+
+    fn set_screen_mode(
+        &mut self,
+        _handle: WindowHandle,
+        _screen_mode: ScreenMode,
+    ) -> Result<(), LokinitError> {
+        Err(LokinitError::Unsupported("setting the screen mode"))
+    }
+
+    fn set_ime_cursor_area(
+        &mut self,
+        handle: WindowHandle,
+        area: WindowRect,
+    ) -> Result<(), LokinitError> {
+        let window = self
+            .windows
+            .get_mut(handle.0)
+            .and_then(Option::as_mut)
+            .ok_or(LokinitError::InvalidWindow(handle))?;
+        window.ime_cursor_area = Some(area);
+
+        if let Some(text_input) = self.text_input {
+            if window.ime_allowed && self.text_input_focus == Some(handle.0) {
+                self.client.call_method(&text_input, cursor_rectangle(area));
+                self.client
+                    .call_method(&text_input, ZwpTextInputV3Method::Commit);
             }
         }
+        Ok(())
     }
-    fn set_screen_mode(&mut self, _handle: WindowHandle, _screen_mode: ScreenMode) {
-        todo!("Set screen mode")
+
+    fn set_ime_allowed(&mut self, handle: WindowHandle, allowed: bool) -> Result<(), LokinitError> {
+        let window = self
+            .windows
+            .get_mut(handle.0)
+            .and_then(Option::as_mut)
+            .ok_or(LokinitError::InvalidWindow(handle))?;
+        if window.ime_allowed == allowed {
+            return Ok(());
+        }
+        window.ime_allowed = allowed;
+
+        if self.text_input_focus == Some(handle.0) {
+            self.update_text_input();
+        }
+        Ok(())
+    }
+
+    fn set_raw_mouse_motion(
+        &mut self,
+        handle: WindowHandle,
+        enabled: bool,
+    ) -> Result<(), LokinitError> {
+        if enabled
+            && self
+                .client
+                .try_get_global::<ZwpRelativePointerManagerV1>()
+                .is_none()
+        {
+            return Err(LokinitError::Unsupported("raw mouse motion"));
+        }
+        let window = self
+            .windows
+            .get_mut(handle.0)
+            .and_then(Option::as_mut)
+            .ok_or(LokinitError::InvalidWindow(handle))?;
+        window.raw_mouse_motion = enabled;
+
+        self.update_relative_pointer();
+        Ok(())
+    }
+
+    fn set_cursor_grab(
+        &mut self,
+        handle: WindowHandle,
+        grab: CursorGrab,
+    ) -> Result<(), LokinitError> {
+        if grab != CursorGrab::None
+            && self
+                .client
+                .try_get_global::<ZwpPointerConstraintsV1>()
+                .is_none()
+        {
+            return Err(LokinitError::Unsupported("cursor grabs"));
+        }
+        let window = self
+            .windows
+            .get_mut(handle.0)
+            .and_then(Option::as_mut)
+            .ok_or(LokinitError::InvalidWindow(handle))?;
+        if window.cursor_grab == grab {
+            return Ok(());
+        }
+        window.cursor_grab = grab;
+
+        self.update_pointer_constraint(handle.0);
+        Ok(())
+    }
+
+    fn set_cursor_icon(
+        &mut self,
+        handle: WindowHandle,
+        icon: CursorIcon,
+    ) -> Result<(), LokinitError> {
+        let window = self
+            .windows
+            .get_mut(handle.0)
+            .and_then(Option::as_mut)
+            .ok_or(LokinitError::InvalidWindow(handle))?;
+        window.cursor_icon = icon;
+        window.custom_cursor = None;
+
+        if self.pointer_focus == Some(handle.0) {
+            self.update_cursor();
+        }
+        Ok(())
+    }
+
+    fn set_cursor_visible(
+        &mut self,
+        handle: WindowHandle,
+        visible: bool,
+    ) -> Result<(), LokinitError> {
+        let window = self
+            .windows
+            .get_mut(handle.0)
+            .and_then(Option::as_mut)
+            .ok_or(LokinitError::InvalidWindow(handle))?;
+        window.cursor_visible = visible;
+
+        if self.pointer_focus == Some(handle.0) {
+            self.update_cursor();
+        }
+        Ok(())
+    }
+
+    fn set_cursor_position(
+        &mut self,
+        handle: WindowHandle,
+        x: f64,
+        y: f64,
+    ) -> Result<(), LokinitError> {
+        let window = self
+            .windows
+            .get(handle.0)
+            .and_then(Option::as_ref)
+            .ok_or(LokinitError::InvalidWindow(handle))?;
+        // Only a locked pointer can be given a position, which it gets once unlocked
+        let Some(PointerConstraint::Locked(locked)) = &window.pointer_constraint else {
+            return Err(LokinitError::Unsupported(
+                "moving the cursor without locking it",
+            ));
+        };
+
+        self.client.call_method(
+            locked,
+            ZwpLockedPointerV1Method::SetCursorPositionHint(Fixed::from_f64(x), Fixed::from_f64(y)),
+        );
+        // The hint is double-buffered state of the window's surface
+        self.client
+            .call_method(&window.wl_surface, WlSurfaceMethod::Commit);
+        Ok(())
+    }
+
+    fn create_cursor(&mut self, frames: &[CursorFrame]) -> Result<CustomCursor, LokinitError> {
+        let images: Vec<CursorImage> = frames
+            .iter()
+            .map(|frame| CursorImage {
+                width: frame.width(),
+                height: frame.height(),
+                hotspot: frame.hotspot(),
+                delay: frame.delay(),
+                pixels: frame.argb_pixels().collect(),
+            })
+            .collect();
+        Ok(self.upload_cursor(&images, 1)?)
+    }
+
+    fn destroy_cursor(&mut self, cursor: CustomCursor) -> Result<(), LokinitError> {
+        let images = self
+            .custom_cursors
+            .remove(&cursor)
+            .ok_or(LokinitError::InvalidCursor(cursor))?;
+
+        let mut shown = false;
+        for (window_id, window) in self.windows.iter_mut().enumerate() {
+            let Some(window) = window.as_mut().filter(|w| w.custom_cursor == Some(cursor)) else {
+                continue;
+            };
+            window.custom_cursor = None;
+            window.cursor_icon = CursorIcon::Default;
+            shown |= self.pointer_focus == Some(window_id);
+        }
+        if shown {
+            self.update_cursor();
+        }
+
+        let shm = self.shm.get_mut().unwrap();
+        for image in images {
+            shm.free(&mut self.client, image.buffer)
+                .map_err(|(error, _)| error)?;
+        }
+        Ok(())
+    }
+
+    fn set_custom_cursor(
+        &mut self,
+        handle: WindowHandle,
+        cursor: CustomCursor,
+    ) -> Result<(), LokinitError> {
+        if !self.custom_cursors.contains_key(&cursor) {
+            return Err(LokinitError::InvalidCursor(cursor));
+        }
+        let window = self
+            .windows
+            .get_mut(handle.0)
+            .and_then(Option::as_mut)
+            .ok_or(LokinitError::InvalidWindow(handle))?;
+        window.custom_cursor = Some(cursor);
+
+        if self.pointer_focus == Some(handle.0) {
+            self.update_cursor();
+        }
+        Ok(())
     }
 
     #[cfg(feature = "opengl")]
     fn load_opengl_func(&mut self, _proc_name: *const std::ffi::c_char) -> *mut std::ffi::c_void {
-        todo!()
+        std::ptr::null_mut()
     }
 
     #[cfg(feature = "opengl")]
@@ -174,23 +883,31 @@ impl LokinitBackend for WaylandBackend {
         &mut self,
         _window: WindowHandle,
         _cfg: crate::prelude::OpenGlConfig,
-    ) -> super::WindowSurface {
-        todo!()
+    ) -> Result<super::WindowSurface, LokinitError> {
+        Err(LokinitError::Unsupported("OpenGL"))
     }
 
     #[cfg(feature = "opengl")]
-    fn make_surface_active(&self, window: WindowHandle, surface: crate::native::WindowSurface) {
-        todo!()
+    fn make_surface_active(
+        &self,
+        window: WindowHandle,
+        surface: crate::native::WindowSurface,
+    ) -> Result<(), LokinitError> {
+        Err(LokinitError::Unsupported("OpenGL"))
     }
 
     #[cfg(feature = "opengl")]
-    fn flush_surface(&self, window: WindowHandle, surface: super::WindowSurface) {
-        todo!()
+    fn flush_surface(
+        &self,
+        window: WindowHandle,
+        surface: super::WindowSurface,
+    ) -> Result<(), LokinitError> {
+        Err(LokinitError::Unsupported("OpenGL"))
     }
 
     #[cfg(feature = "opengl")]
-    fn update_surface(&self, surface: crate::native::WindowSurface) {
-        todo!()
+    fn update_surface(&self, surface: crate::native::WindowSurface) -> Result<(), LokinitError> {
+        Err(LokinitError::Unsupported("OpenGL"))
     }
 }
 
@@ -198,7 +915,92 @@ pub struct WaylandWindow {
     pub wl_surface: WlSurface,
     pub xdg_surface: XdgSurface,
     pub xdg_toplevel: XdgToplevel,
+    pub ime_allowed: bool,
+    pub ime_cursor_area: Option<WindowRect>,
+    pub raw_mouse_motion: bool,
+    pub cursor_grab: CursorGrab,
+    pub cursor_icon: CursorIcon,
+    /// The cursor shown instead of `cursor_icon`, if any.
+    pub custom_cursor: Option<CustomCursor>,
+    pub cursor_visible: bool,
+    /// The constraint that applies `cursor_grab`, if the seat has a pointer.
+    pub pointer_constraint: Option<PointerConstraint>,
+    /// The buffer scale the compositor prefers for the window, which theme cursors are drawn
+    /// for while the pointer is over it.
+    pub buffer_scale: i32,
+    pub buffer: Buffer,
+}
+
+/// A frame of a custom cursor, drawn in a shm buffer.
+pub struct ShmCursorFrame {
     pub buffer: Buffer,
+    pub width: i32,
+    pub height: i32,
+    pub hotspot: (i32, i32),
+    pub delay: Duration,
+    /// The buffer scale of the cursor surface while this frame is shown.
+    pub scale: i32,
+}
+
+/// The frame of an animated cursor that is being shown, and when to show the next one.
+#[derive(Clone, Copy)]
+pub struct CursorAnimation {
+    pub cursor: CustomCursor,
+    pub frame: usize,
+    pub next_frame: Instant,
+}
+
+pub enum PointerConstraint {
+    Locked(ZwpLockedPointerV1),
+    Confined(ZwpConfinedPointerV1),
+}
+
+/// Scrolling events, which are grouped into frames.
+#[derive(Default)]
+pub struct ScrollFrame {
+    /// How far each axis scrolled, in pixels.
+    pub pixels: (f64, f64),
+    /// How far each axis scrolled, in 120ths of a wheel step.
+    pub value120: (i32, i32),
+    /// What scrolled, as a `wl_pointer.axis_source` value.
+    pub source: Option<u32>,
+    /// Whether fingers left the device.
+    pub stopped: bool,
+    pub inverted: bool,
+}
+
+fn cursor_shape_of(icon: CursorIcon) -> u32 {
+    match icon {
+        CursorIcon::Default => shape::DEFAULT,
+        CursorIcon::Pointer => shape::POINTER,
+        CursorIcon::Text => shape::TEXT,
+        CursorIcon::Crosshair => shape::CROSSHAIR,
+        CursorIcon::Help => shape::HELP,
+        CursorIcon::Wait => shape::WAIT,
+        CursorIcon::Progress => shape::PROGRESS,
+        CursorIcon::NotAllowed => shape::NOT_ALLOWED,
+        CursorIcon::Move => shape::MOVE,
+        CursorIcon::Grab => shape::GRAB,
+        CursorIcon::Grabbing => shape::GRABBING,
+        CursorIcon::NResize => shape::N_RESIZE,
+        CursorIcon::EResize => shape::E_RESIZE,
+        CursorIcon::SResize => shape::S_RESIZE,
+        CursorIcon::WResize => shape::W_RESIZE,
+        CursorIcon::NeResize => shape::NE_RESIZE,
+        CursorIcon::NwResize => shape::NW_RESIZE,
+        CursorIcon::SeResize => shape::SE_RESIZE,
+        CursorIcon::SwResize => shape::SW_RESIZE,
+        CursorIcon::EwResize => shape::EW_RESIZE,
+        CursorIcon::NsResize => shape::NS_RESIZE,
+        CursorIcon::NeswResize => shape::NESW_RESIZE,
+        CursorIcon::NwseResize => shape::NWSE_RESIZE,
+        CursorIcon::ColResize => shape::COL_RESIZE,
+        CursorIcon::RowResize => shape::ROW_RESIZE,
+    }
+}
+
+fn cursor_rectangle(area: WindowRect) -> ZwpTextInputV3Method {
+    ZwpTextInputV3Method::SetCursorRectangle(area.x, area.y, area.width as _, area.height as _)
 }
 
 type WindowSurface = ();
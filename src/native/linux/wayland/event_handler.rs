@@ -1,7 +1,11 @@
 use {
-    super::WaylandBackend,
+    super::{super::modifiers_from_mask, ScrollFrame, WaylandBackend},
     crate::{
-        event::{Event, EventKind},
+        error::LokinitError,
+        event::{
+            Event, EventKind, KeyboardEvent, MouseButton, MouseEvent, ScrollDelta, ScrollEvent,
+            TouchPhase,
+        },
         prelude::WindowHandle,
     },
     loki_linux::wayland::{events::*, interfaces::all::*, methods::*},
@@ -9,80 +13,424 @@ use {
 };
 
 impl WaylandBackend {
-    pub fn handle_event(&mut self, event: WaylandEvent) -> Option<Event> {
-        match event {
-            WaylandEvent::WlDisplayEvent(display_event) => match display_event {
-                WlDisplayEvent::Error(_, obj, error_code, error) => {
-                    let interface = self
-                        .client
-                        .object_interface(obj)
-                        .expect("Wayland compositor sent an invalid object ID");
-                    panic!(
-                        "\
-                        Lokinit: Critical wayland error!\n\
-                        Object: #{obj} (interface: `{interface}`)\n\
-                        Wayland Error Code: {error_code}\n\
-                        Wayland Error Message: `{error}`\
-                        ",
-                    )
-                }
-                _ => None,
-            },
-
-            WaylandEvent::WlRegistryEvent(registry_event) => match registry_event {
-                WlRegistryEvent::Global(_, name, interface, _version) => {
-                    println!("Trying to register object {}", interface.as_str());
-                    match interface.as_str() {
-                        "wl_compositor" => {
-                            self.client.bind_global::<WlCompositor>(name);
+    /// Handles an event sent by the compositor, turning it into a Lokinit event if it has one.
+    pub fn handle_event(&mut self, event: WaylandEvent) -> Result<Option<Event>, LokinitError> {
+        let event =
+            match event {
+                WaylandEvent::WlDisplayEvent(display_event) => match display_event {
+                    WlDisplayEvent::Error(_, obj, error_code, error) => {
+                        let interface = match self.client.object_interface(obj) {
+                            Some(interface) => format!("`{interface}`"),
+                            None => "unknown".to_owned(),
+                        };
+                        return Err(LokinitError::Protocol(
+                            format!(
+                                "\
+                            Object: #{obj} (interface: {interface}), \
+                            Wayland Error Code: {error_code}, \
+                            Wayland Error Message: `{error}`\
+                            ",
+                            )
+                            .into(),
+                        ));
+                    }
+                    _ => None,
+                },
+
+                WaylandEvent::WlRegistryEvent(registry_event) => match registry_event {
+                    WlRegistryEvent::Global(_, name, interface, _version) => {
+                        println!("Trying to register object {}", interface.as_str());
+                        match interface.as_str() {
+                            "wl_compositor" => {
+                                self.client.bind_global::<WlCompositor>(name);
+                            }
+                            "xdg_wm_base" => {
+                                self.client.bind_global::<XdgWmBase>(name);
+                            }
+                            "wl_shm" => {
+                                self.client.bind_global::<WlShm>(name);
+                            }
+                            "wl_seat" => {
+                                self.client.bind_global::<WlSeat>(name);
+                            }
+                            "zwp_text_input_manager_v3" => {
+                                self.client.bind_global::<ZwpTextInputManagerV3>(name);
+                            }
+                            "zwp_relative_pointer_manager_v1" => {
+                                self.client.bind_global::<ZwpRelativePointerManagerV1>(name);
+                            }
+                            "zwp_pointer_constraints_v1" => {
+                                self.client.bind_global::<ZwpPointerConstraintsV1>(name);
+                            }
+                            "wp_cursor_shape_manager_v1" => {
+                                self.client.bind_global::<WpCursorShapeManagerV1>(name);
+                            }
+                            _ => {}
                         }
-                        "xdg_wm_base" => {
-                            self.client.bind_global::<XdgWmBase>(name);
+                        None
+                    }
+                    _ => None,
+                },
+
+                WaylandEvent::WlSeatEvent(WlSeatEvent::Capabilities(seat, capabilities)) => {
+                    const POINTER: u32 = 1;
+                    const KEYBOARD: u32 = 2;
+
+                    if capabilities & POINTER != 0 && self.pointer.is_none() {
+                        let pointer = seat.get_pointer(&mut self.client);
+                        self.pointer = Some(pointer);
+                        let cursor_shape_manager =
+                            self.client.try_get_global::<WpCursorShapeManagerV1>();
+                        if let Some(manager) = cursor_shape_manager {
+                            self.cursor_shape_device =
+                                Some(manager.get_pointer(&mut self.client, pointer));
                         }
-                        "wl_shm" => {
-                            self.client.bind_global::<WlShm>(name);
+                        self.update_relative_pointer();
+                        for window_id in 0..self.windows.len() {
+                            self.update_pointer_constraint(window_id);
                         }
-                        _ => {}
+                    }
+                    if capabilities & KEYBOARD != 0 && self.keyboard.is_none() {
+                        self.keyboard = Some(seat.get_keyboard(&mut self.client));
                     }
                     None
                 }
-                _ => None,
-            },
-
-            WaylandEvent::XdgWmBaseEvent(XdgWmBaseEvent::Ping(xdg_wm_base, serial)) => {
-                self.client
-                    .call_method(&xdg_wm_base, XdgWmBaseMethod::Pong(serial));
-                None
-            }
-
-            WaylandEvent::XdgSurfaceEvent(XdgSurfaceEvent::Configure(xdg_surface, serial)) => {
-                let window = self.windows[*self.object_to_window_map.get(&xdg_surface.id).unwrap()]
-                    .as_ref()
-                    .unwrap();
-                self.client.call_method(
-                    &window.wl_surface,
-                    WlSurfaceMethod::Attach(Some(window.buffer.wl_buffer()), 0, 0),
-                );
-                self.client
-                    .call_method(&xdg_surface, XdgSurfaceMethod::AckConfigure(serial));
-                self.client
-                    .call_method(&window.wl_surface, WlSurfaceMethod::Commit);
-                None
-            }
-
-            WaylandEvent::XdgToplevelEvent(toplevel_event) => match toplevel_event {
-                XdgToplevelEvent::ConfigureBounds(xdg_toplevel, width, height) => {
-                    let window_id = *self.object_to_window_map.get(&xdg_toplevel.id).unwrap();
+
+                WaylandEvent::WlPointerEvent(pointer_event) => match pointer_event {
+                    WlPointerEvent::Enter(_, serial, surface, x, y) => {
+                        let Some(window) = self.object_to_window_map.get(&surface.id).copied()
+                        else {
+                            return Ok(None);
+                        };
+                        let (x, y) = (x.to_f64(), y.to_f64());
+                        self.pointer_focus = Some(window);
+                        self.pointer_position = (x, y);
+                        self.pointer_serial = serial;
+                        self.update_cursor();
+
+                        Some(Event {
+                            time: Duration::ZERO,
+                            window: WindowHandle(window),
+                            kind: EventKind::Mouse(MouseEvent::CursorIn(x, y)),
+                        })
+                    }
+                    WlPointerEvent::Leave(_, _, _) => {
+                        let Some(window) = self.pointer_focus.take() else {
+                            return Ok(None);
+                        };
+                        let (x, y) = self.pointer_position;
+                        self.scrolling = false;
+                        self.cursor_animation = None;
+
+                        Some(Event {
+                            time: Duration::ZERO,
+                            window: WindowHandle(window),
+                            kind: EventKind::Mouse(MouseEvent::CursorOut(x, y)),
+                        })
+                    }
+                    WlPointerEvent::Motion(_, time, x, y) => {
+                        let Some(window) = self.pointer_focus else {
+                            return Ok(None);
+                        };
+                        let (x, y) = (x.to_f64(), y.to_f64());
+                        self.pointer_position = (x, y);
+
+                        Some(Event {
+                            time: Duration::from_millis(time.into()),
+                            window: WindowHandle(window),
+                            kind: EventKind::Mouse(MouseEvent::CursorMove(x, y)),
+                        })
+                    }
+                    WlPointerEvent::Button(_, _, time, button, state) => {
+                        // Linux input event codes, from linux/input-event-codes.h
+                        const BTN_LEFT: u32 = 0x110;
+                        const BTN_RIGHT: u32 = 0x111;
+                        const BTN_MIDDLE: u32 = 0x112;
+                        const BTN_SIDE: u32 = 0x113;
+                        const BTN_EXTRA: u32 = 0x114;
+                        const PRESSED: u32 = 1;
+
+                        let Some(window) = self.pointer_focus else {
+                            return Ok(None);
+                        };
+                        let button = match button {
+                            BTN_LEFT => MouseButton::Left,
+                            BTN_RIGHT => MouseButton::Right,
+                            BTN_MIDDLE => MouseButton::Middle,
+                            BTN_SIDE => MouseButton::Back,
+                            BTN_EXTRA => MouseButton::Forward,
+                            other => MouseButton::Other(other as u16),
+                        };
+                        let (x, y) = self.pointer_position;
+                        let mouse_event = match state {
+                            PRESSED => MouseEvent::ButtonPress(button, x, y, self.modifiers),
+                            _ => MouseEvent::ButtonRelease(button, x, y, self.modifiers),
+                        };
+
+                        Some(Event {
+                            time: Duration::from_millis(time.into()),
+                            window: WindowHandle(window),
+                            kind: EventKind::Mouse(mouse_event),
+                        })
+                    }
+                    WlPointerEvent::Axis(_, _, axis, value) => {
+                        match axis {
+                            0 => self.scroll_frame.pixels.1 += value.to_f64(),
+                            _ => self.scroll_frame.pixels.0 += value.to_f64(),
+                        }
+                        None
+                    }
+                    WlPointerEvent::AxisValue120(_, axis, value120) => {
+                        match axis {
+                            0 => self.scroll_frame.value120.1 += value120,
+                            _ => self.scroll_frame.value120.0 += value120,
+                        }
+                        None
+                    }
+                    WlPointerEvent::AxisSource(_, source) => {
+                        self.scroll_frame.source = Some(source);
+                        None
+                    }
+                    WlPointerEvent::AxisStop(_, _, _) => {
+                        self.scroll_frame.stopped = true;
+                        None
+                    }
+                    WlPointerEvent::AxisRelativeDirection(_, _, direction) => {
+                        self.scroll_frame.inverted |= direction == 1;
+                        None
+                    }
+                    WlPointerEvent::Frame(_) => {
+                        let frame = std::mem::take(&mut self.scroll_frame);
+                        self.queue_scroll(frame);
+                        None
+                    }
+                    _ => None,
+                },
+
+                WaylandEvent::ZwpRelativePointerV1Event(
+                    ZwpRelativePointerV1Event::RelativeMotion(_, utime_hi, utime_lo, _, _, dx, dy),
+                ) => {
+                    let Some(window) = self.pointer_focus.filter(|window_id| {
+                        self.windows[*window_id]
+                            .as_ref()
+                            .is_some_and(|w| w.raw_mouse_motion)
+                    }) else {
+                        return Ok(None);
+                    };
+
+                    let utime = u64::from(utime_hi) << 32 | u64::from(utime_lo);
                     Some(Event {
-                        time: Duration::ZERO,
-                        window: WindowHandle(window_id),
-                        kind: EventKind::Resized(width as _, height as _),
+                        time: Duration::from_micros(utime),
+                        window: WindowHandle(window),
+                        kind: EventKind::RawMouseMotion {
+                            dx: dx.to_f64(),
+                            dy: dy.to_f64(),
+                        },
                     })
                 }
+
+                WaylandEvent::WlKeyboardEvent(keyboard_event) => match keyboard_event {
+                    WlKeyboardEvent::Enter(_, _, surface) => {
+                        self.keyboard_focus = self.object_to_window_map.get(&surface.id).copied();
+                        self.keyboard_focus.map(|window| Event {
+                            time: Duration::ZERO,
+                            window: WindowHandle(window),
+                            kind: EventKind::FocusIn,
+                        })
+                    }
+                    WlKeyboardEvent::Leave(_, _, _) => {
+                        self.keyboard_focus.take().map(|window| Event {
+                            time: Duration::ZERO,
+                            window: WindowHandle(window),
+                            kind: EventKind::FocusOut,
+                        })
+                    }
+                    WlKeyboardEvent::Modifiers(_, _, depressed, latched, locked, _) => {
+                        let modifiers = modifiers_from_mask(depressed | latched | locked);
+                        if modifiers == self.modifiers {
+                            return Ok(None);
+                        }
+
+                        self.modifiers = modifiers;
+                        Some(Event {
+                            time: Duration::ZERO,
+                            window: self.keyboard_focus.map_or(WindowHandle::NONE, WindowHandle),
+                            kind: EventKind::ModifiersChanged(modifiers),
+                        })
+                    }
+                    // Key events need the keymap, which can't be received yet
+                    _ => None,
+                },
+
+                WaylandEvent::ZwpTextInputV3Event(text_input_event) => match text_input_event {
+                    ZwpTextInputV3Event::Enter(_, surface) => {
+                        self.text_input_focus = self.object_to_window_map.get(&surface.id).copied();
+                        self.update_text_input();
+                        None
+                    }
+                    ZwpTextInputV3Event::Leave(_, _) => {
+                        self.clear_preedit();
+                        self.text_input_focus = None;
+                        self.pending_preedit = None;
+                        self.pending_commit = None;
+                        None
+                    }
+                    ZwpTextInputV3Event::PreeditString(_, text, cursor_begin, cursor_end) => {
+                        let cursor_range = (cursor_begin >= 0 && cursor_end >= 0)
+                            .then_some((cursor_begin as usize, cursor_end as usize));
+                        self.pending_preedit = Some((text, cursor_range));
+                        None
+                    }
+                    ZwpTextInputV3Event::CommitString(_, text) => {
+                        self.pending_commit = Some(text);
+                        None
+                    }
+                    ZwpTextInputV3Event::Done(_, _) => {
+                        let window = self
+                            .text_input_focus
+                            .map_or(WindowHandle::NONE, WindowHandle);
+                        let preedit = self.pending_preedit.take();
+                        let commit = self.pending_commit.take().filter(|text| !text.is_empty());
+
+                        // The old preedit text goes away before the commit text is inserted
+                        let has_new_preedit =
+                            preedit.as_ref().is_some_and(|(text, _)| !text.is_empty());
+                        if commit.is_some() || !has_new_preedit {
+                            self.clear_preedit();
+                        }
+                        if let Some(text) = commit {
+                            self.event_queue.push_back(Event {
+                                time: Duration::ZERO,
+                                window,
+                                kind: EventKind::Keyboard(KeyboardEvent::ImeCommit(text)),
+                            });
+                        }
+                        if let Some((text, cursor_range)) = preedit.filter(|_| has_new_preedit) {
+                            self.has_preedit = true;
+                            self.event_queue.push_back(Event {
+                                time: Duration::ZERO,
+                                window,
+                                kind: EventKind::Keyboard(KeyboardEvent::ImePreedit {
+                                    text,
+                                    cursor_range,
+                                }),
+                            });
+                        }
+                        None
+                    }
+                    // The surrounding text is never sent, so there's nothing to delete from
+                    ZwpTextInputV3Event::DeleteSurroundingText(..) => None,
+                },
+
+                WaylandEvent::WlSurfaceEvent(surface_event) => match surface_event {
+                    WlSurfaceEvent::PreferredBufferScale(surface, factor) => {
+                        let Some(&window_id) = self.object_to_window_map.get(&surface.id) else {
+                            return Ok(None);
+                        };
+                        if let Some(window) = self.windows[window_id].as_mut() {
+                            window.buffer_scale = factor.max(1);
+                        }
+                        if self.pointer_focus == Some(window_id) {
+                            self.update_cursor();
+                        }
+                        None
+                    }
+                    // The preferred scale already accounts for the outputs the surface is on
+                    WlSurfaceEvent::Enter(..)
+                    | WlSurfaceEvent::Leave(..)
+                    | WlSurfaceEvent::PreferredBufferTransform(..) => None,
+                },
+
+                WaylandEvent::XdgWmBaseEvent(XdgWmBaseEvent::Ping(xdg_wm_base, serial)) => {
+                    self.client
+                        .call_method(&xdg_wm_base, XdgWmBaseMethod::Pong(serial));
+                    None
+                }
+
+                WaylandEvent::XdgSurfaceEvent(XdgSurfaceEvent::Configure(xdg_surface, serial)) => {
+                    // The window may have been closed in the meantime
+                    let Some(window) = self
+                        .object_to_window_map
+                        .get(&xdg_surface.id)
+                        .and_then(|window_id| self.windows[*window_id].as_ref())
+                    else {
+                        return Ok(None);
+                    };
+                    self.client.call_method(
+                        &window.wl_surface,
+                        WlSurfaceMethod::Attach(Some(window.buffer.wl_buffer()), 0, 0),
+                    );
+                    self.client
+                        .call_method(&xdg_surface, XdgSurfaceMethod::AckConfigure(serial));
+                    self.client
+                        .call_method(&window.wl_surface, WlSurfaceMethod::Commit);
+                    None
+                }
+
+                WaylandEvent::XdgToplevelEvent(toplevel_event) => match toplevel_event {
+                    XdgToplevelEvent::ConfigureBounds(xdg_toplevel, width, height) => {
+                        let Some(&window_id) = self.object_to_window_map.get(&xdg_toplevel.id)
+                        else {
+                            return Ok(None);
+                        };
+                        Some(Event {
+                            time: Duration::ZERO,
+                            window: WindowHandle(window_id),
+                            kind: EventKind::Resized(width as _, height as _),
+                        })
+                    }
+                    _ => None,
+                },
+
                 _ => None,
-            },
+            };
+
+        Ok(event)
+    }
 
-            _ => None,
+    /// Queues the scroll events of a frame of pointer events.
+    fn queue_scroll(&mut self, frame: ScrollFrame) {
+        const FINGER: u32 = 1;
+        const CONTINUOUS: u32 = 2;
+
+        let window = self.pointer_focus.map_or(WindowHandle::NONE, WindowHandle);
+        let mut queue_scroll = |delta, phase| {
+            self.event_queue.push_back(Event {
+                time: Duration::ZERO,
+                window,
+                kind: EventKind::Mouse(MouseEvent::Scroll(ScrollEvent {
+                    delta,
+                    phase,
+                    inverted: frame.inverted,
+                })),
+            });
+        };
+
+        // Wayland scrolls down and right with positive values
+        let (value120_x, value120_y) = frame.value120;
+        let (pixels_x, pixels_y) = frame.pixels;
+        let delta = if frame.value120 != (0, 0) {
+            Some(ScrollDelta::Lines(
+                f64::from(value120_x) / 120.0,
+                f64::from(-value120_y) / 120.0,
+            ))
+        } else if frame.pixels != (0.0, 0.0) {
+            Some(ScrollDelta::Pixels(pixels_x, -pixels_y))
+        } else {
+            None
+        };
+
+        let continuous = matches!(frame.source, Some(FINGER | CONTINUOUS));
+        if let Some(delta) = delta {
+            let phase = match (continuous, self.scrolling) {
+                (true, false) => TouchPhase::Started,
+                _ => TouchPhase::Moved,
+            };
+            self.scrolling |= continuous;
+            queue_scroll(delta, phase);
+        }
+        if frame.stopped && std::mem::take(&mut self.scrolling) {
+            queue_scroll(ScrollDelta::Pixels(0.0, 0.0), TouchPhase::Ended);
         }
     }
 }
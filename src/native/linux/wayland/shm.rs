@@ -209,6 +209,7 @@ pub struct Buffer {
     wl_buffer: WlBuffer,
     pool_id: u16,
     ptr: usize,
+    len: usize,
 }
 impl Buffer {
     pub fn wl_buffer(&self) -> WlBuffer {
@@ -313,6 +314,15 @@ impl ShmAllocatorAllocator {
         v
     }
 
+    /// The memory of a buffer, to draw into. Returns `None` if the buffer comes from another
+    /// allocator.
+    pub fn data_mut(&mut self, buffer: &Buffer) -> Option<&mut [u8]> {
+        self.allocators
+            .get_mut(buffer.pool_id as usize)
+            .and_then(|x| x.as_mut())
+            .map(|x| x.data_mut(buffer))
+    }
+
     pub fn free(
         &mut self,
         client: &mut WaylandClient,
@@ -424,9 +434,14 @@ impl ShmAllocator {
             wl_buffer,
             ptr,
             pool_id: self.pool_id,
+            len,
         })
     }
 
+    pub fn data_mut(&mut self, buffer: &Buffer) -> &mut [u8] {
+        &mut self.memmap[buffer.ptr..buffer.ptr + buffer.len]
+    }
+
     pub fn free(
         &mut self,
         client: &mut WaylandClient,
@@ -436,6 +451,7 @@ impl ShmAllocator {
             wl_buffer,
             ptr,
             pool_id,
+            ..
         } = buffer;
 
         if pool_id != self.pool_id {
@@ -2,34 +2,63 @@
 
 use std::borrow::Cow;
 use std::collections::{HashMap, VecDeque};
-use std::ffi::{c_int, c_long, c_void, CString};
+use std::ffi::{c_char, c_int, c_long, c_uint, c_void, CStr, CString};
+use std::fmt;
+use std::io;
+use std::os::fd::RawFd;
 use std::ptr::{null, null_mut, NonNull};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use crate::event::{Event, EventKind, KeyboardEvent, MouseButton, MouseEvent};
-use crate::keycode::KeyCode;
-use crate::lok::{CreateWindowError, LokinitBackend};
+use crate::cursor::{CursorFrame, CursorIcon, CustomCursor};
+use crate::error::LokinitError;
+use crate::event::{
+    Event, EventKind, KeyboardEvent, Modifiers, MouseButton, MouseEvent, ScrollEvent,
+};
+use crate::event_loop::{EventLoopProxy, Interest, TimerId};
+use crate::keycode::{KeyCode, ScanCode};
+use crate::lok::LokinitBackend;
 use crate::prelude::{WindowBuilder, WindowHandle, WindowPos, WindowSize};
-use crate::window::ScreenMode;
+use crate::window::{CursorGrab, ScreenMode, WindowRect};
 
 use loki_linux::glx::LibGlx;
 use loki_linux::locale::{setlocale, LC_CTYPE};
+use loki_linux::x11::xcursor::{LibXcursor, XcursorImage};
+use loki_linux::x11::xinput2::{xi, XIDeviceEvent, XIRawEvent};
 use loki_linux::x11::{
-    et, xclass, xcw, xevent_mask, xim, xn, Atom, LibX11, Status, XClientMessageData,
-    XClientMessageEvent, XDisplay, XErrorEvent, XEvent, XKeyEvent, XPoint, XSetWindowAttributes,
-    XWindow, XID, X_BUFFER_OVERFLOW, _XIC, _XIM,
+    cursor_font, et, grab_mode, grab_status, xclass, xcw, xevent_mask, xim, xim_caret, xn, Atom,
+    Cursor, LibX11, Status, XClientMessageData, XClientMessageEvent, XColor, XDisplay, XErrorEvent,
+    XEvent, XICCallback, XIMPreeditCaretCallbackStruct, XIMPreeditDrawCallbackStruct, XIMText,
+    XKeyEvent, XPoint, XPointer, XSetWindowAttributes, XWindow, _XIC, _XIM, CURRENT_TIME, XIC, XID,
+    X_BUFFER_OVERFLOW,
 };
 use loki_linux::LoadingError;
 
+use super::event_loop::EventLoop;
+use super::modifiers_from_mask;
 use super::OpenGlConfig;
 
 mod keysym;
+mod xinput2;
+
+use xinput2::XInput2;
 
 #[derive(Clone, Debug)]
 pub enum X11NativeCoreError {
     LibLoading(LoadingError),
     CannotOpenDisplay,
     CannotOpenInputMethod,
+    CannotCreateEventLoop,
+}
+
+impl fmt::Display for X11NativeCoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LibLoading(error) => write!(f, "Failed to load Xlib: {error}"),
+            Self::CannotOpenDisplay => write!(f, "Cannot open the X display"),
+            Self::CannotOpenInputMethod => write!(f, "Cannot open the X input method"),
+            Self::CannotCreateEventLoop => write!(f, "Cannot create the event loop"),
+        }
+    }
 }
 
 impl From<LoadingError> for X11NativeCoreError {
@@ -56,6 +85,28 @@ pub struct X11NativeWindow {
     size: WindowSize,
     wm_delete_message: u64,
     xic: NonNull<_XIC>,
+    /// The text being composed in `xic`, which its preedit callbacks write to. It's freed along
+    /// with `xic`, in [`destroy_ic`].
+    preedit: *mut Preedit,
+    ime_allowed: bool,
+    /// Where the input method should show its candidate window.
+    ime_spot: XPoint,
+    raw_mouse_motion: bool,
+    cursor_grab: CursorGrab,
+    cursor_icon: CursorIcon,
+    /// The cursor shown instead of `cursor_icon`, if any.
+    custom_cursor: Option<CustomCursor>,
+    cursor_visible: bool,
+}
+
+/// The preedit text of an input context, as drawn by the input method through its callbacks.
+#[derive(Default)]
+struct Preedit {
+    text: Vec<char>,
+    /// The caret's position in `text`, in characters.
+    caret: usize,
+    /// Whether the text or the caret changed since the last `ImePreedit` event.
+    changed: bool,
 }
 
 pub struct X11Backend {
@@ -66,15 +117,39 @@ pub struct X11Backend {
     windows: HashMap<WindowHandle, X11NativeWindow>,
     event_queue: VecDeque<Event>,
     prev_key: Option<KeyCode>,
+    /// The modifiers reported by the last `ModifiersChanged` event.
+    modifiers: Modifiers,
     str_buffer: Vec<u8>,
     n_windows: u32,
+    event_loop: EventLoop,
+    /// XInput2, if the server supports smooth scrolling.
+    xinput2: Option<XInput2>,
+    /// The window and position of the last `CursorMove` event.
+    cursor_position: Option<(WindowHandle, f64, f64)>,
+    /// The window that locked the cursor, and the pixel it's locked on.
+    cursor_lock: Option<(WindowHandle, c_int, c_int)>,
+    /// The window that confined the cursor to an area smaller than itself, and that area.
+    cursor_confine: Option<(WindowHandle, WindowRect)>,
+    /// The window with the keyboard focus.
+    focus: Option<WindowHandle>,
+    /// Xcursor, which loads cursors from the cursor theme, if it's installed.
+    xcursor: Option<LibXcursor>,
+    /// The cursors loaded by `set_cursor_icon`, which are kept for the other windows.
+    cursors: HashMap<CursorIcon, Cursor>,
+    /// The cursors created by `create_cursor`.
+    custom_cursors: HashMap<CustomCursor, Cursor>,
+    next_cursor_id: u64,
+    /// A transparent cursor that hides the cursor, created the first time it's hidden.
+    invisible_cursor: Option<Cursor>,
 
     #[cfg(feature = "opengl")]
     glx: LibGlx,
 }
 
 impl X11Backend {
-    pub fn init() -> Result<Self, X11NativeCoreError> {
+    /// Connects to the X server, either the one named by `display` (such as `:1`) or the one in
+    /// the `DISPLAY` environment variable.
+    pub fn init(display: Option<&str>) -> Result<Self, X11NativeCoreError> {
         unsafe {
             let x11 = LibX11::new()?;
 
@@ -88,8 +163,24 @@ impl X11Backend {
             (x11.XInitThreads)();
             (x11.XrmInitialize)();
 
-            // Open the default X11 display
-            let display = (x11.XOpenDisplay)(null());
+            // Everything that can fail without the display comes first, so that the display and
+            // the input method don't have to be closed when it does
+            let event_loop =
+                EventLoop::new().map_err(|_| X11NativeCoreError::CannotCreateEventLoop)?;
+
+            #[cfg(feature = "opengl")]
+            let glx = LibGlx::new()?;
+
+            // Open the requested X11 display, or the default one
+            let display_name = display
+                .map(CString::new)
+                .transpose()
+                .map_err(|_| X11NativeCoreError::CannotOpenDisplay)?;
+            let display = (x11.XOpenDisplay)(
+                display_name
+                    .as_ref()
+                    .map_or(null(), |display_name| display_name.as_ptr()),
+            );
             let display = NonNull::new(display).ok_or(X11NativeCoreError::CannotOpenDisplay)?;
 
             let root = (x11.XDefaultRootWindow)(display.as_ptr());
@@ -105,12 +196,14 @@ impl X11Backend {
 
             // Initialize IME
             let xim = (x11.XOpenIM)(display.as_ptr(), null_mut(), null(), null());
-            let xim = NonNull::new(xim).ok_or(X11NativeCoreError::CannotOpenInputMethod)?;
+            let Some(xim) = NonNull::new(xim) else {
+                (x11.XCloseDisplay)(display.as_ptr());
+                return Err(X11NativeCoreError::CannotOpenInputMethod);
+            };
 
-            (x11.XFlush)(display.as_ptr());
+            let xinput2 = XInput2::new(&x11, display);
 
-            #[cfg(feature = "opengl")]
-            let glx = LibGlx::new()?;
+            (x11.XFlush)(display.as_ptr());
 
             Ok(Self {
                 x11,
@@ -120,8 +213,20 @@ impl X11Backend {
                 windows: HashMap::new(),
                 event_queue: VecDeque::new(),
                 prev_key: None,
+                modifiers: Modifiers::empty(),
                 str_buffer: vec![0; 16],
                 n_windows: 0,
+                event_loop,
+                xinput2,
+                cursor_position: None,
+                cursor_lock: None,
+                cursor_confine: None,
+                focus: None,
+                xcursor: LibXcursor::new().ok(),
+                cursors: HashMap::new(),
+                custom_cursors: HashMap::new(),
+                next_cursor_id: 0,
+                invisible_cursor: None,
 
                 #[cfg(feature = "opengl")]
                 glx,
@@ -134,13 +239,27 @@ impl X11Backend {
     }
 
     #[cfg(feature = "opengl")]
-    pub fn window_pos(&self, window: WindowHandle) -> WindowPos {
-        self.get_window(window).position
+    pub fn window_pos(&self, window: WindowHandle) -> Result<WindowPos, LokinitError> {
+        Ok(self.get_window(window)?.position)
     }
 
     #[cfg(feature = "opengl")]
-    pub fn window_size(&self, window: WindowHandle) -> WindowSize {
-        self.get_window(window).size
+    pub fn window_size(&self, window: WindowHandle) -> Result<WindowSize, LokinitError> {
+        Ok(self.get_window(window)?.size)
+    }
+}
+
+impl Drop for X11Backend {
+    fn drop(&mut self) {
+        unsafe {
+            for window in self.windows.values() {
+                destroy_ic(&self.x11, window);
+            }
+            (self.x11.XCloseIM)(self.xim.as_ptr());
+
+            // This also destroys every window that is still open
+            (self.x11.XCloseDisplay)(self.display.as_ptr());
+        }
     }
 }
 
@@ -149,10 +268,14 @@ impl LokinitBackend for X11Backend {
     where
         Self: Sized + 'static,
     {
-        todo!()
+        Self::init(None).unwrap_or_else(|why| panic!("{why}"))
     }
 
-    fn create_window(&mut self, builder: WindowBuilder) -> Result<WindowHandle, CreateWindowError> {
+    fn create_window(&mut self, builder: WindowBuilder) -> Result<WindowHandle, LokinitError> {
+        // Checked before creating the window, so that there's nothing to clean up
+        let title = CString::new(builder.title)
+            .map_err(|_| LokinitError::CreateWindow("the title contains a nul byte".into()))?;
+
         unsafe {
             let mut attributes = XSetWindowAttributes {
                 event_mask: xevent_mask::EXPOSURE
@@ -186,7 +309,6 @@ impl LokinitBackend for X11Backend {
                 &mut attributes,
             );
 
-            let title = CString::new(builder.title).unwrap();
             (self.x11.XStoreName)(self.display.as_ptr(), window, title.as_ptr());
 
             // register interest in the delete window message
@@ -195,24 +317,28 @@ impl LokinitBackend for X11Backend {
                 (self.x11.XInternAtom)(self.display.as_ptr(), atom_name.as_ptr(), 0);
             (self.x11.XSetWMProtocols)(self.display.as_ptr(), window, &wm_delete_message, 1);
 
+            if let Some(xinput2) = &self.xinput2 {
+                xinput2.select_events(self.display, window);
+            }
+
             // spawn window on the screen
             (self.x11.XMapWindow)(self.display.as_ptr(), window);
 
             // create IME context for this window
-            let xic = (self.x11.XCreateIC)(
-                self.xim.as_ptr(),
-                xn::INPUT_STYLE,
-                xim::PREEDIT_NOTHING | xim::STATUS_NOTHING,
-                xn::CLIENT_WINDOW,
-                window,
-                null_mut::<c_void>(),
-            );
-            let xic =
-                NonNull::new(xic).ok_or(CreateWindowError("cannot open X11 display".into()))?;
+            let preedit = Box::into_raw(Box::<Preedit>::default());
+            let Some(xic) = create_ic(&self.x11, self.xim, window, preedit) else {
+                drop(Box::from_raw(preedit));
+                (self.x11.XDestroyWindow)(self.display.as_ptr(), window);
+                (self.x11.XFlush)(self.display.as_ptr());
+                return Err(LokinitError::CreateWindow(
+                    "cannot create the X input context".into(),
+                ));
+            };
 
             // select IME and position it
+            let ime_spot = XPoint::new(0, 0);
             (self.x11.XSetICFocus)(xic.as_ptr());
-            place_ime(&self.x11, xic, XPoint::new(0, 0));
+            place_ime(&self.x11, xic, ime_spot);
 
             (self.x11.XFlush)(self.display.as_ptr());
 
@@ -227,6 +353,14 @@ impl LokinitBackend for X11Backend {
                     size: builder.size,
                     wm_delete_message,
                     xic,
+                    preedit,
+                    ime_allowed: true,
+                    ime_spot,
+                    raw_mouse_motion: false,
+                    cursor_grab: CursorGrab::None,
+                    cursor_icon: CursorIcon::Default,
+                    custom_cursor: None,
+                    cursor_visible: true,
                 },
             );
 
@@ -235,52 +369,100 @@ impl LokinitBackend for X11Backend {
         }
     }
 
-    fn close_window(&mut self, handle: WindowHandle) {
-        let window = self.windows.remove(&handle).unwrap();
-        unsafe { (self.x11.XUnmapWindow)(self.display.as_ptr(), window.window) };
+    fn close_window(&mut self, handle: WindowHandle) -> Result<(), LokinitError> {
+        let window = self
+            .windows
+            .remove(&handle)
+            .ok_or(LokinitError::InvalidWindow(handle))?;
+        unsafe {
+            destroy_ic(&self.x11, &window);
+            (self.x11.XUnmapWindow)(self.display.as_ptr(), window.window);
+        }
         self.n_windows -= 1;
+        if window.raw_mouse_motion {
+            self.select_raw_motion();
+        }
+        if self.focus == Some(handle) {
+            self.focus = None;
+            self.update_cursor_grab()?;
+        }
+        Ok(())
     }
 
-    fn poll_event(&mut self) -> Option<Event> {
-        if self.n_windows == 0 {
-            // No events to poll if there are no windows
-            return None;
-        }
+    fn poll_event(&mut self) -> Result<Option<Event>, LokinitError> {
+        self.wait_event(None)
+    }
 
-        if let Some(win_event) = self.event_queue.pop_front() {
-            return Some(win_event);
-        }
+    fn try_poll_event(&mut self) -> Result<Option<Event>, LokinitError> {
+        self.wait_event(Some(Duration::ZERO))
+    }
 
-        unsafe {
-            // make sure we always poll a Lokinit event by skipping unknown ones
-            let mut win_event = None;
-            while win_event.is_none() {
-                let count = (self.x11.XPending)(self.display.as_ptr());
-
-                // get all pending events or wait for the next one
-                for _ in 0..count.max(1) {
-                    let mut xevent = XEvent { type_id: 0 };
-                    (self.x11.XNextEvent)(self.display.as_ptr(), &mut xevent);
-
-                    // Apparently, this forwards the event to the IME and returns whether the event was consumed.
-                    // I know, weird. The name of the function is even weirder.
-                    if (self.x11.XFilterEvent)(&mut xevent, XWindow::NONE) > 0 {
-                        continue;
-                    }
+    fn poll_event_timeout(&mut self, timeout: Duration) -> Result<Option<Event>, LokinitError> {
+        self.wait_event(Some(timeout))
+    }
 
+    fn dispatch_pending(&mut self) -> Result<(), LokinitError> {
+        unsafe {
+            // process every event that has already been received
+            while (self.x11.XPending)(self.display.as_ptr()) > 0 {
+                let mut xevent = XEvent { type_id: 0 };
+                (self.x11.XNextEvent)(self.display.as_ptr(), &mut xevent);
+
+                // Apparently, this forwards the event to the IME and returns whether the event was consumed.
+                // I know, weird. The name of the function is even weirder.
+                if (self.x11.XFilterEvent)(&mut xevent, XWindow::NONE) == 0 {
                     self.process_event(&xevent);
                 }
 
-                (self.x11.XFlush)(self.display.as_ptr());
-
-                win_event = self.event_queue.pop_front();
+                // The IME may have drawn preedit text while handling the event
+                self.queue_preedits();
             }
 
-            win_event
+            (self.x11.XFlush)(self.display.as_ptr());
         }
+
+        self.event_loop.dispatch(&mut self.event_queue);
+        Ok(())
+    }
+
+    fn next_queued_event(&mut self) -> Option<Event> {
+        self.event_queue.pop_front()
+    }
+
+    fn connection_fd(&self) -> Result<RawFd, LokinitError> {
+        Ok(unsafe { (self.x11.XConnectionNumber)(self.display.as_ptr()) })
+    }
+
+    fn create_proxy(&mut self) -> Result<EventLoopProxy, LokinitError> {
+        Ok(self.event_loop.create_proxy())
+    }
+
+    fn set_timer(&mut self, duration: Duration, repeat: bool) -> Result<TimerId, LokinitError> {
+        Ok(self.event_loop.set_timer(duration, repeat))
+    }
+
+    fn cancel_timer(&mut self, timer: TimerId) -> Result<(), LokinitError> {
+        self.event_loop.cancel_timer(timer);
+        Ok(())
+    }
+
+    fn watch_fd(&mut self, fd: RawFd, interest: Interest) -> Result<(), LokinitError> {
+        self.event_loop.watch_fd(fd, interest);
+        Ok(())
+    }
+
+    fn unwatch_fd(&mut self, fd: RawFd) -> Result<(), LokinitError> {
+        self.event_loop.unwatch_fd(fd);
+        Ok(())
     }
 
-    fn set_screen_mode(&mut self, window: WindowHandle, screen_mode: ScreenMode) {
+    fn set_screen_mode(
+        &mut self,
+        window: WindowHandle,
+        screen_mode: ScreenMode,
+    ) -> Result<(), LokinitError> {
+        self.get_window(window)?;
+
         // TODO: what really is the difference between borderless and fullscreen on X11?
         // What about bypassing the compositor as well?
 
@@ -307,10 +489,251 @@ impl LokinitBackend for X11Backend {
                 );
             }
         }
+
+        Ok(())
     }
 
-    fn fetch_monitors(&mut self) -> Vec<crate::prelude::Monitor> {
-        todo!()
+    fn fetch_monitors(&mut self) -> Result<Vec<crate::prelude::Monitor>, LokinitError> {
+        Err(LokinitError::Unsupported("fetching monitors"))
+    }
+
+    fn set_ime_cursor_area(
+        &mut self,
+        handle: WindowHandle,
+        area: WindowRect,
+    ) -> Result<(), LokinitError> {
+        let window = self
+            .windows
+            .get_mut(&handle)
+            .ok_or(LokinitError::InvalidWindow(handle))?;
+
+        // The spot is where the text's baseline starts, so right below the cursor
+        let clamp = |n: i64| n.clamp(i16::MIN.into(), i16::MAX.into()) as i16;
+        window.ime_spot = XPoint::new(
+            clamp(area.x.into()),
+            clamp(i64::from(area.y) + i64::from(area.height)),
+        );
+        unsafe { place_ime(&self.x11, window.xic, window.ime_spot) };
+        Ok(())
+    }
+
+    fn set_ime_allowed(&mut self, handle: WindowHandle, allowed: bool) -> Result<(), LokinitError> {
+        let window = self
+            .windows
+            .get_mut(&handle)
+            .ok_or(LokinitError::InvalidWindow(handle))?;
+        if window.ime_allowed == allowed {
+            return Ok(());
+        }
+        window.ime_allowed = allowed;
+
+        unsafe {
+            if allowed {
+                (self.x11.XSetICFocus)(window.xic.as_ptr());
+                place_ime(&self.x11, window.xic, window.ime_spot);
+            } else {
+                (self.x11.XUnsetICFocus)(window.xic.as_ptr());
+
+                // Drop the text being composed, which would be committed otherwise
+                let text = (self.x11.Xutf8ResetIC)(window.xic.as_ptr());
+                if !text.is_null() {
+                    (self.x11.XFree)(text as *mut c_void);
+                }
+                let preedit = &mut *window.preedit;
+                if !preedit.text.is_empty() {
+                    *preedit = Preedit {
+                        changed: true,
+                        ..Default::default()
+                    };
+                }
+            }
+        }
+
+        self.queue_preedits();
+        Ok(())
+    }
+
+    fn set_raw_mouse_motion(
+        &mut self,
+        handle: WindowHandle,
+        enabled: bool,
+    ) -> Result<(), LokinitError> {
+        if self.xinput2.is_none() {
+            return Err(LokinitError::Unsupported("raw mouse motion"));
+        }
+        let window = self
+            .windows
+            .get_mut(&handle)
+            .ok_or(LokinitError::InvalidWindow(handle))?;
+        window.raw_mouse_motion = enabled;
+
+        self.select_raw_motion();
+        Ok(())
+    }
+
+    fn set_cursor_grab(
+        &mut self,
+        handle: WindowHandle,
+        grab: CursorGrab,
+    ) -> Result<(), LokinitError> {
+        let window = self
+            .windows
+            .get_mut(&handle)
+            .ok_or(LokinitError::InvalidWindow(handle))?;
+        window.cursor_grab = grab;
+
+        if self.focus == Some(handle) {
+            self.update_cursor_grab()?;
+        }
+        Ok(())
+    }
+
+    fn set_cursor_icon(
+        &mut self,
+        handle: WindowHandle,
+        icon: CursorIcon,
+    ) -> Result<(), LokinitError> {
+        let window = self
+            .windows
+            .get_mut(&handle)
+            .ok_or(LokinitError::InvalidWindow(handle))?;
+        window.cursor_icon = icon;
+        window.custom_cursor = None;
+
+        self.update_window_cursor(handle);
+        Ok(())
+    }
+
+    fn set_cursor_visible(
+        &mut self,
+        handle: WindowHandle,
+        visible: bool,
+    ) -> Result<(), LokinitError> {
+        let window = self
+            .windows
+            .get_mut(&handle)
+            .ok_or(LokinitError::InvalidWindow(handle))?;
+        window.cursor_visible = visible;
+
+        self.update_window_cursor(handle);
+        Ok(())
+    }
+
+    fn set_cursor_position(
+        &mut self,
+        handle: WindowHandle,
+        x: f64,
+        y: f64,
+    ) -> Result<(), LokinitError> {
+        let window = self.get_window(handle)?.window;
+        let (x, y) = (x.round() as c_int, y.round() as c_int);
+
+        // Otherwise the cursor would be warped back to where it was locked
+        if let Some((lock, ..)) = self.cursor_lock {
+            if lock == handle {
+                self.cursor_lock = Some((handle, x, y));
+            }
+        }
+        unsafe {
+            (self.x11.XWarpPointer)(
+                self.display.as_ptr(),
+                XWindow::NONE,
+                window,
+                0,
+                0,
+                0,
+                0,
+                x,
+                y,
+            );
+            (self.x11.XFlush)(self.display.as_ptr());
+        }
+        Ok(())
+    }
+
+    fn create_cursor(&mut self, frames: &[CursorFrame]) -> Result<CustomCursor, LokinitError> {
+        let Some(xcursor) = self.xcursor.as_ref() else {
+            return Err(LokinitError::Unsupported("custom cursors"));
+        };
+        let out_of_memory = || LokinitError::from(io::Error::from(io::ErrorKind::OutOfMemory));
+
+        let cursor = unsafe {
+            if let [frame] = frames {
+                let image = create_xcursor_image(xcursor, frame).ok_or_else(out_of_memory)?;
+                let cursor = (xcursor.XcursorImageLoadCursor)(self.display.as_ptr(), image);
+                (xcursor.XcursorImageDestroy)(image);
+                cursor
+            } else {
+                let images = (xcursor.XcursorImagesCreate)(frames.len() as c_int);
+                if images.is_null() {
+                    return Err(out_of_memory());
+                }
+                for frame in frames {
+                    let Some(image) = create_xcursor_image(xcursor, frame) else {
+                        (xcursor.XcursorImagesDestroy)(images);
+                        return Err(out_of_memory());
+                    };
+                    *(*images).images.add((*images).nimage as usize) = image;
+                    (*images).nimage += 1;
+                }
+                // The server animates the cursor by itself
+                let cursor = (xcursor.XcursorImagesLoadCursor)(self.display.as_ptr(), images);
+                (xcursor.XcursorImagesDestroy)(images);
+                cursor
+            }
+        };
+        if cursor == XID::NONE {
+            return Err(LokinitError::Protocol(
+                "The X server failed to create the cursor".into(),
+            ));
+        }
+
+        let custom_cursor = CustomCursor(self.next_cursor_id);
+        self.next_cursor_id += 1;
+        self.custom_cursors.insert(custom_cursor, cursor);
+        Ok(custom_cursor)
+    }
+
+    fn destroy_cursor(&mut self, cursor: CustomCursor) -> Result<(), LokinitError> {
+        let x_cursor = self
+            .custom_cursors
+            .remove(&cursor)
+            .ok_or(LokinitError::InvalidCursor(cursor))?;
+
+        let mut handles = Vec::new();
+        for (handle, window) in self.windows.iter_mut() {
+            if window.custom_cursor == Some(cursor) {
+                window.custom_cursor = None;
+                window.cursor_icon = CursorIcon::Default;
+                handles.push(*handle);
+            }
+        }
+        for handle in handles {
+            self.update_window_cursor(handle);
+        }
+        unsafe {
+            (self.x11.XFreeCursor)(self.display.as_ptr(), x_cursor);
+            (self.x11.XFlush)(self.display.as_ptr());
+        }
+        Ok(())
+    }
+
+    fn set_custom_cursor(
+        &mut self,
+        handle: WindowHandle,
+        cursor: CustomCursor,
+    ) -> Result<(), LokinitError> {
+        if !self.custom_cursors.contains_key(&cursor) {
+            return Err(LokinitError::InvalidCursor(cursor));
+        }
+        let window = self
+            .windows
+            .get_mut(&handle)
+            .ok_or(LokinitError::InvalidWindow(handle))?;
+        window.custom_cursor = Some(cursor);
+
+        self.update_window_cursor(handle);
+        Ok(())
     }
 
     #[cfg(feature = "opengl")]
@@ -318,7 +741,7 @@ impl LokinitBackend for X11Backend {
         &mut self,
         window: WindowHandle,
         config: OpenGlConfig,
-    ) -> super::WindowSurface {
+    ) -> Result<super::WindowSurface, LokinitError> {
         use std::ptr;
 
         use loki_linux::glx::{
@@ -364,7 +787,9 @@ impl LokinitBackend for X11Backend {
             );
 
             if fb_configs.is_null() || fb_count == 0 {
-                panic!("no GLX framebuffer config available");
+                return Err(LokinitError::Unsupported(
+                    "no GLX framebuffer config available",
+                ));
             }
 
             let fb_config: GLXFBConfig = *fb_configs;
@@ -407,12 +832,17 @@ impl LokinitBackend for X11Backend {
                 println!("glx context is indirect");
             }
 
-            GlSurface(context)
+            Ok(GlSurface(context))
         }
     }
 
     #[cfg(feature = "opengl")]
-    fn make_surface_active(&self, window: WindowHandle, surface: super::WindowSurface) {
+    fn make_surface_active(
+        &self,
+        window: WindowHandle,
+        surface: super::WindowSurface,
+    ) -> Result<(), LokinitError> {
+        self.get_window(window)?;
         unsafe {
             (self.glx.glXMakeCurrent)(
                 self.display.as_ptr(),
@@ -420,18 +850,26 @@ impl LokinitBackend for X11Backend {
                 surface.0,
             );
         }
+        Ok(())
     }
 
     #[cfg(feature = "opengl")]
-    fn flush_surface(&self, window: WindowHandle, _surface: super::WindowSurface) {
+    fn flush_surface(
+        &self,
+        window: WindowHandle,
+        _surface: super::WindowSurface,
+    ) -> Result<(), LokinitError> {
+        self.get_window(window)?;
         unsafe {
             (self.glx.glXSwapBuffers)(self.display.as_ptr(), XID::from_raw(window.0 as u64));
         }
+        Ok(())
     }
 
     #[cfg(feature = "opengl")]
-    fn update_surface(&self, surface: super::WindowSurface) {
-        todo!()
+    fn update_surface(&self, surface: super::WindowSurface) -> Result<(), LokinitError> {
+        // GLX surfaces follow the window's size on their own
+        Ok(())
     }
 
     #[cfg(feature = "opengl")]
@@ -448,8 +886,60 @@ enum WmStateAction {
 }
 
 impl X11Backend {
-    fn get_window(&self, window: WindowHandle) -> &X11NativeWindow {
-        self.windows.get(&window).unwrap()
+    /// Waits for the next Lokinit event for at most `timeout`, or indefinitely if `timeout` is `None`.
+    fn wait_event(&mut self, timeout: Option<Duration>) -> Result<Option<Event>, LokinitError> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+
+        loop {
+            if let Some(win_event) = self.event_queue.pop_front() {
+                return Ok(Some(win_event));
+            }
+
+            self.dispatch_pending()?;
+            if !self.event_queue.is_empty() {
+                continue;
+            }
+
+            // without windows, only Lokinit's own event sources could still produce events
+            if self.n_windows == 0 && self.event_loop.is_idle() {
+                return Ok(None);
+            }
+
+            // nothing to report yet, so sleep until the X server or an event source wakes us up
+            if !self.event_loop.wait(self.connection_fd()?, deadline)? {
+                return Ok(None);
+            }
+        }
+    }
+
+    /// Queues an `ImePreedit` event for every window whose preedit text changed.
+    fn queue_preedits(&mut self) {
+        for (&handle, window) in &self.windows {
+            // SAFETY: the preedit lives as long as the window, and the callbacks writing to it
+            // only run while Xlib handles events, which isn't the case here.
+            let preedit = unsafe { &mut *window.preedit };
+            if !std::mem::take(&mut preedit.changed) {
+                continue;
+            }
+
+            let text = preedit.text.iter().collect::<String>();
+            let caret = preedit.text[..preedit.caret]
+                .iter()
+                .map(|c| c.len_utf8())
+                .sum::<usize>();
+            let cursor_range = (!text.is_empty()).then_some((caret, caret));
+            self.event_queue.push_back(Event {
+                time: Duration::ZERO,
+                window: handle,
+                kind: EventKind::Keyboard(KeyboardEvent::ImePreedit { text, cursor_range }),
+            });
+        }
+    }
+
+    fn get_window(&self, window: WindowHandle) -> Result<&X11NativeWindow, LokinitError> {
+        self.windows
+            .get(&window)
+            .ok_or(LokinitError::InvalidWindow(window))
     }
 
     unsafe fn intern_atom(&self, name: &[u8]) -> Atom {
@@ -514,25 +1004,44 @@ impl X11Backend {
                 let (keysym, text) =
                     utf8_lookup_string(&self.x11, &mut self.str_buffer, window, xevent);
 
-                if let Some(keycode) = keysym::to_keycode(keysym.raw() as u32) {
+                // The state is the one from right before the event
+                let modifiers = modifiers_from_mask(xevent.state);
+                update_modifiers(
+                    &mut self.event_queue,
+                    &mut self.modifiers,
+                    handle,
+                    time,
+                    modifiers,
+                );
+
+                // X servers using evdev offset its codes by 8
+                let scancode = ScanCode(xevent.keycode.saturating_sub(8));
+
+                let raw_keysym = keysym.raw() as u32;
+                // There's no keysym when the input method only has text to commit
+                if raw_keysym != 0 {
+                    let keycode =
+                        keysym::to_keycode(raw_keysym).unwrap_or(KeyCode::Unidentified(raw_keysym));
                     let kb_event = match (xevent.type_id, self.prev_key) {
                         (et::KEY_PRESS, Some(k)) if k == keycode => {
-                            KeyboardEvent::KeyRepeat(keycode)
+                            KeyboardEvent::KeyRepeat(keycode, scancode, modifiers)
                         }
                         (et::KEY_PRESS, _) => {
                             self.prev_key = Some(keycode);
-                            KeyboardEvent::KeyPress(keycode)
+                            KeyboardEvent::KeyPress(keycode, scancode, modifiers)
                         }
                         (et::KEY_RELEASE, Some(k)) if k == keycode => {
                             self.prev_key = None;
-                            KeyboardEvent::KeyRelease(keycode)
+                            KeyboardEvent::KeyRelease(keycode, scancode, modifiers)
+                        }
+                        (et::KEY_RELEASE, _) => {
+                            KeyboardEvent::KeyRelease(keycode, scancode, modifiers)
                         }
-                        (et::KEY_RELEASE, _) => KeyboardEvent::KeyRelease(keycode),
                         _ => unreachable!(),
                     };
 
                     // Send IME commit only on a non-repeated key press
-                    let do_ime = matches!(kb_event, KeyboardEvent::KeyPress(_));
+                    let do_ime = matches!(kb_event, KeyboardEvent::KeyPress(..));
 
                     self.event_queue.push_back(Event {
                         time,
@@ -540,6 +1049,28 @@ impl X11Backend {
                         kind: EventKind::Keyboard(kb_event),
                     });
 
+                    // Apply the key itself, since X only reports it in the next event's state
+                    let flag = Modifiers::for_key(keycode);
+                    let is_lock = flag == Modifiers::CAPS_LOCK || flag == Modifiers::NUM_LOCK;
+                    let mut new_modifiers = modifiers;
+                    match (xevent.type_id, is_lock) {
+                        // Locks toggle on press, and don't toggle again when the key repeats
+                        (et::KEY_PRESS, true) if do_ime && modifiers.contains(flag) => {
+                            new_modifiers.remove(flag)
+                        }
+                        (et::KEY_PRESS, true) if do_ime => new_modifiers.insert(flag),
+                        (et::KEY_PRESS, false) => new_modifiers.insert(flag),
+                        (_, false) => new_modifiers.remove(flag),
+                        _ => {}
+                    }
+                    update_modifiers(
+                        &mut self.event_queue,
+                        &mut self.modifiers,
+                        handle,
+                        time,
+                        new_modifiers,
+                    );
+
                     if !do_ime {
                         return Some(());
                     }
@@ -547,7 +1078,7 @@ impl X11Backend {
 
                 // Handle IME commit
                 if let Some(text) = text {
-                    place_ime(&self.x11, window.xic, XPoint::new(0, 0));
+                    place_ime(&self.x11, window.xic, window.ime_spot);
                     self.event_queue.push_back(Event {
                         time,
                         window: handle,
@@ -562,17 +1093,59 @@ impl X11Backend {
 
                 let handle = WindowHandle::from(xevent.window);
 
+                let modifiers = modifiers_from_mask(xevent.state as u32);
+                update_modifiers(
+                    &mut self.event_queue,
+                    &mut self.modifiers,
+                    handle,
+                    time,
+                    modifiers,
+                );
+
+                // The wheel is reported as buttons, which are pressed then released right away
+                // for each notch. Only presses count, so that notches aren't counted twice.
+                let wheel = match xevent.button {
+                    4 => Some((0.0, 1.0)),
+                    5 => Some((0.0, -1.0)),
+                    6 => Some((-1.0, 0.0)),
+                    7 => Some((1.0, 0.0)),
+                    _ => None,
+                };
+                if let Some((x, y)) = wheel {
+                    // XInput2 reports the same scrolling with more precision
+                    if xevent.type_id == et::BUTTON_PRESS && self.xinput2.is_none() {
+                        self.event_queue.push_back(Event {
+                            time,
+                            window: handle,
+                            kind: EventKind::Mouse(MouseEvent::Scroll(ScrollEvent::lines(x, y))),
+                        });
+                    }
+                    return Some(());
+                }
+
                 let mouse_button = match xevent.button {
                     1 => MouseButton::Left,
                     2 => MouseButton::Middle,
                     3 => MouseButton::Right,
+                    8 => MouseButton::Back,
+                    9 => MouseButton::Forward,
                     b => MouseButton::Other(b as u16),
                 };
 
                 let kind = if xevent.type_id == et::BUTTON_PRESS {
-                    EventKind::Mouse(MouseEvent::ButtonPress(mouse_button, xevent.x, xevent.y))
+                    EventKind::Mouse(MouseEvent::ButtonPress(
+                        mouse_button,
+                        xevent.x.into(),
+                        xevent.y.into(),
+                        modifiers,
+                    ))
                 } else {
-                    EventKind::Mouse(MouseEvent::ButtonRelease(mouse_button, xevent.x, xevent.y))
+                    EventKind::Mouse(MouseEvent::ButtonRelease(
+                        mouse_button,
+                        xevent.x.into(),
+                        xevent.y.into(),
+                        modifiers,
+                    ))
                 };
 
                 self.event_queue.push_back(Event {
@@ -630,11 +1203,58 @@ impl X11Backend {
                 let time = Duration::from_millis(xevent.time);
 
                 let handle = WindowHandle::from(xevent.window);
+                update_modifiers(
+                    &mut self.event_queue,
+                    &mut self.modifiers,
+                    handle,
+                    time,
+                    modifiers_from_mask(xevent.state),
+                );
+
+                let (x, y) = (xevent.x.into(), xevent.y.into());
+                if self.keep_cursor_grabbed(handle, x, y) {
+                    return None;
+                }
+                self.cursor_position = Some((handle, x, y));
 
                 self.event_queue.push_back(Event {
                     time,
                     window: handle,
-                    kind: EventKind::Mouse(MouseEvent::CursorMove(xevent.x, xevent.y)),
+                    kind: EventKind::Mouse(MouseEvent::CursorMove(x, y)),
+                });
+            }
+
+            et::FOCUS_IN | et::FOCUS_OUT => {
+                // The window under the pointer gets these too when nothing has the focus
+                const NOTIFY_POINTER: c_int = 5;
+
+                let xevent = xevent.xfocus;
+                let handle = WindowHandle::from(xevent.window);
+                if xevent.detail == NOTIFY_POINTER {
+                    return None;
+                }
+
+                let kind = if xevent.type_id == et::FOCUS_IN {
+                    if self.focus == Some(handle) || !self.windows.contains_key(&handle) {
+                        return None;
+                    }
+                    self.focus = Some(handle);
+                    EventKind::FocusIn
+                } else {
+                    if self.focus != Some(handle) {
+                        return None;
+                    }
+                    self.focus = None;
+                    EventKind::FocusOut
+                };
+                // The grab is only lost until the window gets the focus again, so this isn't worth
+                // failing the event loop for
+                let _ = self.update_cursor_grab();
+
+                self.event_queue.push_back(Event {
+                    time: Duration::ZERO,
+                    window: handle,
+                    kind,
                 });
             }
 
@@ -643,11 +1263,25 @@ impl X11Backend {
                 let time = Duration::from_millis(xevent.time);
 
                 let handle = WindowHandle::from(xevent.window);
+                if xevent.type_id == et::ENTER_NOTIFY {
+                    if let Some(xinput2) = &mut self.xinput2 {
+                        xinput2.reset_scroll();
+                    }
+
+                    // Modifiers may have changed while the cursor was in another window
+                    update_modifiers(
+                        &mut self.event_queue,
+                        &mut self.modifiers,
+                        handle,
+                        time,
+                        modifiers_from_mask(xevent.state),
+                    );
+                }
 
                 let kind = if xevent.type_id == et::ENTER_NOTIFY {
-                    EventKind::Mouse(MouseEvent::CursorIn(xevent.x, xevent.y))
+                    EventKind::Mouse(MouseEvent::CursorIn(xevent.x.into(), xevent.y.into()))
                 } else {
-                    EventKind::Mouse(MouseEvent::CursorOut(xevent.x, xevent.y))
+                    EventKind::Mouse(MouseEvent::CursorOut(xevent.x.into(), xevent.y.into()))
                 };
 
                 self.event_queue.push_back(Event {
@@ -674,11 +1308,283 @@ impl X11Backend {
                 }
             }
 
+            et::GENERIC_EVENT => {
+                let mut cookie = xevent.xcookie;
+                let xinput2 = self.xinput2.as_ref()?;
+                if cookie.extension != xinput2.opcode
+                    || (self.x11.XGetEventData)(self.display.as_ptr(), &mut cookie) == 0
+                {
+                    return None;
+                }
+
+                match cookie.evtype {
+                    xi::MOTION => self.process_xi_motion(&*(cookie.data as *const XIDeviceEvent)),
+                    xi::RAW_MOTION => {
+                        self.process_xi_raw_motion(&*(cookie.data as *const XIRawEvent))
+                    }
+                    _ => (),
+                }
+                (self.x11.XFreeEventData)(self.display.as_ptr(), &mut cookie);
+            }
+
             _ => (),
         }
 
         Some(())
     }
+
+    /// Loads the cursor for an icon from the cursor theme, or from the core cursor font if the
+    /// theme doesn't have it.
+    fn load_cursor(&mut self, icon: CursorIcon) -> Cursor {
+        if let Some(cursor) = self.cursors.get(&icon) {
+            return *cursor;
+        }
+
+        let theme_cursor = self.xcursor.as_ref().and_then(|xcursor| {
+            let names = [icon.name()]
+                .into_iter()
+                .chain(icon.legacy_names().iter().copied());
+            names
+                .map(|name| CString::new(name).unwrap())
+                .find_map(|name| {
+                    let cursor = unsafe {
+                        (xcursor.XcursorLibraryLoadCursor)(self.display.as_ptr(), name.as_ptr())
+                    };
+                    (cursor != XID::NONE).then_some(cursor)
+                })
+        });
+        let cursor = theme_cursor.unwrap_or_else(|| unsafe {
+            (self.x11.XCreateFontCursor)(self.display.as_ptr(), font_cursor_shape(icon))
+        });
+
+        self.cursors.insert(icon, cursor);
+        cursor
+    }
+
+    /// Shows the cursor a window asks for: none if it's hidden, its custom cursor if it has one,
+    /// or its cursor icon.
+    fn update_window_cursor(&mut self, handle: WindowHandle) {
+        let Some(window) = self.windows.get(&handle) else {
+            return;
+        };
+        let (xwindow, icon) = (window.window, window.cursor_icon);
+        let custom_cursor = window
+            .custom_cursor
+            .and_then(|cursor| self.custom_cursors.get(&cursor).copied());
+
+        let cursor = if !window.cursor_visible {
+            self.invisible_cursor()
+        } else if let Some(cursor) = custom_cursor {
+            cursor
+        } else {
+            self.load_cursor(icon)
+        };
+        unsafe {
+            (self.x11.XDefineCursor)(self.display.as_ptr(), xwindow, cursor);
+            (self.x11.XFlush)(self.display.as_ptr());
+        }
+    }
+
+    /// A cursor with a single transparent pixel.
+    fn invisible_cursor(&mut self) -> Cursor {
+        *self.invisible_cursor.get_or_insert_with(|| unsafe {
+            let display = self.display.as_ptr();
+            let data: c_char = 0;
+            let bitmap = (self.x11.XCreateBitmapFromData)(display, self.root, &data, 1, 1);
+            let mut color = XColor::default();
+            let cursor = (self.x11.XCreatePixmapCursor)(
+                display, bitmap, bitmap, &mut color, &mut color, 0, 0,
+            );
+            (self.x11.XFreePixmap)(display, bitmap);
+            cursor
+        })
+    }
+
+    /// Grabs the pointer for the focused window if it asks for it, and releases it otherwise.
+    fn update_cursor_grab(&mut self) -> Result<(), LokinitError> {
+        self.cursor_lock = None;
+        self.cursor_confine = None;
+
+        let grab = self
+            .focus
+            .and_then(|handle| {
+                let window = self.windows.get(&handle)?;
+                Some((handle, window.window, window.size, window.cursor_grab))
+            })
+            .filter(|(.., grab)| *grab != CursorGrab::None);
+        let Some((handle, window, size, grab)) = grab else {
+            unsafe { (self.x11.XUngrabPointer)(self.display.as_ptr(), CURRENT_TIME) };
+            return Ok(());
+        };
+
+        // The pointer's events still go to the window that's under it, as without a grab
+        let event_mask =
+            xevent_mask::BUTTON_PRESS | xevent_mask::BUTTON_RELEASE | xevent_mask::POINTER_MOTION;
+        let status = unsafe {
+            (self.x11.XGrabPointer)(
+                self.display.as_ptr(),
+                window,
+                true as _,
+                event_mask as _,
+                grab_mode::ASYNC,
+                grab_mode::ASYNC,
+                window,
+                XID::NONE,
+                CURRENT_TIME,
+            )
+        };
+        match status {
+            grab_status::SUCCESS => (),
+            grab_status::ALREADY_GRABBED => {
+                return Err(LokinitError::GrabCursor("another client grabbed it"))
+            }
+            grab_status::NOT_VIEWABLE => {
+                return Err(LokinitError::GrabCursor("the window isn't viewable"))
+            }
+            grab_status::FROZEN => return Err(LokinitError::GrabCursor("another client froze it")),
+            _ => return Err(LokinitError::GrabCursor("the X server refused the grab")),
+        }
+
+        // X11 can't lock the cursor, so it's warped back whenever it moves. The pointer only
+        // lands on whole pixels, so the lock point is rounded once instead of compared as is.
+        if grab == CursorGrab::Locked {
+            let (x, y) = match self.cursor_position {
+                Some((window, x, y)) if window == handle => (x.round(), y.round()),
+                _ => (f64::from(size.width / 2), f64::from(size.height / 2)),
+            };
+            self.cursor_lock = Some((handle, x as c_int, y as c_int));
+        }
+        // The grab confines the cursor to the whole window, and warping keeps it in the area
+        if let CursorGrab::ConfinedTo(area) = grab {
+            self.cursor_confine = Some((handle, area));
+        }
+        Ok(())
+    }
+
+    /// Warps the cursor back to where it's locked if it moved away from there, or back into the
+    /// area it's confined to if it left it. Returns whether the cursor was warped or is locked in
+    /// the window, in which case its motion shouldn't be reported.
+    fn keep_cursor_grabbed(&self, handle: WindowHandle, x: f64, y: f64) -> bool {
+        if let Some((_, area)) = self
+            .cursor_confine
+            .filter(|(confine, _)| *confine == handle)
+        {
+            let (x, y) = (x.round() as c_int, y.round() as c_int);
+            let right = area.x + (area.width as c_int - 1).max(0);
+            let bottom = area.y + (area.height as c_int - 1).max(0);
+            let (inside_x, inside_y) = (x.clamp(area.x, right), y.clamp(area.y, bottom));
+            if (x, y) == (inside_x, inside_y) {
+                return false;
+            }
+
+            unsafe {
+                (self.x11.XWarpPointer)(
+                    self.display.as_ptr(),
+                    XWindow::NONE,
+                    XWindow::from(handle),
+                    0,
+                    0,
+                    0,
+                    0,
+                    inside_x,
+                    inside_y,
+                );
+            }
+            return true;
+        }
+
+        let Some((_, lock_x, lock_y)) = self.cursor_lock.filter(|(lock, ..)| *lock == handle)
+        else {
+            return false;
+        };
+
+        if (x.round() as c_int, y.round() as c_int) != (lock_x, lock_y) {
+            unsafe {
+                (self.x11.XWarpPointer)(
+                    self.display.as_ptr(),
+                    XWindow::NONE,
+                    XWindow::from(handle),
+                    0,
+                    0,
+                    0,
+                    0,
+                    lock_x,
+                    lock_y,
+                );
+            }
+        }
+        true
+    }
+
+    /// Selects raw motion events while any window wants them.
+    fn select_raw_motion(&self) {
+        if let Some(xinput2) = &self.xinput2 {
+            let on = self.windows.values().any(|window| window.raw_mouse_motion);
+            unsafe { xinput2.select_raw_motion(self.display, self.root, on) };
+        }
+    }
+
+    /// Handles an XInput2 raw motion event, sending it to the focused window if it wants it.
+    unsafe fn process_xi_raw_motion(&mut self, xevent: &XIRawEvent) {
+        let Some(handle) = self.focus else {
+            return;
+        };
+        if !self
+            .windows
+            .get(&handle)
+            .is_some_and(|window| window.raw_mouse_motion)
+        {
+            return;
+        }
+
+        let (dx, dy) = XInput2::raw_delta(xevent);
+        if dx != 0.0 || dy != 0.0 {
+            self.event_queue.push_back(Event {
+                time: Duration::from_millis(xevent.time),
+                window: handle,
+                kind: EventKind::RawMouseMotion { dx, dy },
+            });
+        }
+    }
+
+    /// Handles an XInput2 motion event, which replaces the core one and also reports scrolling.
+    unsafe fn process_xi_motion(&mut self, xevent: &XIDeviceEvent) {
+        let time = Duration::from_millis(xevent.time);
+        let handle = WindowHandle::from(xevent.event);
+        update_modifiers(
+            &mut self.event_queue,
+            &mut self.modifiers,
+            handle,
+            time,
+            modifiers_from_mask(xevent.mods.effective as u32),
+        );
+
+        let Some(xinput2) = &mut self.xinput2 else {
+            return;
+        };
+        // Touchpads scroll by fractions of steps too, since XInput2 can't tell them from wheels
+        let (x, y) = xinput2.scroll_delta(self.display, xevent);
+        if x != 0.0 || y != 0.0 {
+            self.event_queue.push_back(Event {
+                time,
+                window: handle,
+                kind: EventKind::Mouse(MouseEvent::Scroll(ScrollEvent::lines(x, y))),
+            });
+        }
+
+        // Scrolling alone doesn't move the cursor
+        let (x, y) = (xevent.event_x, xevent.event_y);
+        if self.keep_cursor_grabbed(handle, x, y) {
+            return;
+        }
+        if self.cursor_position.replace((handle, x, y)) != Some((handle, x, y)) {
+            self.event_queue.push_back(Event {
+                time,
+                window: handle,
+                kind: EventKind::Mouse(MouseEvent::CursorMove(x, y)),
+            });
+        }
+    }
 }
 
 unsafe extern "C" fn x11_error_handler(_display: *mut XDisplay, event: *mut XErrorEvent) -> i32 {
@@ -691,6 +1597,219 @@ unsafe extern "C" fn x11_error_handler(_display: *mut XDisplay, event: *mut XErr
     0
 }
 
+/// Creates an Xcursor image from a cursor frame. Returns `None` if it couldn't be allocated.
+unsafe fn create_xcursor_image(
+    xcursor: &LibXcursor,
+    frame: &CursorFrame,
+) -> Option<*mut XcursorImage> {
+    let image = (xcursor.XcursorImageCreate)(frame.width() as c_int, frame.height() as c_int);
+    if image.is_null() {
+        return None;
+    }
+
+    let image_ref = &mut *image;
+    (image_ref.xhot, image_ref.yhot) = frame.hotspot();
+    image_ref.delay = frame.delay().as_millis().try_into().unwrap_or(c_uint::MAX);
+    let pixels = std::slice::from_raw_parts_mut(
+        image_ref.pixels,
+        (image_ref.width * image_ref.height) as usize,
+    );
+    for (pixel, argb) in pixels.iter_mut().zip(frame.argb_pixels()) {
+        *pixel = argb;
+    }
+
+    Some(image)
+}
+
+/// The shape of the core cursor font that looks the most like an icon.
+fn font_cursor_shape(icon: CursorIcon) -> c_uint {
+    match icon {
+        CursorIcon::Default => cursor_font::LEFT_PTR,
+        CursorIcon::Pointer => cursor_font::HAND2,
+        CursorIcon::Text => cursor_font::XTERM,
+        CursorIcon::Crosshair => cursor_font::CROSSHAIR,
+        CursorIcon::Help => cursor_font::QUESTION_ARROW,
+        CursorIcon::Wait | CursorIcon::Progress => cursor_font::WATCH,
+        CursorIcon::NotAllowed => cursor_font::X_CURSOR,
+        CursorIcon::Move | CursorIcon::Grabbing => cursor_font::FLEUR,
+        CursorIcon::Grab => cursor_font::HAND1,
+        CursorIcon::NResize => cursor_font::TOP_SIDE,
+        CursorIcon::EResize => cursor_font::RIGHT_SIDE,
+        CursorIcon::SResize => cursor_font::BOTTOM_SIDE,
+        CursorIcon::WResize => cursor_font::LEFT_SIDE,
+        CursorIcon::NeResize | CursorIcon::NeswResize => cursor_font::TOP_RIGHT_CORNER,
+        CursorIcon::NwResize | CursorIcon::NwseResize => cursor_font::TOP_LEFT_CORNER,
+        CursorIcon::SeResize => cursor_font::BOTTOM_RIGHT_CORNER,
+        CursorIcon::SwResize => cursor_font::BOTTOM_LEFT_CORNER,
+        CursorIcon::EwResize | CursorIcon::ColResize => cursor_font::SB_H_DOUBLE_ARROW,
+        CursorIcon::NsResize | CursorIcon::RowResize => cursor_font::SB_V_DOUBLE_ARROW,
+    }
+}
+
+/// Queues a `ModifiersChanged` event if `modifiers` differ from the `current` ones, which are the
+/// last reported ones. This only borrows the fields it needs, since events borrow the backend's
+/// windows and string buffer while they're processed.
+fn update_modifiers(
+    event_queue: &mut VecDeque<Event>,
+    current: &mut Modifiers,
+    window: WindowHandle,
+    time: Duration,
+    modifiers: Modifiers,
+) {
+    if modifiers != *current {
+        *current = modifiers;
+        event_queue.push_back(Event {
+            time,
+            window,
+            kind: EventKind::ModifiersChanged(modifiers),
+        });
+    }
+}
+
+/// Creates an input context for `window` whose preedit text is drawn by the application, with
+/// callbacks writing it to `preedit`. Falls back to an input context without preedit text if the
+/// input method doesn't support that.
+unsafe fn create_ic(
+    x11: &LibX11,
+    xim: NonNull<_XIM>,
+    window: XWindow,
+    preedit: *mut Preedit,
+) -> Option<NonNull<_XIC>> {
+    let callback = |callback| XICCallback {
+        client_data: preedit as XPointer,
+        callback: Some(callback),
+    };
+    let start = callback(preedit_start);
+    let done = callback(preedit_done);
+    let draw = callback(preedit_draw);
+    let caret = callback(preedit_caret);
+
+    // Xlib copies the callbacks, so they don't need to outlive this function
+    let preedit_attr = (x11.XVaCreateNestedList)(
+        0,
+        xn::PREEDIT_START_CALLBACK,
+        &start,
+        xn::PREEDIT_DONE_CALLBACK,
+        &done,
+        xn::PREEDIT_DRAW_CALLBACK,
+        &draw,
+        xn::PREEDIT_CARET_CALLBACK,
+        &caret,
+        null_mut::<c_void>(),
+    );
+    let xic = (x11.XCreateIC)(
+        xim.as_ptr(),
+        xn::INPUT_STYLE,
+        xim::PREEDIT_CALLBACKS | xim::STATUS_NOTHING,
+        xn::CLIENT_WINDOW,
+        window,
+        xn::PREEDIT_ATTRIBUTES,
+        preedit_attr,
+        null_mut::<c_void>(),
+    );
+    (x11.XFree)(preedit_attr);
+
+    NonNull::new(xic).or_else(|| {
+        let xic = (x11.XCreateIC)(
+            xim.as_ptr(),
+            xn::INPUT_STYLE,
+            xim::PREEDIT_NOTHING | xim::STATUS_NOTHING,
+            xn::CLIENT_WINDOW,
+            window,
+            null_mut::<c_void>(),
+        );
+        NonNull::new(xic)
+    })
+}
+
+/// Destroys the input context of a window, then the preedit text its callbacks wrote to.
+unsafe fn destroy_ic(x11: &LibX11, window: &X11NativeWindow) {
+    (x11.XDestroyIC)(window.xic.as_ptr());
+    drop(Box::from_raw(window.preedit));
+}
+
+unsafe extern "C" fn preedit_start(_ic: XIC, client_data: XPointer, _call_data: XPointer) -> c_int {
+    *(client_data as *mut Preedit) = Preedit {
+        changed: true,
+        ..Default::default()
+    };
+
+    // No limit on the length of the preedit text
+    -1
+}
+
+unsafe extern "C" fn preedit_done(_ic: XIC, client_data: XPointer, _call_data: XPointer) -> c_int {
+    *(client_data as *mut Preedit) = Preedit {
+        changed: true,
+        ..Default::default()
+    };
+    0
+}
+
+unsafe extern "C" fn preedit_draw(_ic: XIC, client_data: XPointer, call_data: XPointer) -> c_int {
+    let preedit = &mut *(client_data as *mut Preedit);
+    let draw = &*(call_data as *const XIMPreeditDrawCallbackStruct);
+
+    let len = preedit.text.len();
+    let first = (draw.chg_first.max(0) as usize).min(len);
+    let end = first
+        .saturating_add(draw.chg_length.max(0) as usize)
+        .min(len);
+    let text = match draw.text.as_ref() {
+        Some(text) => xim_text_chars(text),
+        None => Vec::new(),
+    };
+    preedit.text.splice(first..end, text);
+
+    preedit.caret = (draw.caret.max(0) as usize).min(preedit.text.len());
+    preedit.changed = true;
+    0
+}
+
+unsafe extern "C" fn preedit_caret(_ic: XIC, client_data: XPointer, call_data: XPointer) -> c_int {
+    let preedit = &mut *(client_data as *mut Preedit);
+    let caret = &mut *(call_data as *mut XIMPreeditCaretCallbackStruct);
+
+    let len = preedit.text.len();
+    preedit.caret = match caret.direction {
+        xim_caret::ABSOLUTE_POSITION => (caret.position.max(0) as usize).min(len),
+        xim_caret::FORWARD_CHAR => (preedit.caret + 1).min(len),
+        xim_caret::BACKWARD_CHAR => preedit.caret.saturating_sub(1),
+        xim_caret::LINE_START => 0,
+        xim_caret::LINE_END => len,
+        _ => preedit.caret,
+    };
+    preedit.changed = true;
+
+    // The input method reads the resulting position back
+    caret.position = preedit.caret as c_int;
+    0
+}
+
+/// Decodes the characters of an `XIMText`. Multi-byte text is in the locale's encoding, which is
+/// assumed to be UTF-8.
+unsafe fn xim_text_chars(text: &XIMText) -> Vec<char> {
+    if text.encoding_is_wchar != 0 {
+        let wide_char = text.string.wide_char;
+        if wide_char.is_null() {
+            return Vec::new();
+        }
+        std::slice::from_raw_parts(wide_char, text.length.into())
+            .iter()
+            .filter_map(|&c| char::from_u32(c))
+            .collect()
+    } else {
+        let multi_byte = text.string.multi_byte;
+        if multi_byte.is_null() {
+            return Vec::new();
+        }
+        CStr::from_ptr(multi_byte)
+            .to_string_lossy()
+            .chars()
+            .collect()
+    }
+}
+
 unsafe fn place_ime(x11: &LibX11, xic: NonNull<_XIC>, place: XPoint) {
     let preedit_attr =
         (x11.XVaCreateNestedList)(0, xn::SPOT_LOCATION, &place, null_mut::<c_void>());
@@ -89,6 +89,7 @@ pub fn to_keycode(keysym: u32) -> Option<KeyCode> {
         0xfe03 => KeyCode::RAlt,
 
         0xffeb => KeyCode::LSuper,
+        0xff67 => KeyCode::Menu,
 
         0xff51 => KeyCode::ArrowLeft,
         0xff52 => KeyCode::ArrowUp,
@@ -114,6 +115,20 @@ pub fn to_keycode(keysym: u32) -> Option<KeyCode> {
         0xffc7 => KeyCode::F10,
         0xffc8 => KeyCode::F11,
         0xffc9 => KeyCode::F12,
+        0xffca => KeyCode::F13,
+        0xffcb => KeyCode::F14,
+        0xffcc => KeyCode::F15,
+        0xffcd => KeyCode::F16,
+        0xffce => KeyCode::F17,
+        0xffcf => KeyCode::F18,
+        0xffd0 => KeyCode::F19,
+        0xffd1 => KeyCode::F20,
+        0xffd2 => KeyCode::F21,
+        0xffd3 => KeyCode::F22,
+        0xffd4 => KeyCode::F23,
+        0xffd5 => KeyCode::F24,
+
+        0xff63 => KeyCode::Insert,
 
         0xffff => KeyCode::Delete,
 
@@ -121,8 +136,27 @@ pub fn to_keycode(keysym: u32) -> Option<KeyCode> {
         0x1008ff15 => KeyCode::Stop,
         0x1008ff16 => KeyCode::PrevTrack,
         0x1008ff17 => KeyCode::NextTrack,
+        0x1008ff13 => KeyCode::VolumeUp,
+        0x1008ff11 => KeyCode::VolumeDown,
+        0x1008ff12 => KeyCode::VolumeMute,
+        0x1008ff26 => KeyCode::BrowserBack,
+        0x1008ff27 => KeyCode::BrowserForward,
+        0x1008ff02 => KeyCode::BrightnessUp,
+        0x1008ff03 => KeyCode::BrightnessDown,
 
         0xff61 | 0xff15 => KeyCode::PrintScreen,
+        0xff14 => KeyCode::ScrollLock,
+        0xff13 => KeyCode::Pause,
+
+        // Kana_Lock, Kana_Shift, Hiragana_Katakana
+        0xff2d | 0xff2e | 0xff27 => KeyCode::KanaMode,
+        0xff23 => KeyCode::Henkan,
+        0xff22 => KeyCode::Muhenkan,
+        0xff31 => KeyCode::Hangul,
+        0xff34 => KeyCode::Hanja,
+        // yen, kana_RO. IntlBackslash has no keysym of its own, and neither does Ro without kana
+        0xa5 => KeyCode::IntlYen,
+        0x4db => KeyCode::IntlRo,
 
         _ => return None,
     })
@@ -0,0 +1,203 @@
+//! Smooth scrolling through XInput2, which reports scrolling as valuators rather than as wheel
+//! buttons, so that touchpads and high-resolution wheels scroll by fractions of steps.
+
+use std::ffi::c_int;
+use std::ptr::NonNull;
+
+use loki_linux::x11::xinput2::{
+    mask_is_set, mask_len, set_mask, xi, xi_class, LibXi, XIDeviceEvent, XIEventMask, XIRawEvent,
+    XIScrollClassInfo, XIValuatorClassInfo, EXTENSION_NAME, XI_ALL_MASTER_DEVICES,
+    XI_SCROLL_TYPE_VERTICAL,
+};
+use loki_linux::x11::{LibX11, XDisplay, XWindow};
+
+pub struct XInput2 {
+    xi: LibXi,
+    /// The major opcode of the extension, which its events are tagged with.
+    pub opcode: c_int,
+    /// The physical device whose valuators are in `scroll_valuators`, or `None` if they have to
+    /// be queried again.
+    source: Option<c_int>,
+    scroll_valuators: Vec<ScrollValuator>,
+}
+
+struct ScrollValuator {
+    number: c_int,
+    vertical: bool,
+    /// How much the valuator changes for one wheel step.
+    increment: f64,
+    /// The last value of the valuator, which scrolling is relative to.
+    value: f64,
+}
+
+impl XInput2 {
+    /// Loads XInput2, if the server supports version 2.1, the first one with smooth scrolling.
+    pub unsafe fn new(x11: &LibX11, display: NonNull<XDisplay>) -> Option<Self> {
+        let xi = LibXi::new().ok()?;
+
+        let (mut opcode, mut first_event, mut first_error) = (0, 0, 0);
+        let has_extension = (x11.XQueryExtension)(
+            display.as_ptr(),
+            EXTENSION_NAME.as_ptr() as *const _,
+            &mut opcode,
+            &mut first_event,
+            &mut first_error,
+        );
+        if has_extension == 0 {
+            return None;
+        }
+
+        // The server answers with the version it supports, as long as it's at least 2.0
+        let (mut major, mut minor) = (2, 1);
+        let status = (xi.XIQueryVersion)(display.as_ptr(), &mut major, &mut minor);
+        if status != 0 || (major, minor) < (2, 1) {
+            return None;
+        }
+
+        Some(Self {
+            xi,
+            opcode,
+            source: None,
+            scroll_valuators: Vec::new(),
+        })
+    }
+
+    /// Selects the XInput2 events of a window. They replace the matching core events, which
+    /// aren't sent anymore.
+    pub unsafe fn select_events(&self, display: NonNull<XDisplay>, window: XWindow) {
+        let mut mask = [0; mask_len()];
+        set_mask(&mut mask, xi::MOTION);
+
+        let mut event_mask = XIEventMask {
+            deviceid: XI_ALL_MASTER_DEVICES,
+            mask_len: mask.len() as c_int,
+            mask: mask.as_mut_ptr(),
+        };
+        (self.xi.XISelectEvents)(display.as_ptr(), window, &mut event_mask, 1);
+    }
+
+    /// Starts or stops receiving raw motion events, which are only sent to the root window.
+    pub unsafe fn select_raw_motion(&self, display: NonNull<XDisplay>, root: XWindow, on: bool) {
+        let mut mask = [0; mask_len()];
+        if on {
+            set_mask(&mut mask, xi::RAW_MOTION);
+        }
+
+        let mut event_mask = XIEventMask {
+            deviceid: XI_ALL_MASTER_DEVICES,
+            mask_len: mask.len() as c_int,
+            mask: mask.as_mut_ptr(),
+        };
+        (self.xi.XISelectEvents)(display.as_ptr(), root, &mut event_mask, 1);
+    }
+
+    /// Returns how far a raw motion event moved the pointer, before acceleration.
+    pub unsafe fn raw_delta(event: &XIRawEvent) -> (f64, f64) {
+        let valuators = &event.valuators;
+        let (mut dx, mut dy) = (0.0, 0.0);
+        let mut value_index = 0;
+        // The first two valuators are the X and Y axes
+        for number in 0..2 {
+            if !mask_is_set(valuators.mask, valuators.mask_len, number) {
+                continue;
+            }
+            let value = *event.raw_values.add(value_index);
+            value_index += 1;
+
+            match number {
+                0 => dx = value,
+                _ => dy = value,
+            }
+        }
+
+        (dx, dy)
+    }
+
+    /// Forgets the scroll valuators, since they may have changed while the cursor was away.
+    pub fn reset_scroll(&mut self) {
+        self.source = None;
+    }
+
+    /// Returns how many wheel steps a motion event scrolled, horizontally then vertically, with
+    /// the same signs as [`ScrollDelta`](crate::event::ScrollDelta).
+    pub unsafe fn scroll_delta(
+        &mut self,
+        display: NonNull<XDisplay>,
+        event: &XIDeviceEvent,
+    ) -> (f64, f64) {
+        // The master device takes the valuators of the physical device that drives it
+        if self.source != Some(event.sourceid) {
+            self.query_scroll_valuators(display, event.deviceid);
+            self.source = Some(event.sourceid);
+        }
+
+        let valuators = &event.valuators;
+        let (mut x, mut y) = (0.0, 0.0);
+        let mut value_index = 0;
+        for number in 0..valuators.mask_len * 8 {
+            if !mask_is_set(valuators.mask, valuators.mask_len, number) {
+                continue;
+            }
+            let value = *valuators.values.add(value_index);
+            value_index += 1;
+
+            let Some(valuator) = self
+                .scroll_valuators
+                .iter_mut()
+                .find(|v| v.number == number)
+            else {
+                continue;
+            };
+            let steps = (value - valuator.value) / valuator.increment;
+            valuator.value = value;
+
+            // Valuators grow when scrolling down or right
+            if valuator.vertical {
+                y -= steps;
+            } else {
+                x += steps;
+            }
+        }
+
+        (x, y)
+    }
+
+    unsafe fn query_scroll_valuators(&mut self, display: NonNull<XDisplay>, device: c_int) {
+        self.scroll_valuators.clear();
+
+        let mut n_devices = 0;
+        let info = (self.xi.XIQueryDevice)(display.as_ptr(), device, &mut n_devices);
+        let Some(info) = info.as_ref() else {
+            return;
+        };
+
+        let classes = std::slice::from_raw_parts(info.classes, info.num_classes as usize);
+        for &class in classes {
+            if (*class).type_ == xi_class::SCROLL {
+                let scroll = &*(class as *const XIScrollClassInfo);
+                if scroll.increment != 0.0 {
+                    self.scroll_valuators.push(ScrollValuator {
+                        number: scroll.number,
+                        vertical: scroll.scroll_type == XI_SCROLL_TYPE_VERTICAL,
+                        increment: scroll.increment,
+                        value: 0.0,
+                    });
+                }
+            }
+        }
+
+        // Scrolling is relative to the valuators' current values
+        for &class in classes {
+            if (*class).type_ == xi_class::VALUATOR {
+                let valuator = &*(class as *const XIValuatorClassInfo);
+                for scroll in &mut self.scroll_valuators {
+                    if scroll.number == valuator.number {
+                        scroll.value = valuator.value;
+                    }
+                }
+            }
+        }
+
+        (self.xi.XIFreeDeviceInfo)(info as *const _ as *mut _);
+    }
+}
@@ -1,5 +1,5 @@
 mod event_handler;
-mod keysym;
+pub(crate) mod keysym;
 #[cfg(feature = "opengl")]
 pub mod opengl;
 mod window;
@@ -12,15 +12,18 @@ use {
 };
 use {
     crate::{
+        error::LokinitError,
         event::{Event, EventKind},
+        event_loop::{EventLoopProxy, Interest, TimerId},
         keycode::KeyCode,
-        lok::{CreateWindowError, LokinitBackend},
+        lok::LokinitBackend,
         prelude::Monitor,
         window::{WindowBorder, WindowBuilder, WindowHandle},
     },
     loki_mac::prelude::*,
     std::{
         collections::{HashMap, HashSet, VecDeque},
+        os::fd::RawFd,
         time::Duration,
     },
     window::Window,
@@ -72,6 +75,33 @@ impl MacosBackend {
         self.frontmost_window = Some(new_window_id);
         self.windows.get_mut(&new_window_id).unwrap().focus();
     }
+
+    /// The OpenGL context of a surface created with `create_window_surface`.
+    #[cfg(feature = "opengl")]
+    fn gl_context(&self, surface: WindowSurface) -> Result<&ObjcRc<NSOpenGLContext>, LokinitError> {
+        self.windows
+            .get(&surface.window.0)
+            .and_then(|window| window.gl_context.as_ref())
+            .ok_or(LokinitError::InvalidWindow(surface.window))
+    }
+
+    /// Returns the next queued event, or waits for AppKit to send one until `until`.
+    /// Returns `None` if no event arrived in time.
+    fn wait_event(&mut self, until: NSDate) -> Option<Event> {
+        if let Some(event) = self.event_queue.pop_front() {
+            return Some(event);
+        }
+
+        let raw_event = self.nsapp.next_event(
+            NSEventMask::Any,
+            until,
+            NSRunLoopMode::default(),
+            true.into(),
+        )?;
+        self.handle_raw_event(raw_event);
+
+        self.event_queue.pop_front()
+    }
 }
 
 impl LokinitBackend for MacosBackend {
@@ -93,7 +123,7 @@ impl LokinitBackend for MacosBackend {
         }
     }
 
-    fn create_window(&mut self, builder: WindowBuilder) -> Result<WindowHandle, CreateWindowError> {
+    fn create_window(&mut self, builder: WindowBuilder) -> Result<WindowHandle, LokinitError> {
         let size = NSRect {
             size: NSSize {
                 width: builder.size.width as f64,
@@ -124,47 +154,172 @@ impl LokinitBackend for MacosBackend {
         self.frontmost_window = Some(window_id);
         let old_window = self.windows.insert(window_id, Window::new(window));
         if old_window.is_some() {
-            panic!("Lokinit error: macOS returned two windows with the same ID.");
+            return Err(LokinitError::Protocol(
+                "macOS returned two windows with the same ID".into(),
+            ));
         }
 
         Ok(WindowHandle(window_id))
     }
 
-    fn close_window(&mut self, handle: WindowHandle) {
+    fn close_window(&mut self, handle: WindowHandle) -> Result<(), LokinitError> {
         let window_id = handle.0;
-        let window = self.windows.remove(&window_id).unwrap();
+        let window = self
+            .windows
+            .remove(&window_id)
+            .ok_or(LokinitError::InvalidWindow(handle))?;
         window.close();
 
         if self.frontmost_window == Some(window_id) {
             self.frontmost_window = None;
         }
+        Ok(())
+    }
+
+    fn set_screen_mode(
+        &mut self,
+        _handle: WindowHandle,
+        _screen_mode: crate::window::ScreenMode,
+    ) -> Result<(), LokinitError> {
+        Err(LokinitError::Unsupported("setting the screen mode"))
+    }
+
+    fn set_ime_cursor_area(
+        &mut self,
+        _handle: WindowHandle,
+        _area: crate::window::WindowRect,
+    ) -> Result<(), LokinitError> {
+        Err(LokinitError::Unsupported("input methods"))
+    }
+
+    fn set_ime_allowed(
+        &mut self,
+        _handle: WindowHandle,
+        _allowed: bool,
+    ) -> Result<(), LokinitError> {
+        Err(LokinitError::Unsupported("input methods"))
+    }
+
+    fn set_raw_mouse_motion(
+        &mut self,
+        _handle: WindowHandle,
+        _enabled: bool,
+    ) -> Result<(), LokinitError> {
+        Err(LokinitError::Unsupported("raw mouse motion"))
     }
 
-    fn set_screen_mode(&mut self, _handle: WindowHandle, _screen_mode: crate::window::ScreenMode) {
-        todo!()
+    fn set_cursor_grab(
+        &mut self,
+        _handle: WindowHandle,
+        _grab: crate::window::CursorGrab,
+    ) -> Result<(), LokinitError> {
+        Err(LokinitError::Unsupported("cursor grabs"))
+    }
+
+    fn set_cursor_icon(
+        &mut self,
+        _handle: WindowHandle,
+        _icon: crate::cursor::CursorIcon,
+    ) -> Result<(), LokinitError> {
+        Err(LokinitError::Unsupported("cursor icons"))
     }
 
-    fn poll_event(&mut self) -> Option<Event> {
+    fn set_cursor_visible(
+        &mut self,
+        _handle: WindowHandle,
+        _visible: bool,
+    ) -> Result<(), LokinitError> {
+        Err(LokinitError::Unsupported("cursor visibility"))
+    }
+
+    fn set_cursor_position(
+        &mut self,
+        _handle: WindowHandle,
+        _x: f64,
+        _y: f64,
+    ) -> Result<(), LokinitError> {
+        Err(LokinitError::Unsupported("moving the cursor"))
+    }
+
+    fn create_cursor(
+        &mut self,
+        _frames: &[crate::cursor::CursorFrame],
+    ) -> Result<crate::cursor::CustomCursor, LokinitError> {
+        Err(LokinitError::Unsupported("custom cursors"))
+    }
+
+    fn destroy_cursor(&mut self, cursor: crate::cursor::CustomCursor) -> Result<(), LokinitError> {
+        Err(LokinitError::InvalidCursor(cursor))
+    }
+
+    fn set_custom_cursor(
+        &mut self,
+        _handle: WindowHandle,
+        _cursor: crate::cursor::CustomCursor,
+    ) -> Result<(), LokinitError> {
+        Err(LokinitError::Unsupported("custom cursors"))
+    }
+
+    fn poll_event(&mut self) -> Result<Option<Event>, LokinitError> {
         loop {
-            if let Some(event) = self.event_queue.pop_front() {
-                return Some(event);
+            if let Some(event) = self.wait_event(NSDate::distant_future()) {
+                return Ok(Some(event));
             }
+        }
+    }
 
-            let raw_event = self
-                .nsapp
-                .next_event(
-                    NSEventMask::Any,
-                    NSDate::distant_future(),
-                    NSRunLoopMode::default(),
-                    true.into(),
-                )
-                .unwrap();
+    fn try_poll_event(&mut self) -> Result<Option<Event>, LokinitError> {
+        Ok(self.wait_event(NSDate::distant_past()))
+    }
+
+    fn poll_event_timeout(&mut self, timeout: Duration) -> Result<Option<Event>, LokinitError> {
+        Ok(self.wait_event(NSDate::from_now(timeout.as_secs_f64())))
+    }
+
+    fn dispatch_pending(&mut self) -> Result<(), LokinitError> {
+        while let Some(raw_event) = self.nsapp.next_event(
+            NSEventMask::Any,
+            NSDate::distant_past(),
+            NSRunLoopMode::default(),
+            true.into(),
+        ) {
             self.handle_raw_event(raw_event);
         }
+        Ok(())
+    }
+
+    fn next_queued_event(&mut self) -> Option<Event> {
+        self.event_queue.pop_front()
+    }
+
+    fn connection_fd(&self) -> Result<RawFd, LokinitError> {
+        Err(LokinitError::Unsupported(
+            "getting the connection to the display server",
+        ))
     }
 
-    fn fetch_monitors(&mut self) -> Vec<Monitor> {
-        todo!()
+    fn create_proxy(&mut self) -> Result<EventLoopProxy, LokinitError> {
+        Err(LokinitError::Unsupported("event loop proxies"))
+    }
+
+    fn set_timer(&mut self, _duration: Duration, _repeat: bool) -> Result<TimerId, LokinitError> {
+        Err(LokinitError::Unsupported("timers"))
+    }
+
+    fn cancel_timer(&mut self, _timer: TimerId) -> Result<(), LokinitError> {
+        Err(LokinitError::Unsupported("timers"))
+    }
+
+    fn watch_fd(&mut self, _fd: RawFd, _interest: Interest) -> Result<(), LokinitError> {
+        Err(LokinitError::Unsupported("watching file descriptors"))
+    }
+
+    fn unwatch_fd(&mut self, _fd: RawFd) -> Result<(), LokinitError> {
+        Err(LokinitError::Unsupported("watching file descriptors"))
+    }
+
+    fn fetch_monitors(&mut self) -> Result<Vec<Monitor>, LokinitError> {
+        Err(LokinitError::Unsupported("fetching monitors"))
     }
 
     #[cfg(feature = "opengl")]
@@ -172,8 +327,11 @@ impl LokinitBackend for MacosBackend {
         &mut self,
         window_handle: WindowHandle,
         _cfg: OpenGlConfig,
-    ) -> WindowSurface {
-        let window = self.windows.get_mut(&window_handle.0).unwrap();
+    ) -> Result<WindowSurface, LokinitError> {
+        let window = self
+            .windows
+            .get_mut(&window_handle.0)
+            .ok_or(LokinitError::InvalidWindow(window_handle))?;
         let view = window.content_view();
 
         let mut attrs = Vec::new();
@@ -201,42 +359,35 @@ impl LokinitBackend for MacosBackend {
 
         window.gl_context = Some(context);
 
-        WindowSurface {
+        Ok(WindowSurface {
             window: window_handle,
-        }
+        })
     }
     #[cfg(feature = "opengl")]
     fn load_opengl_func(&mut self, proc_name: *const c_char) -> Option<*mut c_void> {
         self.opengl.load(proc_name)
     }
     #[cfg(feature = "opengl")]
-    fn make_surface_active(&self, surface: WindowSurface) {
-        self.windows
-            .get(&surface.window.0)
-            .unwrap()
-            .gl_context
-            .as_ref()
-            .unwrap()
-            .make_current();
+    fn make_surface_active(
+        &self,
+        _window: WindowHandle,
+        surface: WindowSurface,
+    ) -> Result<(), LokinitError> {
+        self.gl_context(surface)?.make_current();
+        Ok(())
     }
     #[cfg(feature = "opengl")]
-    fn flush_surface(&self, surface: WindowSurface) {
-        self.windows
-            .get(&surface.window.0)
-            .unwrap()
-            .gl_context
-            .as_ref()
-            .unwrap()
-            .flush_buffer();
+    fn flush_surface(
+        &self,
+        _window: WindowHandle,
+        surface: WindowSurface,
+    ) -> Result<(), LokinitError> {
+        self.gl_context(surface)?.flush_buffer();
+        Ok(())
     }
     #[cfg(feature = "opengl")]
-    fn update_surface(&self, surface: WindowSurface) {
-        self.windows
-            .get(&surface.window.0)
-            .unwrap()
-            .gl_context
-            .as_ref()
-            .unwrap()
-            .update();
+    fn update_surface(&self, surface: WindowSurface) -> Result<(), LokinitError> {
+        self.gl_context(surface)?.update();
+        Ok(())
     }
 }
@@ -1,9 +1,21 @@
 use {
     super::{window::Point, *},
-    crate::event::*,
+    crate::{
+        event::*,
+        keycode::{KeyCode, ScanCode},
+    },
 };
 
 impl MacosBackend {
+    /// The modifiers held right now, according to the modifier keys we saw.
+    fn modifiers(&self) -> Modifiers {
+        self.active_modifiers
+            .iter()
+            .fold(Modifiers::empty(), |modifiers, key| {
+                modifiers | Modifiers::for_key(*key)
+            })
+    }
+
     pub fn handle_raw_event(&mut self, raw_event: NSEvent) {
         let event_window = WindowHandle(raw_event.window_number());
         match raw_event.event_type() {
@@ -65,7 +77,7 @@ impl MacosBackend {
                     // TODO: Time
                     time: Duration::ZERO,
                     window: event_window,
-                    kind: EventKind::Mouse(MouseEvent::CursorMove(x as i32, y as i32)),
+                    kind: EventKind::Mouse(MouseEvent::CursorMove(x, y)),
                 });
             }
             NSEventType::MouseEntered => {
@@ -74,7 +86,7 @@ impl MacosBackend {
                     // TODO: Time
                     time: Duration::ZERO,
                     window: event_window,
-                    kind: EventKind::Mouse(MouseEvent::CursorIn(x as i32, y as i32)),
+                    kind: EventKind::Mouse(MouseEvent::CursorIn(x, y)),
                 });
             }
             NSEventType::MouseExited => {
@@ -83,7 +95,7 @@ impl MacosBackend {
                     // TODO: Time
                     time: Duration::ZERO,
                     window: event_window,
-                    kind: EventKind::Mouse(MouseEvent::CursorOut(x as i32, y as i32)),
+                    kind: EventKind::Mouse(MouseEvent::CursorOut(x, y)),
                 });
             }
 
@@ -104,8 +116,9 @@ impl MacosBackend {
                     window: event_window,
                     kind: EventKind::Mouse(MouseEvent::ButtonPress(
                         MouseButton::Left,
-                        mouse_loc.x as i32,
-                        mouse_loc.y as i32,
+                        mouse_loc.x,
+                        mouse_loc.y,
+                        self.modifiers(),
                     )),
                 });
             }
@@ -118,8 +131,9 @@ impl MacosBackend {
                     window: event_window,
                     kind: EventKind::Mouse(MouseEvent::ButtonRelease(
                         MouseButton::Left,
-                        x as i32,
-                        y as i32,
+                        x,
+                        y,
+                        self.modifiers(),
                     )),
                 });
             }
@@ -131,8 +145,9 @@ impl MacosBackend {
                     window: event_window,
                     kind: EventKind::Mouse(MouseEvent::ButtonPress(
                         MouseButton::Right,
-                        x as i32,
-                        y as i32,
+                        x,
+                        y,
+                        self.modifiers(),
                     )),
                 });
             }
@@ -144,52 +159,62 @@ impl MacosBackend {
                     window: event_window,
                     kind: EventKind::Mouse(MouseEvent::ButtonRelease(
                         MouseButton::Right,
-                        x as i32,
-                        y as i32,
+                        x,
+                        y,
+                        self.modifiers(),
                     )),
                 });
             }
             NSEventType::OtherMouseDown => {
                 let NSPoint { x, y } = self.windows.get(&event_window.0).unwrap().mouse_location();
                 let button_number = raw_event.mouse_button_number();
-                let button = if button_number == 2 {
-                    MouseButton::Middle
-                } else {
-                    MouseButton::Other(button_number as _)
+                let button = match button_number {
+                    2 => MouseButton::Middle,
+                    3 => MouseButton::Back,
+                    4 => MouseButton::Forward,
+                    _ => MouseButton::Other(button_number as _),
                 };
 
                 self.event_queue.push_back(Event {
                     // TODO: Time
                     time: Duration::ZERO,
                     window: event_window,
-                    kind: EventKind::Mouse(MouseEvent::ButtonPress(button, x as i32, y as i32)),
+                    kind: EventKind::Mouse(MouseEvent::ButtonPress(button, x, y, self.modifiers())),
                 });
             }
             NSEventType::OtherMouseUp => {
                 let NSPoint { x, y } = self.windows.get(&event_window.0).unwrap().mouse_location();
                 let button_number = raw_event.mouse_button_number();
-                let button = if button_number == 2 {
-                    MouseButton::Middle
-                } else {
-                    MouseButton::Other(button_number as _)
+                let button = match button_number {
+                    2 => MouseButton::Middle,
+                    3 => MouseButton::Back,
+                    4 => MouseButton::Forward,
+                    _ => MouseButton::Other(button_number as _),
                 };
 
                 self.event_queue.push_back(Event {
                     // TODO: Time
                     time: Duration::ZERO,
                     window: event_window,
-                    kind: EventKind::Mouse(MouseEvent::ButtonRelease(button, x as i32, y as i32)),
+                    kind: EventKind::Mouse(MouseEvent::ButtonRelease(
+                        button,
+                        x,
+                        y,
+                        self.modifiers(),
+                    )),
                 });
             }
 
             NSEventType::KeyDown => {
-                let key = super::keysym::to_keycode(raw_event.key_code()).unwrap();
+                let key = super::keysym::to_keycode(raw_event.key_code())
+                    .unwrap_or(KeyCode::Unidentified(raw_event.key_code()));
+                let scancode = ScanCode(raw_event.key_code());
                 let repeat: bool = raw_event.is_repeat().into();
 
                 let event_type = if repeat {
-                    KeyboardEvent::KeyRepeat(key)
+                    KeyboardEvent::KeyRepeat(key, scancode, self.modifiers())
                 } else {
-                    KeyboardEvent::KeyPress(key)
+                    KeyboardEvent::KeyPress(key, scancode, self.modifiers())
                 };
                 self.event_queue.push_back(Event {
                     // TODO: Time
@@ -199,13 +224,19 @@ impl MacosBackend {
                 });
             }
             NSEventType::KeyUp => {
-                let key = super::keysym::to_keycode(raw_event.key_code()).unwrap();
+                let key = super::keysym::to_keycode(raw_event.key_code())
+                    .unwrap_or(KeyCode::Unidentified(raw_event.key_code()));
+                let scancode = ScanCode(raw_event.key_code());
 
                 self.event_queue.push_back(Event {
                     // TODO: Time
                     time: Duration::ZERO,
                     window: event_window,
-                    kind: EventKind::Keyboard(KeyboardEvent::KeyRelease(key)),
+                    kind: EventKind::Keyboard(KeyboardEvent::KeyRelease(
+                        key,
+                        scancode,
+                        self.modifiers(),
+                    )),
                 });
             }
             NSEventType::FlagsChanged => {
@@ -216,25 +247,29 @@ impl MacosBackend {
                     );
                     return;
                 };
-                match self.active_modifiers.contains(&keycode) {
-                    true => {
-                        self.active_modifiers.remove(&keycode);
-                        self.event_queue.push_back(Event {
-                            // TODO: Time
-                            time: Duration::ZERO,
-                            window: event_window,
-                            kind: EventKind::Keyboard(KeyboardEvent::KeyRelease(keycode)),
-                        });
-                    }
-                    false => {
-                        self.active_modifiers.insert(keycode);
-                        self.event_queue.push_back(Event {
-                            // TODO: Time
-                            time: Duration::ZERO,
-                            window: event_window,
-                            kind: EventKind::Keyboard(KeyboardEvent::KeyPress(keycode)),
-                        });
-                    }
+                let scancode = ScanCode(raw_event.key_code());
+                let modifiers = self.modifiers();
+                let kind = if self.active_modifiers.remove(&keycode) {
+                    KeyboardEvent::KeyRelease(keycode, scancode, modifiers)
+                } else {
+                    self.active_modifiers.insert(keycode);
+                    KeyboardEvent::KeyPress(keycode, scancode, modifiers)
+                };
+                self.event_queue.push_back(Event {
+                    // TODO: Time
+                    time: Duration::ZERO,
+                    window: event_window,
+                    kind: EventKind::Keyboard(kind),
+                });
+
+                let new_modifiers = self.modifiers();
+                if new_modifiers != modifiers {
+                    self.event_queue.push_back(Event {
+                        // TODO: Time
+                        time: Duration::ZERO,
+                        window: event_window,
+                        kind: EventKind::ModifiersChanged(new_modifiers),
+                    });
                 }
             }
 
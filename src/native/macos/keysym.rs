@@ -15,7 +15,7 @@ pub fn to_keycode(keysym: u32) -> Option<KeyCode> {
         0x07 => KeyCode::X,
         0x08 => KeyCode::C,
         0x09 => KeyCode::V,
-        // 0x0A => ISO section?
+        0x0A => KeyCode::IntlBackslash,
         0x0B => KeyCode::B,
         0x0C => KeyCode::Q,
         0x0D => KeyCode::W,
@@ -69,7 +69,7 @@ pub fn to_keycode(keysym: u32) -> Option<KeyCode> {
         0x3D => KeyCode::RAlt,
         0x3E => KeyCode::RCtrl,
         // 0x3F => nil
-        // 0x40 => F17
+        0x40 => KeyCode::F17,
         0x41 => KeyCode::NumpadDecimal,
         // 0x42 => nil
         0x43 => KeyCode::NumpadMultiply,
@@ -77,15 +77,15 @@ pub fn to_keycode(keysym: u32) -> Option<KeyCode> {
         0x45 => KeyCode::NumpadAdd,
         // 0x46 => nil
         0x47 => KeyCode::NumLock,
-        // 0x48 => volume up
-        // 0x49 => volume down
-        // 0x4A => mute volume
+        0x48 => KeyCode::VolumeUp,
+        0x49 => KeyCode::VolumeDown,
+        0x4A => KeyCode::VolumeMute,
         0x4B => KeyCode::NumpadDivide,
         0x4C => KeyCode::NumpadEnter,
         // 0x4D => nil
         0x4E => KeyCode::NumpadSubtract,
-        // 0x4F => F18
-        // 0x50 => F19
+        0x4F => KeyCode::F18,
+        0x50 => KeyCode::F19,
         0x51 => KeyCode::NumpadEquals,
         0x52 => KeyCode::Numpad0,
         0x53 => KeyCode::Numpad1,
@@ -95,11 +95,11 @@ pub fn to_keycode(keysym: u32) -> Option<KeyCode> {
         0x57 => KeyCode::Numpad5,
         0x58 => KeyCode::Numpad6,
         0x59 => KeyCode::Numpad7,
-        // 0x5A => F20
+        0x5A => KeyCode::F20,
         0x5B => KeyCode::Numpad8,
         0x5C => KeyCode::Numpad9,
-        // 0x5D => JIS yen
-        // 0x5E => JIS underscore
+        0x5D => KeyCode::IntlYen,
+        0x5E => KeyCode::IntlRo,
         0x5F => KeyCode::NumpadComma,
         0x60 => KeyCode::F5,
         0x61 => KeyCode::F6,
@@ -109,17 +109,17 @@ pub fn to_keycode(keysym: u32) -> Option<KeyCode> {
         0x65 => KeyCode::F9,
         // 0x66 => JIS eisu
         0x67 => KeyCode::F11,
-        // 0x68 => JIS kana
-        // 0x69 => F13
-        // 0x6A => F16
-        // 0x6B => F14
+        0x68 => KeyCode::KanaMode,
+        0x69 => KeyCode::F13,
+        0x6A => KeyCode::F16,
+        0x6B => KeyCode::F14,
         // 0x6C => nil
         0x6D => KeyCode::F10,
-        // 0x6E => context menu?
+        0x6E => KeyCode::Menu,
         0x6F => KeyCode::F12,
         // 0x70 => nil
-        // 0x71 => F15
-        // 0x72 => help or insert?
+        0x71 => KeyCode::F15,
+        0x72 => KeyCode::Insert,
         0x73 => KeyCode::Home,
         0x74 => KeyCode::PageUp,
         0x75 => KeyCode::Delete,
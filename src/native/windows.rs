@@ -6,7 +6,9 @@ use std::{
 
 use crate::{
     event::{Event, EventKind},
-    lok::{CreateWindowError, LokinitBackend, Monitor},
+    event_loop::{EventLoopProxy, TimerId},
+    error::LokinitError,
+    lok::{LokinitBackend, Monitor},
     window::{ScreenMode, WindowBuilder, WindowHandle, WindowBorder},
 };
 
@@ -46,6 +48,79 @@ impl TryFrom<isize> for WindowBorder {
 
 }
 
+impl WindowsBackend {
+    /// Blocks until the next event, or returns `None` once every window has been closed.
+    fn next_event(&mut self) -> Option<Event> {
+        loop {
+            let n_events = n_events();
+            if n_events != 0 {
+                // println!("Events in poll_event: {}", n_events);
+            }
+            if let Some(event) = recv_event() {
+                return Some(event);
+            }
+
+            unsafe {
+                let mut msg: MSG = std::mem::zeroed();
+                if PeekMessageW(&mut msg, NULL as _, 0, 0, PM_REMOVE) != 0 {
+                    let window = WindowHandle(msg.hwnd as usize);
+                    //println!("peeking message");
+
+                    match msg.message {
+                        WM_QUIT => {
+                            println!("quitting");
+                            return Some(Event {
+                                time: Duration::from_millis(1),
+                                window,
+                                kind: EventKind::CloseRequested,
+                            });
+                        }
+                        WM_NCLBUTTONDOWN => {
+                            self.window_resize_direction = WindowBorder::try_from(std::mem::transmute::<usize, isize>(msg.wParam)).ok();
+                            println!("{:?}", self.window_resize_direction);
+                            SetCapture(msg.hwnd);
+                        }
+                        WM_LBUTTONUP => {
+                            self.window_resize_direction = None;
+                            ReleaseCapture();
+                        }
+                        WM_MOUSEMOVE => {
+                            if self.window_resize_direction != None {
+                                let mut window_rect = RECT{left:0, right:0, top:0, bottom:0};
+                                GetWindowRect(msg.hwnd, &window_rect as *const _ as _); 
+                                println!("left:{0}, right:{1}, top: {2}, bottom:{3}", window_rect.left, window_rect.right, window_rect.top, window_rect.bottom);
+                                match self.window_resize_direction.unwrap() {
+                                    WindowBorder::Top => {window_rect.top += (GET_Y_LPARAM(msg.lParam) + self.window_border_size +self.window_title_size)},
+                                    WindowBorder::Bottom => {window_rect.bottom = (GET_Y_LPARAM(msg.lParam)+window_rect.top+2*self.window_border_size+self.window_title_size)},
+                                    WindowBorder::Left => {window_rect.left += (GET_X_LPARAM(msg.lParam)+self.window_border_size)},
+                                    WindowBorder::Right => {window_rect.right = (GET_X_LPARAM(msg.lParam)+window_rect.left+2*self.window_border_size)},
+                                    WindowBorder::TopLeft => todo!(),
+                                    WindowBorder::TopRight => todo!(),
+                                    WindowBorder::BottomLeft => todo!(),
+                                    WindowBorder::BottomRight => todo!(),
+                                }
+                                println!("left:{0}, right:{1}, top: {2}, bottom:{3}", window_rect.left, window_rect.right, window_rect.top, window_rect.bottom);
+                                SetWindowPos(msg.hwnd, NULL as _, window_rect.left, window_rect.top, (window_rect.right-window_rect.left), (window_rect.bottom - window_rect.top), NULL as _);
+                            }
+                        }
+                        _ => {}
+                    }
+
+                    TranslateMessage(&mut msg as *mut _ as _);
+                    //DispatchMessageW(&mut msg as *mut _ as _);
+                    
+                }
+            }
+
+            // println!("Remaining windows: {}", self.window_handles.len());
+            if self.window_handles.is_empty() {
+                // We quit once all windows have quit
+                return None;
+            }
+        }
+    }
+}
+
 impl LokinitBackend for WindowsBackend {
     fn init() -> Self {
         Self {  
@@ -55,7 +130,7 @@ impl LokinitBackend for WindowsBackend {
             window_title_size: unsafe { GetSystemMetrics(SM_CYCAPTION) } }
     }
 
-    fn create_window(&mut self, builder: WindowBuilder) -> Result<WindowHandle, CreateWindowError> {
+    fn create_window(&mut self, builder: WindowBuilder) -> Result<WindowHandle, LokinitError> {
         let class_name = "LOKINIT\0".encode_utf16().collect::<Vec<u16>>();
         let mut window_name = builder.title.encode_utf16().collect::<Vec<u16>>();
         window_name.push(0);
@@ -127,84 +202,49 @@ impl LokinitBackend for WindowsBackend {
         }
     }
 
-    fn poll_event(&mut self) -> Option<Event> {
-        loop {
-            let n_events = n_events();
-            if n_events != 0 {
-                // println!("Events in poll_event: {}", n_events);
-            }
-            if let Some(event) = recv_event() {
-                return Some(event);
-            }
+    fn poll_event(&mut self) -> Result<Option<Event>, LokinitError> {
+        Ok(self.next_event())
+    }
 
-            unsafe {
-                let mut msg: MSG = std::mem::zeroed();
-                if PeekMessageW(&mut msg, NULL as _, 0, 0, PM_REMOVE) != 0 {
-                    let window = WindowHandle(msg.hwnd as usize);
-                    //println!("peeking message");
+    fn try_poll_event(&mut self) -> Result<Option<Event>, LokinitError> {
+        Err(LokinitError::Unsupported("polling events without blocking"))
+    }
 
-                    match msg.message {
-                        WM_QUIT => {
-                            println!("quitting");
-                            return Some(Event {
-                                time: Duration::from_millis(1),
-                                window,
-                                kind: EventKind::CloseRequested,
-                            });
-                        }
-                        WM_NCLBUTTONDOWN => {
-                            self.window_resize_direction = WindowBorder::try_from(std::mem::transmute::<usize, isize>(msg.wParam)).ok();
-                            println!("{:?}", self.window_resize_direction);
-                            SetCapture(msg.hwnd);
-                        }
-                        WM_LBUTTONUP => {
-                            self.window_resize_direction = None;
-                            ReleaseCapture();
-                        }
-                        WM_MOUSEMOVE => {
-                            if self.window_resize_direction != None {
-                                let mut window_rect = RECT{left:0, right:0, top:0, bottom:0};
-                                GetWindowRect(msg.hwnd, &window_rect as *const _ as _); 
-                                println!("left:{0}, right:{1}, top: {2}, bottom:{3}", window_rect.left, window_rect.right, window_rect.top, window_rect.bottom);
-                                match self.window_resize_direction.unwrap() {
-                                    WindowBorder::Top => {window_rect.top += (GET_Y_LPARAM(msg.lParam) + self.window_border_size +self.window_title_size)},
-                                    WindowBorder::Bottom => {window_rect.bottom = (GET_Y_LPARAM(msg.lParam)+window_rect.top+2*self.window_border_size+self.window_title_size)},
-                                    WindowBorder::Left => {window_rect.left += (GET_X_LPARAM(msg.lParam)+self.window_border_size)},
-                                    WindowBorder::Right => {window_rect.right = (GET_X_LPARAM(msg.lParam)+window_rect.left+2*self.window_border_size)},
-                                    WindowBorder::TopLeft => todo!(),
-                                    WindowBorder::TopRight => todo!(),
-                                    WindowBorder::BottomLeft => todo!(),
-                                    WindowBorder::BottomRight => todo!(),
-                                }
-                                println!("left:{0}, right:{1}, top: {2}, bottom:{3}", window_rect.left, window_rect.right, window_rect.top, window_rect.bottom);
-                                SetWindowPos(msg.hwnd, NULL as _, window_rect.left, window_rect.top, (window_rect.right-window_rect.left), (window_rect.bottom - window_rect.top), NULL as _);
-                            }
-                        }
-                        _ => {}
-                    }
+    fn poll_event_timeout(&mut self, _timeout: Duration) -> Result<Option<Event>, LokinitError> {
+        Err(LokinitError::Unsupported("polling events with a timeout"))
+    }
 
-                    TranslateMessage(&mut msg as *mut _ as _);
-                    //DispatchMessageW(&mut msg as *mut _ as _);
-                    
-                }
-            }
+    fn dispatch_pending(&mut self) -> Result<(), LokinitError> {
+        Err(LokinitError::Unsupported("dispatching pending events"))
+    }
 
-            // println!("Remaining windows: {}", self.window_handles.len());
-            if self.window_handles.is_empty() {
-                // We quit once all windows have quit
-                return None;
-            }
-        }
+    fn next_queued_event(&mut self) -> Option<Event> {
+        recv_event()
     }
 
-    fn close_window(&mut self, handle: WindowHandle) {
-        self.window_handles.remove(&handle);
+    fn create_proxy(&mut self) -> Result<EventLoopProxy, LokinitError> {
+        Err(LokinitError::Unsupported("event loop proxies"))
+    }
+
+    fn set_timer(&mut self, _duration: Duration, _repeat: bool) -> Result<TimerId, LokinitError> {
+        Err(LokinitError::Unsupported("timers"))
+    }
+
+    fn cancel_timer(&mut self, _timer: TimerId) -> Result<(), LokinitError> {
+        Err(LokinitError::Unsupported("timers"))
+    }
+
+    fn close_window(&mut self, handle: WindowHandle) -> Result<(), LokinitError> {
+        if !self.window_handles.remove(&handle) {
+            return Err(LokinitError::InvalidWindow(handle));
+        }
         println!("Remaining windows: {}", self.window_handles.len());
         unsafe { DestroyWindow(handle.0 as _) };
+        Ok(())
     }
 
-    fn fetch_monitors(&mut self) -> Vec<Monitor> {
-        todo!()
+    fn fetch_monitors(&mut self) -> Result<Vec<Monitor>, LokinitError> {
+        Err(LokinitError::Unsupported("fetching monitors"))
     }
 }
 
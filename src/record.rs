@@ -0,0 +1,768 @@
+//! Recording events to a trace, and replaying them.
+//!
+//! Traces are line-based text files. The first line is a header, and every other line holds one
+//! event: its time in nanoseconds, its window (`none` for [`WindowHandle::NONE`]), then its kind,
+//! with the same names as the [`EventKind`] variants. For example:
+//!
+//! ```text
+//! lokinit-trace 1
+//! 1250000000 4194307 Resized 800 600
+//! 1252000000 4194307 Keyboard KeyPress LShift 42 -
+//! 1252000000 4194307 ModifiersChanged SHIFT
+//! 1253000000 4194307 Keyboard KeyPress A 30 SHIFT
+//! 1253000000 4194307 Keyboard ImeCommit "A"
+//! 1254000000 4194307 Keyboard ImePreedit "にほ" 6 6
+//! 1260000000 4194307 Mouse ButtonPress Left 12.5 34 SHIFT
+//! 1261000000 4194307 Mouse Scroll Pixels 0 -12.5 Moved false
+//! 0 none Timer 0
+//! ```
+//!
+//! Key events hold the key code's name, then the raw [`ScanCode`]. Preedit events hold their
+//! text, then their cursor's byte range, or `-` when it's hidden. Modifiers are separated by `|`,
+//! and written as `-` when there are none. Strings are quoted, with `\\`, `\"`, `\n`, `\r` and
+//! `\t` escapes. Empty lines and lines starting with `#` are ignored, so traces can be annotated
+//! by hand.
+
+use std::error::Error;
+use std::fmt::{self, Write as _};
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::error::LokinitError;
+use crate::event::{
+    Event, EventKind, KeyboardEvent, Modifiers, MouseButton, MouseEvent, ScrollDelta, ScrollEvent,
+    TouchEvent, TouchPhase,
+};
+#[cfg(unix)]
+use crate::event_loop::Interest;
+use crate::event_loop::TimerId;
+use crate::keycode::ScanCode;
+use crate::window::WindowHandle;
+
+const HEADER: &str = "lokinit-trace 1";
+
+/// Writes events to a trace.
+pub struct Recorder<W: Write> {
+    writer: W,
+    /// The first error we ran into. Nothing gets written after it.
+    error: Option<io::Error>,
+}
+
+impl<W: Write> Recorder<W> {
+    /// Creates a recorder, and writes the trace's header.
+    pub fn new(mut writer: W) -> io::Result<Self> {
+        writeln!(writer, "{HEADER}")?;
+        writer.flush()?;
+
+        Ok(Self {
+            writer,
+            error: None,
+        })
+    }
+
+    /// Writes an event to the trace. The trace is flushed right away, so that it is complete even
+    /// if the app crashes.
+    ///
+    /// If writing fails, the recorder stops, and [`Recorder::finish`] returns the error.
+    pub fn record(&mut self, event: &Event) {
+        if self.error.is_some() {
+            return;
+        }
+
+        let line = format_event(event);
+        let result = writeln!(self.writer, "{line}").and_then(|_| self.writer.flush());
+        if let Err(error) = result {
+            self.error = Some(error);
+        }
+    }
+
+    /// Stops recording, and returns the first error the recorder ran into, if any.
+    pub fn finish(self) -> io::Result<W> {
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok(self.writer),
+        }
+    }
+}
+
+/// Reads events back from a trace, in order.
+pub struct Replay<R: BufRead> {
+    lines: io::Lines<R>,
+    line_number: usize,
+}
+
+impl<R: BufRead> Replay<R> {
+    /// Starts reading a trace, checking its header.
+    pub fn new(reader: R) -> Result<Self, ReplayError> {
+        let mut lines = reader.lines();
+
+        match lines.next().transpose()? {
+            Some(header) if header.trim_end() == HEADER => Ok(Self {
+                lines,
+                line_number: 1,
+            }),
+            _ => Err(ReplayError::Parse {
+                line: 1,
+                message: format!("expected the `{HEADER}` header"),
+            }),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for Replay<R> {
+    type Item = Result<Event, ReplayError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = match self.lines.next()? {
+                Ok(line) => line,
+                Err(error) => return Some(Err(error.into())),
+            };
+            self.line_number += 1;
+
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            return Some(parse_event(line).map_err(|message| ReplayError::Parse {
+                line: self.line_number,
+                message,
+            }));
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(io::Error),
+    /// The events couldn't be fed to the backend.
+    Lokinit(LokinitError),
+    /// A line of the trace is invalid. Lines are numbered from 1.
+    Parse {
+        line: usize,
+        message: String,
+    },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "Failed to read the trace: {error}"),
+            Self::Lokinit(error) => write!(f, "Failed to replay the trace: {error}"),
+            Self::Parse { line, message } => write!(f, "Invalid trace at line {line}: {message}"),
+        }
+    }
+}
+
+impl Error for ReplayError {}
+
+impl From<io::Error> for ReplayError {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<LokinitError> for ReplayError {
+    fn from(value: LokinitError) -> Self {
+        Self::Lokinit(value)
+    }
+}
+
+/// Formats an event as a line of a trace, without the line break.
+pub fn format_event(event: &Event) -> String {
+    let mut line = event.time.as_nanos().to_string();
+
+    if event.window == WindowHandle::NONE {
+        line.push_str(" none");
+    } else {
+        let _ = write!(line, " {}", event.window.0);
+    }
+
+    let _ = match &event.kind {
+        EventKind::Resized(width, height) => write!(line, " Resized {width} {height}"),
+        EventKind::Moved(x, y) => write!(line, " Moved {x} {y}"),
+        EventKind::CloseRequested => write!(line, " CloseRequested"),
+        EventKind::Destroyed => write!(line, " Destroyed"),
+        EventKind::FileDropped(path) => write!(line, " FileDropped {}", quote_path(path)),
+        EventKind::FileHovered(path) => write!(line, " FileHovered {}", quote_path(path)),
+        EventKind::FileHoveredCancelled(path) => {
+            write!(line, " FileHoveredCancelled {}", quote_path(path))
+        }
+        EventKind::Keyboard(event) => match event {
+            KeyboardEvent::KeyPress(key, scancode, modifiers) => {
+                let (scancode, modifiers) = (scancode.0, format_modifiers(*modifiers));
+                write!(line, " Keyboard KeyPress {key:?} {scancode} {modifiers}")
+            }
+            KeyboardEvent::KeyRelease(key, scancode, modifiers) => {
+                let (scancode, modifiers) = (scancode.0, format_modifiers(*modifiers));
+                write!(line, " Keyboard KeyRelease {key:?} {scancode} {modifiers}")
+            }
+            KeyboardEvent::KeyRepeat(key, scancode, modifiers) => {
+                let (scancode, modifiers) = (scancode.0, format_modifiers(*modifiers));
+                write!(line, " Keyboard KeyRepeat {key:?} {scancode} {modifiers}")
+            }
+            KeyboardEvent::ImeCommit(text) => write!(line, " Keyboard ImeCommit {}", quote(text)),
+            KeyboardEvent::ImePreedit { text, cursor_range } => {
+                let cursor_range = match cursor_range {
+                    Some((start, end)) => format!("{start} {end}"),
+                    None => "-".to_owned(),
+                };
+                write!(line, " Keyboard ImePreedit {} {cursor_range}", quote(text))
+            }
+        },
+        EventKind::Mouse(event) => match event {
+            MouseEvent::ButtonPress(button, x, y, modifiers) => {
+                let button = format_button(*button);
+                let modifiers = format_modifiers(*modifiers);
+                write!(line, " Mouse ButtonPress {button} {x} {y} {modifiers}")
+            }
+            MouseEvent::ButtonRelease(button, x, y, modifiers) => {
+                let button = format_button(*button);
+                let modifiers = format_modifiers(*modifiers);
+                write!(line, " Mouse ButtonRelease {button} {x} {y} {modifiers}")
+            }
+            MouseEvent::CursorMove(x, y) => write!(line, " Mouse CursorMove {x} {y}"),
+            MouseEvent::CursorIn(x, y) => write!(line, " Mouse CursorIn {x} {y}"),
+            MouseEvent::CursorOut(x, y) => write!(line, " Mouse CursorOut {x} {y}"),
+            MouseEvent::Scroll(ScrollEvent {
+                delta,
+                phase,
+                inverted,
+            }) => {
+                let delta = match delta {
+                    ScrollDelta::Lines(x, y) => format!("Lines {x} {y}"),
+                    ScrollDelta::Pixels(x, y) => format!("Pixels {x} {y}"),
+                };
+                write!(line, " Mouse Scroll {delta} {phase:?} {inverted}")
+            }
+        },
+        EventKind::ModifiersChanged(modifiers) => {
+            write!(line, " ModifiersChanged {}", format_modifiers(*modifiers))
+        }
+        EventKind::RawMouseMotion { dx, dy } => write!(line, " RawMouseMotion {dx} {dy}"),
+        EventKind::Touch(event) => {
+            write!(line, " Touch {:?} {} {}", event.phase, event.x, event.y)
+        }
+        EventKind::Redraw => write!(line, " Redraw"),
+        EventKind::FocusIn => write!(line, " FocusIn"),
+        EventKind::FocusOut => write!(line, " FocusOut"),
+        EventKind::User(payload) => write!(line, " User {payload}"),
+        EventKind::Timer(timer) => write!(line, " Timer {}", timer.0),
+        #[cfg(unix)]
+        EventKind::FdReady(fd, interest) => {
+            let readable = if interest.is_readable() { "r" } else { "" };
+            let writable = if interest.is_writable() { "w" } else { "" };
+            write!(line, " FdReady {fd} {readable}{writable}")
+        }
+    };
+
+    line
+}
+
+/// Parses a line of a trace, as formatted by [`format_event`].
+pub fn parse_event(line: &str) -> Result<Event, String> {
+    let mut tokens = Tokens::new(line);
+
+    let time = Duration::from_nanos(tokens.parse("time")?);
+    let window = match tokens.next_word("window")? {
+        "none" => WindowHandle::NONE,
+        window => WindowHandle(parse_word(window, "window")?),
+    };
+
+    let kind = match tokens.next_word("event kind")? {
+        "Resized" => EventKind::Resized(tokens.parse("width")?, tokens.parse("height")?),
+        "Moved" => EventKind::Moved(tokens.parse("x")?, tokens.parse("y")?),
+        "CloseRequested" => EventKind::CloseRequested,
+        "Destroyed" => EventKind::Destroyed,
+        "FileDropped" => EventKind::FileDropped(PathBuf::from(tokens.next_string()?)),
+        "FileHovered" => EventKind::FileHovered(PathBuf::from(tokens.next_string()?)),
+        "FileHoveredCancelled" => {
+            EventKind::FileHoveredCancelled(PathBuf::from(tokens.next_string()?))
+        }
+        "Keyboard" => EventKind::Keyboard(match tokens.next_word("keyboard event")? {
+            "KeyPress" => KeyboardEvent::KeyPress(
+                tokens.parse("key code")?,
+                ScanCode(tokens.parse("scancode")?),
+                parse_modifiers(tokens.next_word("modifiers")?)?,
+            ),
+            "KeyRelease" => KeyboardEvent::KeyRelease(
+                tokens.parse("key code")?,
+                ScanCode(tokens.parse("scancode")?),
+                parse_modifiers(tokens.next_word("modifiers")?)?,
+            ),
+            "KeyRepeat" => KeyboardEvent::KeyRepeat(
+                tokens.parse("key code")?,
+                ScanCode(tokens.parse("scancode")?),
+                parse_modifiers(tokens.next_word("modifiers")?)?,
+            ),
+            "ImeCommit" => KeyboardEvent::ImeCommit(tokens.next_string()?),
+            "ImePreedit" => KeyboardEvent::ImePreedit {
+                text: tokens.next_string()?,
+                cursor_range: match tokens.next_word("cursor start")? {
+                    "-" => None,
+                    start => Some((
+                        parse_word(start, "cursor start")?,
+                        tokens.parse("cursor end")?,
+                    )),
+                },
+            },
+            other => return Err(format!("unknown keyboard event `{other}`")),
+        }),
+        "Mouse" => EventKind::Mouse(match tokens.next_word("mouse event")? {
+            "ButtonPress" => MouseEvent::ButtonPress(
+                parse_button(tokens.next_word("mouse button")?)?,
+                tokens.parse("x")?,
+                tokens.parse("y")?,
+                parse_modifiers(tokens.next_word("modifiers")?)?,
+            ),
+            "ButtonRelease" => MouseEvent::ButtonRelease(
+                parse_button(tokens.next_word("mouse button")?)?,
+                tokens.parse("x")?,
+                tokens.parse("y")?,
+                parse_modifiers(tokens.next_word("modifiers")?)?,
+            ),
+            "CursorMove" => MouseEvent::CursorMove(tokens.parse("x")?, tokens.parse("y")?),
+            "CursorIn" => MouseEvent::CursorIn(tokens.parse("x")?, tokens.parse("y")?),
+            "CursorOut" => MouseEvent::CursorOut(tokens.parse("x")?, tokens.parse("y")?),
+            "Scroll" => MouseEvent::Scroll(ScrollEvent {
+                delta: match tokens.next_word("scroll delta")? {
+                    "Lines" => ScrollDelta::Lines(tokens.parse("x")?, tokens.parse("y")?),
+                    "Pixels" => ScrollDelta::Pixels(tokens.parse("x")?, tokens.parse("y")?),
+                    other => return Err(format!("unknown scroll delta `{other}`")),
+                },
+                phase: parse_phase(tokens.next_word("scroll phase")?)?,
+                inverted: tokens.parse("inverted")?,
+            }),
+            other => return Err(format!("unknown mouse event `{other}`")),
+        }),
+        "ModifiersChanged" => {
+            EventKind::ModifiersChanged(parse_modifiers(tokens.next_word("modifiers")?)?)
+        }
+        "RawMouseMotion" => EventKind::RawMouseMotion {
+            dx: tokens.parse("dx")?,
+            dy: tokens.parse("dy")?,
+        },
+        "Touch" => EventKind::Touch(TouchEvent {
+            phase: parse_phase(tokens.next_word("touch phase")?)?,
+            x: tokens.parse("x")?,
+            y: tokens.parse("y")?,
+        }),
+        "Redraw" => EventKind::Redraw,
+        "FocusIn" => EventKind::FocusIn,
+        "FocusOut" => EventKind::FocusOut,
+        "User" => EventKind::User(tokens.parse("payload")?),
+        "Timer" => EventKind::Timer(TimerId(tokens.parse("timer")?)),
+        #[cfg(unix)]
+        "FdReady" => {
+            let fd = tokens.parse("file descriptor")?;
+            let interest = match tokens.next_word("interest")? {
+                "r" => Interest::READABLE,
+                "w" => Interest::WRITABLE,
+                "rw" => Interest::READABLE | Interest::WRITABLE,
+                other => return Err(format!("invalid interest `{other}`")),
+            };
+            EventKind::FdReady(fd, interest)
+        }
+        other => return Err(format!("unknown event kind `{other}`")),
+    };
+
+    match tokens.next_token() {
+        Ok(Some(token)) => Err(format!("unexpected `{token}` at the end of the line")),
+        Ok(None) => Ok(Event { time, window, kind }),
+        Err(error) => Err(error),
+    }
+}
+
+fn format_button(button: MouseButton) -> String {
+    match button {
+        MouseButton::Other(button) => format!("Other:{button}"),
+        button => format!("{button:?}"),
+    }
+}
+
+fn parse_button(button: &str) -> Result<MouseButton, String> {
+    Ok(match button {
+        "Left" => MouseButton::Left,
+        "Right" => MouseButton::Right,
+        "Middle" => MouseButton::Middle,
+        "Back" => MouseButton::Back,
+        "Forward" => MouseButton::Forward,
+        _ => match button.strip_prefix("Other:") {
+            Some(button) => MouseButton::Other(parse_word(button, "mouse button")?),
+            None => return Err(format!("unknown mouse button `{button}`")),
+        },
+    })
+}
+
+fn parse_phase(phase: &str) -> Result<TouchPhase, String> {
+    Ok(match phase {
+        "Started" => TouchPhase::Started,
+        "Moved" => TouchPhase::Moved,
+        "Ended" => TouchPhase::Ended,
+        "Cancelled" => TouchPhase::Cancelled,
+        other => return Err(format!("unknown phase `{other}`")),
+    })
+}
+
+/// Modifiers are written as their names separated by `|`, such as `SHIFT|CTRL`, or as `-` when
+/// there are none.
+fn format_modifiers(modifiers: Modifiers) -> String {
+    if modifiers.is_empty() {
+        "-".to_owned()
+    } else {
+        modifiers.names().collect::<Vec<_>>().join("|")
+    }
+}
+
+fn parse_modifiers(modifiers: &str) -> Result<Modifiers, String> {
+    if modifiers == "-" {
+        return Ok(Modifiers::empty());
+    }
+
+    modifiers
+        .split('|')
+        .try_fold(Modifiers::empty(), |modifiers, name| {
+            Modifiers::from_name(name)
+                .map(|flag| modifiers | flag)
+                .ok_or_else(|| format!("unknown modifier `{name}`"))
+        })
+}
+
+fn quote(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Paths that aren't valid UTF-8 are recorded lossily.
+fn quote_path(path: &std::path::Path) -> String {
+    quote(&path.to_string_lossy())
+}
+
+fn parse_word<T: FromStr>(word: &str, what: &str) -> Result<T, String> {
+    word.parse().map_err(|_| format!("invalid {what} `{word}`"))
+}
+
+/// Splits a line into words and quoted strings.
+struct Tokens<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Tokens<'a> {
+    fn new(line: &'a str) -> Self {
+        Self { rest: line }
+    }
+
+    /// Returns the next word or string, or `None` at the end of the line.
+    fn next_token(&mut self) -> Result<Option<String>, String> {
+        self.rest = self.rest.trim_start();
+        if self.rest.is_empty() {
+            Ok(None)
+        } else if self.rest.starts_with('"') {
+            self.next_string().map(Some)
+        } else {
+            self.next_word("token").map(|word| Some(word.to_owned()))
+        }
+    }
+
+    fn next_word(&mut self, what: &str) -> Result<&'a str, String> {
+        self.rest = self.rest.trim_start();
+        let end = self
+            .rest
+            .find(char::is_whitespace)
+            .unwrap_or(self.rest.len());
+        if end == 0 {
+            return Err(format!("missing {what}"));
+        }
+
+        let (word, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        Ok(word)
+    }
+
+    fn next_string(&mut self) -> Result<String, String> {
+        self.rest = self.rest.trim_start();
+        let mut chars = self.rest.char_indices();
+        if chars.next().map(|(_, c)| c) != Some('"') {
+            return Err("expected a quoted string".to_owned());
+        }
+
+        let mut string = String::new();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.rest = &self.rest[i + 1..];
+                    return Ok(string);
+                }
+                '\\' => string.push(match chars.next().map(|(_, c)| c) {
+                    Some('"') => '"',
+                    Some('\\') => '\\',
+                    Some('n') => '\n',
+                    Some('r') => '\r',
+                    Some('t') => '\t',
+                    Some(c) => return Err(format!("invalid escape `\\{c}`")),
+                    None => break,
+                }),
+                c => string.push(c),
+            }
+        }
+
+        Err("unterminated string".to_owned())
+    }
+
+    fn parse<T: FromStr>(&mut self, what: &str) -> Result<T, String> {
+        let word = self.next_word(what)?;
+        parse_word(word, what)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+    use crate::keycode::KeyCode;
+
+    /// The name of an event's variant, nested for keyboard and mouse events. The match is
+    /// exhaustive, so that new variants can't be left out of [`all_events`].
+    fn variant_name(kind: &EventKind) -> &'static str {
+        match kind {
+            EventKind::Resized(..) => "Resized",
+            EventKind::Moved(..) => "Moved",
+            EventKind::CloseRequested => "CloseRequested",
+            EventKind::Destroyed => "Destroyed",
+            EventKind::FileDropped(_) => "FileDropped",
+            EventKind::FileHovered(_) => "FileHovered",
+            EventKind::FileHoveredCancelled(_) => "FileHoveredCancelled",
+            EventKind::Keyboard(event) => match event {
+                KeyboardEvent::KeyPress(..) => "Keyboard KeyPress",
+                KeyboardEvent::KeyRelease(..) => "Keyboard KeyRelease",
+                KeyboardEvent::KeyRepeat(..) => "Keyboard KeyRepeat",
+                KeyboardEvent::ImeCommit(_) => "Keyboard ImeCommit",
+                KeyboardEvent::ImePreedit { .. } => "Keyboard ImePreedit",
+            },
+            EventKind::ModifiersChanged(_) => "ModifiersChanged",
+            EventKind::Mouse(event) => match event {
+                MouseEvent::ButtonPress(..) => "Mouse ButtonPress",
+                MouseEvent::ButtonRelease(..) => "Mouse ButtonRelease",
+                MouseEvent::CursorMove(..) => "Mouse CursorMove",
+                MouseEvent::CursorIn(..) => "Mouse CursorIn",
+                MouseEvent::CursorOut(..) => "Mouse CursorOut",
+                MouseEvent::Scroll(_) => "Mouse Scroll",
+            },
+            EventKind::RawMouseMotion { .. } => "RawMouseMotion",
+            EventKind::Touch(_) => "Touch",
+            EventKind::Redraw => "Redraw",
+            EventKind::FocusIn => "FocusIn",
+            EventKind::FocusOut => "FocusOut",
+            EventKind::User(_) => "User",
+            EventKind::Timer(_) => "Timer",
+            #[cfg(unix)]
+            EventKind::FdReady(..) => "FdReady",
+        }
+    }
+
+    fn all_events() -> Vec<Event> {
+        let window = WindowHandle(4194307);
+        let modifiers = Modifiers::SHIFT | Modifiers::CTRL;
+        let kinds = vec![
+            EventKind::Resized(800, 600),
+            EventKind::Moved(-20, 35),
+            EventKind::CloseRequested,
+            EventKind::Destroyed,
+            EventKind::FileDropped("/tmp/a file.txt".into()),
+            EventKind::FileHovered("C:\\Users\\\"quoted\"".into()),
+            EventKind::FileHoveredCancelled("".into()),
+            EventKind::Keyboard(KeyboardEvent::KeyPress(KeyCode::A, ScanCode(30), modifiers)),
+            EventKind::Keyboard(KeyboardEvent::KeyRelease(
+                KeyCode::LShift,
+                ScanCode(42),
+                Modifiers::empty(),
+            )),
+            EventKind::Keyboard(KeyboardEvent::KeyRepeat(
+                KeyCode::Unidentified(0x1008ff99),
+                ScanCode(250),
+                Modifiers::NUM_LOCK,
+            )),
+            EventKind::Keyboard(KeyboardEvent::ImeCommit(
+                "tab\there\nline \\ \"q\"\r".into(),
+            )),
+            EventKind::Keyboard(KeyboardEvent::ImePreedit {
+                text: "にほ".into(),
+                cursor_range: Some((3, 6)),
+            }),
+            EventKind::Keyboard(KeyboardEvent::ImePreedit {
+                text: String::new(),
+                cursor_range: None,
+            }),
+            EventKind::ModifiersChanged(Modifiers::ALT | Modifiers::SUPER | Modifiers::CAPS_LOCK),
+            EventKind::Mouse(MouseEvent::ButtonPress(
+                MouseButton::Left,
+                12.5,
+                34.0,
+                modifiers,
+            )),
+            EventKind::Mouse(MouseEvent::ButtonRelease(
+                MouseButton::Other(9),
+                -0.1,
+                1e-7,
+                Modifiers::empty(),
+            )),
+            EventKind::Mouse(MouseEvent::CursorMove(1.0 / 3.0, 2.0)),
+            EventKind::Mouse(MouseEvent::CursorIn(0.0, 0.0)),
+            EventKind::Mouse(MouseEvent::CursorOut(-1.5, 600.25)),
+            EventKind::Mouse(MouseEvent::Scroll(ScrollEvent {
+                delta: ScrollDelta::Pixels(0.0, -12.5),
+                phase: TouchPhase::Moved,
+                inverted: false,
+            })),
+            EventKind::Mouse(MouseEvent::Scroll(ScrollEvent {
+                delta: ScrollDelta::Lines(1.0, 0.0),
+                phase: TouchPhase::Ended,
+                inverted: true,
+            })),
+            EventKind::RawMouseMotion { dx: 3.75, dy: -2.0 },
+            EventKind::Touch(TouchEvent {
+                phase: TouchPhase::Cancelled,
+                x: 10.0,
+                y: 20.5,
+            }),
+            EventKind::Redraw,
+            EventKind::FocusIn,
+            EventKind::FocusOut,
+            EventKind::User(u64::MAX),
+            EventKind::Timer(TimerId(7)),
+            #[cfg(unix)]
+            EventKind::FdReady(5, Interest::READABLE),
+            #[cfg(unix)]
+            EventKind::FdReady(6, Interest::READABLE | Interest::WRITABLE),
+        ];
+
+        kinds
+            .into_iter()
+            .enumerate()
+            .map(|(i, kind)| Event {
+                time: Duration::from_nanos(1_250_000_000 + i as u64),
+                window: if i % 2 == 0 {
+                    window
+                } else {
+                    WindowHandle::NONE
+                },
+                kind,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn samples_cover_every_variant() {
+        let covered = all_events()
+            .iter()
+            .map(|event| variant_name(&event.kind))
+            .collect::<BTreeSet<_>>();
+        // One for each name in `variant_name`
+        let expected = if cfg!(unix) { 27 } else { 26 };
+        assert_eq!(covered.len(), expected, "covered: {covered:?}");
+    }
+
+    #[test]
+    fn events_round_trip() {
+        for event in all_events() {
+            let line = format_event(&event);
+            let parsed = parse_event(&line).unwrap_or_else(|error| panic!("{line}: {error}"));
+            assert_eq!(format!("{parsed:?}"), format!("{event:?}"), "{line}");
+        }
+    }
+
+    #[test]
+    fn recording_replays() {
+        let events = all_events();
+        let mut recorder = Recorder::new(Vec::new()).unwrap();
+        for event in &events {
+            recorder.record(event);
+        }
+        let trace = recorder.finish().unwrap();
+
+        let replayed = Replay::new(trace.as_slice())
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(format!("{replayed:?}"), format!("{events:?}"));
+    }
+
+    #[test]
+    fn replay_skips_comments_and_blank_lines() {
+        let trace = "lokinit-trace 1\n# a comment\n\n  \n0 none Redraw\n";
+        let events = Replay::new(trace.as_bytes())
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0].kind, EventKind::Redraw));
+    }
+
+    #[test]
+    fn rejects_missing_header() {
+        let error = Replay::new("0 none Redraw\n".as_bytes()).err().unwrap();
+        assert!(matches!(error, ReplayError::Parse { line: 1, .. }));
+        assert!(Replay::new("".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn reports_malformed_line_numbers() {
+        let trace = "lokinit-trace 1\n0 none Redraw\n# comment\n0 none Bogus\n0 none FocusIn\n";
+        let mut replay = Replay::new(trace.as_bytes()).unwrap();
+        assert!(replay.next().unwrap().is_ok());
+        match replay.next().unwrap() {
+            Err(ReplayError::Parse { line, message }) => {
+                assert_eq!(line, 4);
+                assert_eq!(message, "unknown event kind `Bogus`");
+            }
+            other => panic!("expected a parse error, got {other:?}"),
+        }
+        assert!(replay.next().unwrap().is_ok());
+        assert!(replay.next().is_none());
+    }
+
+    #[test]
+    fn rejects_malformed_lines() {
+        for line in [
+            "",
+            "soon none Redraw",
+            "0",
+            "0 window Redraw",
+            "0 none",
+            "0 none Resized 800",
+            "0 none Resized 800 tall",
+            "0 none Redraw extra",
+            "0 none Keyboard KeyPress NotAKey 30 -",
+            "0 none Keyboard KeyPress A 30 HYPER",
+            "0 none Keyboard Shout",
+            "0 none Keyboard ImeCommit \"unterminated",
+            "0 none Keyboard ImeCommit \"bad \\q escape\"",
+            "0 none Keyboard ImeCommit unquoted",
+            "0 none Keyboard ImePreedit \"x\" 1",
+            "0 none Mouse ButtonPress Other:big 1 2 -",
+            "0 none Mouse Scroll Pages 1 2 Moved false",
+            "0 none Mouse Scroll Lines 1 2 Sideways false",
+            "0 none Mouse Scroll Lines 1 2 Moved maybe",
+            "0 none Touch Started 1",
+            "0 none FdReady 3 x",
+        ] {
+            assert!(parse_event(line).is_err(), "`{line}` was accepted");
+        }
+    }
+}
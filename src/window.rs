@@ -1,4 +1,6 @@
 use crate::{
+    cursor::{CursorIcon, CustomCursor},
+    error::LokinitError,
     lok::{self, LokinitBackend, MonitorId},
     native::WindowId,
 };
@@ -27,6 +29,26 @@ impl WindowSize {
     }
 }
 
+/// A rectangle inside a window, relative to its top-left corner.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct WindowRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl WindowRect {
+    pub fn new(x: i32, y: i32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum WindowBorder {
     Top = 0,
@@ -48,6 +70,21 @@ pub enum ScreenMode {
     ExclusiveFullscreen,
 }
 
+/// How a window holds on to the cursor, set with [`WindowHandle::set_cursor_grab`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum CursorGrab {
+    /// The cursor moves freely.
+    #[default]
+    None,
+    /// The cursor can't leave the window.
+    Confined,
+    /// The cursor can't leave the given area of the window.
+    ConfinedTo(WindowRect),
+    /// The cursor stays where it is. Mouse motion can still be read from
+    /// [`RawMouseMotion`](crate::event::EventKind::RawMouseMotion) events.
+    Locked,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct WindowBuilder {
     pub title: String,
@@ -147,9 +184,70 @@ impl Default for WindowBuilder {
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct WindowHandle(pub(crate) WindowId);
 impl WindowHandle {
-    pub fn close(self) {
-        lok::with(|backend| {
-            backend.close_window(self);
-        });
+    /// A handle that doesn't refer to any window. Events that aren't tied to a specific window
+    /// are sent to it.
+    pub const NONE: Self = Self(WindowId::MAX);
+
+    pub fn close(self) -> Result<(), LokinitError> {
+        lok::with(|backend| backend.close_window(self))
+    }
+
+    /// Tells the input method where the text cursor is, usually the caret or the current
+    /// selection, so that it can show its candidate window next to it rather than in a corner.
+    pub fn set_ime_cursor_area(self, area: WindowRect) -> Result<(), LokinitError> {
+        lok::with(|backend| backend.set_ime_cursor_area(self, area))
+    }
+
+    /// Enables or disables the input method for this window. It's enabled by default.
+    ///
+    /// While it's disabled, keys are never composed into [`ImePreedit`] events, but typed text
+    /// still arrives as [`ImeCommit`] events. Disable it when the window isn't editing text, so
+    /// that keys reach it directly.
+    ///
+    /// [`ImePreedit`]: crate::event::KeyboardEvent::ImePreedit
+    /// [`ImeCommit`]: crate::event::KeyboardEvent::ImeCommit
+    pub fn set_ime_allowed(self, allowed: bool) -> Result<(), LokinitError> {
+        lok::with(|backend| backend.set_ime_allowed(self, allowed))
+    }
+
+    /// Enables or disables [`RawMouseMotion`] events for this window. They're disabled by
+    /// default, and only sent while the window is focused.
+    ///
+    /// [`RawMouseMotion`]: crate::event::EventKind::RawMouseMotion
+    pub fn set_raw_mouse_motion(self, enabled: bool) -> Result<(), LokinitError> {
+        lok::with(|backend| backend.set_raw_mouse_motion(self, enabled))
+    }
+
+    /// Confines or locks the cursor in this window, or releases it.
+    ///
+    /// The grab only holds while the window is focused: it's released when the window loses the
+    /// focus, and taken again when it gets it back.
+    pub fn set_cursor_grab(self, grab: CursorGrab) -> Result<(), LokinitError> {
+        lok::with(|backend| backend.set_cursor_grab(self, grab))
+    }
+
+    /// Sets the cursor shown while the cursor is over this window.
+    pub fn set_cursor_icon(self, icon: CursorIcon) -> Result<(), LokinitError> {
+        lok::with(|backend| backend.set_cursor_icon(self, icon))
+    }
+
+    /// Hides the cursor while it's over this window, or shows it again.
+    pub fn set_cursor_visible(self, visible: bool) -> Result<(), LokinitError> {
+        lok::with(|backend| backend.set_cursor_visible(self, visible))
+    }
+
+    /// Moves the cursor to a position in this window, in logical pixels.
+    ///
+    /// Wayland doesn't let apps move the cursor, so this only works there while the cursor is
+    /// [locked](CursorGrab::Locked) in the window: the position is then where the cursor shows up
+    /// once it gets unlocked.
+    pub fn set_cursor_position(self, x: f64, y: f64) -> Result<(), LokinitError> {
+        lok::with(|backend| backend.set_cursor_position(self, x, y))
+    }
+
+    /// Shows a custom cursor while the cursor is over this window, instead of its cursor icon.
+    /// Setting a cursor icon afterwards replaces it.
+    pub fn set_custom_cursor(self, cursor: CustomCursor) -> Result<(), LokinitError> {
+        lok::with(|backend| backend.set_custom_cursor(self, cursor))
     }
 }